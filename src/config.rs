@@ -1,6 +1,11 @@
 use serde::Deserialize;
 use std::path::Path;
 
+use crate::{
+    db::market_data::MarketDataFormat, modules::resolution_watch::ResolutionAction,
+    notifier::Severity, strategy::execution::ExecutionMode, utils::logger::LogFormat,
+};
+
 const CONFIG_FILE_PATH: &str = "data/config.toml";
 
 #[derive(Deserialize, Clone, Debug)]
@@ -9,18 +14,76 @@ pub struct Config {
     pub registration_sleep_range: [u64; 2],
     pub mobile_proxies: bool,
     pub swap_ip_link: String,
-    pub polygon_rpc_url: String,
+    pub polygon_rpc_urls: Vec<String>,
+    pub rpc_max_head_lag_blocks: u64,
+    pub rpc_health_check_interval_secs: u64,
+    pub rpc_balance_quorum: usize,
     pub ignore_existing_balance: bool,
     pub usdc_amount_deposit_range: [f64; 2],
     pub deposit_sleep_range: [u64; 2],
+    pub convert_native_usdc_on_deposit: bool,
+    pub dex_router_address: String,
+    pub swap_slippage_percent: f64,
+    pub max_fee_per_gas_gwei: f64,
+    pub max_priority_fee_per_gas_gwei: f64,
+    pub receipt_timeout_secs: u64,
+    pub max_gas_replacements: u32,
+    pub gas_bump_percent: u64,
+    pub cancel_stuck_transactions: bool,
     pub price_difference_threshold: f64,
     pub spread_threshold: f64,
     pub min_event_volume: f64,
+    pub allow_multi_market_events: bool,
     pub bet_balance_percentage: [u64; 2],
     pub sell_delay_range: [u64; 2],
     pub batch_delay_range: [u64; 2],
     pub cycle_count: u64,
     pub withdraw_delay_range: [u64; 2],
+    pub withdraw_max_retries: u32,
+    pub book_imbalance_range_cents: f64,
+    pub relayer_stuck_after_minutes: i64,
+    pub recorder_formats: Vec<MarketDataFormat>,
+    pub recorder_poll_interval_secs: u64,
+    pub recorder_trades_poll_interval_secs: u64,
+    pub recorder_flush_interval_secs: u64,
+    pub strategy: String,
+    pub strategy_mode: ExecutionMode,
+    pub strategy_poll_interval_secs: u64,
+    pub strategy_timer_interval_secs: u64,
+    pub backtest_initial_cash: f64,
+    pub backtest_fee_rate_bps: f64,
+    pub backtest_tick_size: String,
+    pub log_format: LogFormat,
+    pub log_level: String,
+    pub metrics_listen_address: String,
+    pub notify_large_fill_usdc: f64,
+    pub notify_webhook_url: String,
+    pub notify_webhook_min_severity: Severity,
+    pub notify_telegram_api_url: String,
+    pub notify_telegram_bot_token: String,
+    pub notify_telegram_chat_id: String,
+    pub notify_telegram_min_severity: Severity,
+    pub notify_smtp_host: String,
+    pub notify_smtp_port: u16,
+    pub notify_smtp_username: String,
+    pub notify_smtp_password: String,
+    pub notify_smtp_from: String,
+    pub notify_smtp_to: String,
+    pub notify_smtp_min_severity: Severity,
+    pub risk_max_order_usdc: f64,
+    pub risk_max_market_exposure_usdc: f64,
+    pub risk_max_wallet_exposure_usdc: f64,
+    pub risk_max_open_orders: usize,
+    pub risk_daily_loss_limit_usdc: f64,
+    pub risk_allowed_markets: Vec<String>,
+    pub risk_blocked_markets: Vec<String>,
+    pub risk_allowed_tags: Vec<String>,
+    pub risk_blocked_tags: Vec<String>,
+    pub risk_kill_switch_file: String,
+    pub resolution_exit_hours: f64,
+    pub resolution_action: ResolutionAction,
+    pub resolution_check_interval_secs: u64,
+    pub resolution_auto_redeem: bool,
 }
 
 impl Config {
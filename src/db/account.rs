@@ -11,6 +11,8 @@ use crate::{
     polymarket::api::clob::schemas::ClobApiKeyResponseBody, utils::poly::get_proxy_wallet_address,
 };
 
+use super::allowlist::parse_checksummed_address;
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Account {
     private_key: String,
@@ -125,8 +127,13 @@ impl Account {
         Address::from_str(&self.proxy_address).unwrap()
     }
 
-    pub fn get_recipient_address(&self) -> Address {
-        Address::from_str(&self.recipient_address).unwrap()
+    // Only EIP-55 checksummed recipients are withdrawn to, a typo in a lowercase address goes unnoticed
+    pub fn get_recipient_address(&self) -> eyre::Result<Address> {
+        parse_checksummed_address(&self.recipient_address)
+    }
+
+    pub fn recipient_is_eoa(&self) -> bool {
+        self.recipient_address.eq_ignore_ascii_case(&self.address)
     }
 
     pub fn update_credentials(&self, response: ClobApiKeyResponseBody) {
@@ -135,6 +142,15 @@ impl Account {
         *self.passphrase.write().unwrap() = Some(response.passphrase);
     }
 
+    // Every log event emitted inside carries the signer and proxy wallet of the account
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "account",
+            account = %self.address,
+            proxy_wallet = %self.proxy_address
+        )
+    }
+
     pub fn get_api_creds(&self) -> Option<ApiCreds> {
         let api_key_guard = self.api_key.read().unwrap();
         let passphrase_guard = self.passphrase.read().unwrap();
@@ -0,0 +1,62 @@
+use alloy::primitives::Address;
+
+use crate::utils::files::read_file_lines;
+
+use super::constants::WITHDRAW_ALLOWLIST_FILE_PATH;
+
+#[derive(Debug, Clone)]
+pub struct AllowListEntry {
+    pub address: Address,
+    pub label: String,
+}
+
+// Destinations withdrawals are allowed to go to, one `<checksummed address> <label>` per line
+#[derive(Debug, Default)]
+pub struct AllowList(pub Vec<AllowListEntry>);
+
+impl AllowList {
+    pub async fn read() -> eyre::Result<Self> {
+        let lines = read_file_lines(WITHDRAW_ALLOWLIST_FILE_PATH)
+            .await
+            .unwrap_or_default();
+
+        let mut entries = vec![];
+
+        for (i, line) in lines.iter().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (address, label) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                eyre::eyre!(
+                    "{WITHDRAW_ALLOWLIST_FILE_PATH}:{} is missing a label",
+                    i + 1
+                )
+            })?;
+
+            let address = parse_checksummed_address(address)
+                .map_err(|e| eyre::eyre!("{WITHDRAW_ALLOWLIST_FILE_PATH}:{}: {e}", i + 1))?;
+
+            entries.push(AllowListEntry {
+                address,
+                label: label.trim().to_string(),
+            });
+        }
+
+        Ok(Self(entries))
+    }
+
+    pub fn label_of(&self, address: &Address) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|entry| entry.address == *address)
+            .map(|entry| entry.label.as_str())
+    }
+}
+
+pub fn parse_checksummed_address(address: &str) -> eyre::Result<Address> {
+    Address::parse_checksummed(address, None)
+        .map_err(|e| eyre::eyre!("`{address}` is not a valid EIP-55 checksummed address: {e}"))
+}
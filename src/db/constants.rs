@@ -2,3 +2,7 @@ pub const PROXIES_FILE_PATH: &str = "data/proxies.txt";
 pub const PRIVATE_KEYS_FILE_PATH: &str = "data/private_keys.txt";
 pub const DB_FILE_PATH: &str = "data/db.json";
 pub const RECIPIENTS_FILE_PATH: &str = "data/recipients.txt";
+pub const RELAYER_TRANSACTIONS_FILE_PATH: &str = "data/relayer_transactions.json";
+pub const WITHDRAW_ALLOWLIST_FILE_PATH: &str = "data/withdraw_allowlist.txt";
+pub const MARKET_DATA_FOLDER_PATH: &str = "data/market_data";
+pub const BACKTESTS_FOLDER_PATH: &str = "data/backtests";
@@ -1,4 +1,4 @@
-use std::fs::File;
+use std::{collections::HashMap, fs::File};
 
 use rand::{
     seq::{IteratorRandom, SliceRandom},
@@ -10,6 +10,7 @@ use crate::utils::files::read_file_lines;
 
 use super::{
     account::Account,
+    allowlist::parse_checksummed_address,
     constants::{DB_FILE_PATH, PRIVATE_KEYS_FILE_PATH, PROXIES_FILE_PATH, RECIPIENTS_FILE_PATH},
 };
 
@@ -43,7 +44,24 @@ impl Database {
             };
 
             let proxy = proxies.get(i).cloned();
-            let recipient = recipients.get(i).cloned();
+            let recipient = recipients.get(i).filter(|r| !r.trim().is_empty()).cloned();
+
+            // Withdrawals enforce EIP-55 themselves, other commands keep working with the file as is
+            match &recipient {
+                Some(recipient) => {
+                    if let Err(e) = parse_checksummed_address(recipient.trim()) {
+                        tracing::warn!(
+                            "Recipient at position {i}: {e}, withdrawals to it are blocked until it is fixed"
+                        );
+                    }
+                }
+                None => tracing::warn!(
+                    "Missing recipient at position {}, withdrawals will default to the account's EOA",
+                    i
+                ),
+            }
+
+            let recipient = recipient.map(|r| r.trim().to_string());
 
             let account = Account::new(&private_key, proxy, recipient);
             data.push(account);
@@ -55,6 +73,19 @@ impl Database {
         Ok(Self(data))
     }
 
+    // Recipient currently listed next to each private key, used to detect drift from the db
+    pub async fn read_listed_recipients() -> eyre::Result<HashMap<String, String>> {
+        let private_keys = read_file_lines(PRIVATE_KEYS_FILE_PATH).await?;
+        let recipients = read_file_lines(RECIPIENTS_FILE_PATH).await?;
+
+        Ok(private_keys
+            .into_iter()
+            .zip(recipients)
+            .map(|(private_key, recipient)| (private_key, recipient.trim().to_string()))
+            .filter(|(_, recipient)| !recipient.is_empty())
+            .collect())
+    }
+
     pub fn get_random_account_with_filter<F>(&mut self, filter: F) -> Option<&mut Account>
     where
         F: Fn(&Account) -> bool,
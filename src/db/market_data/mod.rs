@@ -0,0 +1,132 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub mod reader;
+pub mod writer;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketDataFormat {
+    Csv,
+    Parquet,
+}
+
+impl MarketDataFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv.gz",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ColumnType {
+    Long,
+    Double,
+    OptionalDouble,
+    Text,
+}
+
+// Rows are stored under `<kind>/token=<token id>/date=<yyyy-mm-dd>/hour=<hh>/`, columns in `COLUMNS` order
+pub trait MarketDataRecord: Serialize + DeserializeOwned {
+    const KIND: &'static str;
+    const COLUMNS: &'static [(&'static str, ColumnType)];
+
+    fn token_id(&self) -> &str;
+    fn timestamp_ms(&self) -> i64;
+}
+
+// One price level of a book snapshot, `level` 0 is the first level the CLOB returned for that side
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BookLevelRow {
+    pub timestamp_ms: i64,
+    pub token_id: String,
+    pub market: String,
+    pub hash: String,
+    pub side: String,
+    pub level: i64,
+    pub price: f64,
+    pub size: f64,
+}
+
+impl MarketDataRecord for BookLevelRow {
+    const KIND: &'static str = "books";
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("timestamp_ms", ColumnType::Long),
+        ("token_id", ColumnType::Text),
+        ("market", ColumnType::Text),
+        ("hash", ColumnType::Text),
+        ("side", ColumnType::Text),
+        ("level", ColumnType::Long),
+        ("price", ColumnType::Double),
+        ("size", ColumnType::Double),
+    ];
+
+    fn token_id(&self) -> &str {
+        &self.token_id
+    }
+
+    fn timestamp_ms(&self) -> i64 {
+        self.timestamp_ms
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MidpointRow {
+    pub timestamp_ms: i64,
+    pub token_id: String,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub midpoint: Option<f64>,
+    pub spread: Option<f64>,
+}
+
+impl MarketDataRecord for MidpointRow {
+    const KIND: &'static str = "midpoints";
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("timestamp_ms", ColumnType::Long),
+        ("token_id", ColumnType::Text),
+        ("best_bid", ColumnType::OptionalDouble),
+        ("best_ask", ColumnType::OptionalDouble),
+        ("midpoint", ColumnType::OptionalDouble),
+        ("spread", ColumnType::OptionalDouble),
+    ];
+
+    fn token_id(&self) -> &str {
+        &self.token_id
+    }
+
+    fn timestamp_ms(&self) -> i64 {
+        self.timestamp_ms
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TradeRow {
+    pub timestamp_ms: i64,
+    pub token_id: String,
+    pub side: String,
+    pub price: f64,
+    pub size: f64,
+    pub transaction_hash: String,
+}
+
+impl MarketDataRecord for TradeRow {
+    const KIND: &'static str = "trades";
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("timestamp_ms", ColumnType::Long),
+        ("token_id", ColumnType::Text),
+        ("side", ColumnType::Text),
+        ("price", ColumnType::Double),
+        ("size", ColumnType::Double),
+        ("transaction_hash", ColumnType::Text),
+    ];
+
+    fn token_id(&self) -> &str {
+        &self.token_id
+    }
+
+    fn timestamp_ms(&self) -> i64 {
+        self.timestamp_ms
+    }
+}
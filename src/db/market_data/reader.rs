@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
+use itertools::Itertools;
+use parquet::file::reader::SerializedFileReader;
+
+use crate::polymarket::api::clob::schemas::{Order, OrderBookData};
+
+use super::{BookLevelRow, MarketDataFormat, MarketDataRecord};
+
+// Token ids with recorded rows of the given kind
+pub fn recorded_tokens<R: MarketDataRecord>(root: impl AsRef<Path>) -> eyre::Result<Vec<String>> {
+    Ok(partition_values(&root.as_ref().join(R::KIND), "token")?
+        .into_iter()
+        .map(|(token_id, _)| token_id)
+        .sorted()
+        .collect())
+}
+
+// Rows of one token between two dates (both inclusive), ordered by timestamp.
+// When a flush was written in both formats only the Parquet copy is read.
+pub fn read_records<R: MarketDataRecord>(
+    root: impl AsRef<Path>,
+    token_id: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> eyre::Result<Vec<R>> {
+    let token_dir = root
+        .as_ref()
+        .join(R::KIND)
+        .join(format!("token={token_id}"));
+
+    if !token_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut rows = vec![];
+
+    for (date, date_dir) in partition_values(&token_dir, "date")? {
+        let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")?;
+
+        if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+            continue;
+        }
+
+        for (_, hour_dir) in partition_values(&date_dir, "hour")? {
+            for (path, format) in part_files(&hour_dir)? {
+                match format {
+                    MarketDataFormat::Csv => rows.extend(read_csv::<R>(&path)?),
+                    MarketDataFormat::Parquet => rows.extend(read_parquet::<R>(&path)?),
+                }
+            }
+        }
+    }
+
+    rows.sort_by_key(|row| row.timestamp_ms());
+
+    Ok(rows)
+}
+
+// Rebuilds the recorded book snapshots of a token, keyed by the millisecond they were taken at
+pub fn read_order_books(
+    root: impl AsRef<Path>,
+    token_id: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> eyre::Result<Vec<(i64, OrderBookData)>> {
+    let rows = read_records::<BookLevelRow>(root, token_id, from, to)?;
+
+    let books = rows
+        .into_iter()
+        .chunk_by(|row| row.timestamp_ms)
+        .into_iter()
+        .map(|(timestamp_ms, levels)| {
+            let levels = levels.sorted_by_key(|row| row.level).collect_vec();
+            let first = &levels[0];
+
+            let side = |side: &str| {
+                levels
+                    .iter()
+                    .filter(|row| row.side == side)
+                    .map(|row| Order {
+                        price: row.price,
+                        size: row.size,
+                    })
+                    .collect_vec()
+            };
+
+            (
+                timestamp_ms,
+                OrderBookData {
+                    market: first.market.clone(),
+                    asset_id: first.token_id.clone(),
+                    timestamp: timestamp_ms.to_string(),
+                    hash: first.hash.clone(),
+                    bids: side("bid"),
+                    asks: side("ask"),
+                },
+            )
+        })
+        .collect();
+
+    Ok(books)
+}
+
+// Subdirectories named `<key>=<value>`, sorted by value
+fn partition_values(dir: &Path, key: &str) -> eyre::Result<Vec<(String, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let prefix = format!("{key}=");
+    let mut values = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        let value = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .map(|value| value.to_string());
+
+        if let (Some(value), true) = (value, path.is_dir()) {
+            values.push((value, path));
+        }
+    }
+
+    values.sort();
+
+    Ok(values)
+}
+
+fn part_files(dir: &Path) -> eyre::Result<Vec<(PathBuf, MarketDataFormat)>> {
+    let mut parts = BTreeMap::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        for format in [MarketDataFormat::Parquet, MarketDataFormat::Csv] {
+            if let Some(stem) = name.strip_suffix(&format!(".{}", format.extension())) {
+                let preferred = parts
+                    .get(stem)
+                    .is_none_or(|(_, existing)| *existing == MarketDataFormat::Csv);
+
+                if preferred {
+                    parts.insert(stem.to_string(), (path.clone(), format));
+                }
+            }
+        }
+    }
+
+    Ok(parts.into_values().collect())
+}
+
+fn read_csv<R: MarketDataRecord>(path: &Path) -> eyre::Result<Vec<R>> {
+    let decoder = GzDecoder::new(BufReader::new(File::open(path)?));
+
+    csv::Reader::from_reader(decoder)
+        .deserialize::<R>()
+        .map(|row| row.map_err(|e| eyre::eyre!("{}: {e}", path.display())))
+        .collect()
+}
+
+fn read_parquet<R: MarketDataRecord>(path: &Path) -> eyre::Result<Vec<R>> {
+    SerializedFileReader::new(File::open(path)?)?
+        .into_iter()
+        .map(|row| Ok(serde_json::from_value::<R>(row?.to_json_value())?))
+        .collect()
+}
@@ -0,0 +1,476 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression as GzCompression};
+use itertools::Itertools;
+use parquet::{
+    basic::Compression,
+    data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+
+use crate::polymarket::api::clob::{analytics::BookAnalytics, schemas::OrderBookData};
+
+use super::{BookLevelRow, ColumnType, MarketDataFormat, MarketDataRecord, MidpointRow, TradeRow};
+
+const TMP_EXTENSION: &str = "tmp";
+
+// Buffers rows in memory and writes every flush as new part files, each renamed into place only
+// once complete, so an interrupted recorder never leaves a partial file behind
+pub struct PartitionedWriter {
+    root: PathBuf,
+    formats: Vec<MarketDataFormat>,
+    books: Vec<BookLevelRow>,
+    midpoints: Vec<MidpointRow>,
+    trades: Vec<TradeRow>,
+}
+
+impl PartitionedWriter {
+    pub fn new(root: impl AsRef<Path>, formats: &[MarketDataFormat]) -> eyre::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+
+        let removed = remove_tmp_files(&root)?;
+        if removed > 0 {
+            tracing::warn!("Removed {removed} unfinished market data files of a previous run");
+        }
+
+        Ok(Self {
+            root,
+            formats: formats.to_vec(),
+            books: vec![],
+            midpoints: vec![],
+            trades: vec![],
+        })
+    }
+
+    pub fn push_book(&mut self, timestamp_ms: i64, book: &OrderBookData) {
+        for (side, orders) in [("bid", &book.bids), ("ask", &book.asks)] {
+            for (level, order) in orders.iter().enumerate() {
+                self.books.push(BookLevelRow {
+                    timestamp_ms,
+                    token_id: book.asset_id.clone(),
+                    market: book.market.clone(),
+                    hash: book.hash.clone(),
+                    side: side.to_string(),
+                    level: level as i64,
+                    price: order.price,
+                    size: order.size,
+                });
+            }
+        }
+
+        let analytics = BookAnalytics::new(book.clone());
+
+        self.midpoints.push(MidpointRow {
+            timestamp_ms,
+            token_id: book.asset_id.clone(),
+            best_bid: analytics.best_bid().map(|order| order.price),
+            best_ask: analytics.best_ask().map(|order| order.price),
+            midpoint: analytics.midpoint(),
+            spread: analytics.spread(),
+        });
+    }
+
+    pub fn push_trade(&mut self, trade: TradeRow) {
+        self.trades.push(trade);
+    }
+
+    pub fn buffered_rows(&self) -> usize {
+        self.books.len() + self.midpoints.len() + self.trades.len()
+    }
+
+    // Returns the number of rows written. Every part file is staged before any is renamed into
+    // place, and rows are dropped from the buffers per partition once its files landed, so a
+    // failed flush never has its rows written twice by the next one
+    pub fn flush(&mut self) -> eyre::Result<usize> {
+        let flushed_at = Utc::now().timestamp_millis();
+        let mut staged = vec![];
+
+        let result = stage_partitioned(
+            &self.root,
+            &self.formats,
+            &self.books,
+            flushed_at,
+            &mut staged,
+        )
+        .and_then(|_| {
+            stage_partitioned(
+                &self.root,
+                &self.formats,
+                &self.midpoints,
+                flushed_at,
+                &mut staged,
+            )
+        })
+        .and_then(|_| {
+            stage_partitioned(
+                &self.root,
+                &self.formats,
+                &self.trades,
+                flushed_at,
+                &mut staged,
+            )
+        });
+
+        if let Err(e) = result {
+            remove_staged(&staged);
+            return Err(e);
+        }
+
+        let mut landed = HashSet::new();
+        let mut written = 0;
+        let mut result = Ok(());
+
+        for (index, partition) in staged.iter().enumerate() {
+            let renamed = partition
+                .files
+                .iter()
+                .try_for_each(|(tmp_path, path)| fs::rename(tmp_path, path));
+
+            if let Err(e) = renamed {
+                remove_staged(&staged[index..]);
+                result = Err(e.into());
+                break;
+            }
+
+            landed.insert(partition.dir.clone());
+            written += partition.rows;
+        }
+
+        retain_unwritten(&self.root, &mut self.books, &landed);
+        retain_unwritten(&self.root, &mut self.midpoints, &landed);
+        retain_unwritten(&self.root, &mut self.trades, &landed);
+
+        result.map(|_| written)
+    }
+}
+
+// Part files of one partition written under their tmp names, waiting to be renamed into place
+struct StagedPartition {
+    dir: PathBuf,
+    files: Vec<(PathBuf, PathBuf)>,
+    rows: usize,
+}
+
+fn remove_staged(staged: &[StagedPartition]) {
+    for (tmp_path, _) in staged.iter().flat_map(|partition| &partition.files) {
+        let _ = fs::remove_file(tmp_path);
+    }
+}
+
+fn retain_unwritten<R: MarketDataRecord>(
+    root: &Path,
+    rows: &mut Vec<R>,
+    landed: &HashSet<PathBuf>,
+) {
+    rows.retain(|row| {
+        !landed.contains(&partition_dir::<R>(
+            root,
+            row.token_id(),
+            row.timestamp_ms(),
+        ))
+    });
+}
+
+pub fn partition_dir<R: MarketDataRecord>(
+    root: &Path,
+    token_id: &str,
+    timestamp_ms: i64,
+) -> PathBuf {
+    let datetime = DateTime::from_timestamp_millis(timestamp_ms).unwrap_or_default();
+
+    root.join(R::KIND)
+        .join(format!("token={token_id}"))
+        .join(format!("date={}", datetime.format("%Y-%m-%d")))
+        .join(format!("hour={}", datetime.format("%H")))
+}
+
+fn stage_partitioned<R: MarketDataRecord>(
+    root: &Path,
+    formats: &[MarketDataFormat],
+    rows: &[R],
+    flushed_at: i64,
+    staged: &mut Vec<StagedPartition>,
+) -> eyre::Result<()> {
+    // Hourly partitions rotate the files, rows of a flush spanning two hours land in both
+    let partitions = rows
+        .iter()
+        .into_group_map_by(|row| partition_dir::<R>(root, row.token_id(), row.timestamp_ms()));
+
+    for (dir, rows) in partitions {
+        fs::create_dir_all(&dir)?;
+
+        let mut partition = StagedPartition {
+            dir: dir.clone(),
+            files: vec![],
+            rows: rows.len(),
+        };
+
+        for format in formats {
+            let file_name = format!("part-{flushed_at}.{}", format.extension());
+            let tmp_path = dir.join(format!("{file_name}.{TMP_EXTENSION}"));
+
+            // Pushed first so a failed write has its tmp file cleaned up too
+            partition
+                .files
+                .push((tmp_path.clone(), dir.join(&file_name)));
+
+            let result = match format {
+                MarketDataFormat::Csv => write_csv(&tmp_path, &rows),
+                MarketDataFormat::Parquet => write_parquet(&tmp_path, &rows),
+            };
+
+            if let Err(e) = result {
+                staged.push(partition);
+                return Err(e);
+            }
+        }
+
+        staged.push(partition);
+    }
+
+    Ok(())
+}
+
+fn write_csv<R: MarketDataRecord>(path: &Path, rows: &[&R]) -> eyre::Result<()> {
+    let encoder = GzEncoder::new(File::create(path)?, GzCompression::default());
+    let mut writer = csv::Writer::from_writer(encoder);
+
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| eyre::eyre!("Failed to flush {}: {e}", path.display()))?
+        .finish()?
+        .sync_all()?;
+
+    Ok(())
+}
+
+fn write_parquet<R: MarketDataRecord>(path: &Path, rows: &[&R]) -> eyre::Result<()> {
+    let fields = R::COLUMNS
+        .iter()
+        .map(|(name, column_type)| match column_type {
+            ColumnType::Long => format!("REQUIRED INT64 {name};"),
+            ColumnType::Double => format!("REQUIRED DOUBLE {name};"),
+            ColumnType::OptionalDouble => format!("OPTIONAL DOUBLE {name};"),
+            ColumnType::Text => format!("REQUIRED BINARY {name} (UTF8);"),
+        })
+        .join(" ");
+    let schema = Arc::new(parse_message_type(&format!(
+        "message {} {{ {fields} }}",
+        R::KIND
+    ))?);
+
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+
+    let values = rows
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut writer = SerializedFileWriter::new(File::create(path)?, schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+
+    for (name, column_type) in R::COLUMNS {
+        let column = values.iter().map(|value| &value[name]).collect_vec();
+        let mut column_writer = row_group
+            .next_column()?
+            .ok_or_else(|| eyre::eyre!("Parquet schema is missing column `{name}`"))?;
+
+        match column_type {
+            ColumnType::Long => {
+                let data = column
+                    .iter()
+                    .map(|v| v.as_i64().unwrap_or_default())
+                    .collect_vec();
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(&data, None, None)?;
+            }
+            ColumnType::Double => {
+                let data = column
+                    .iter()
+                    .map(|v| v.as_f64().unwrap_or_default())
+                    .collect_vec();
+                column_writer
+                    .typed::<DoubleType>()
+                    .write_batch(&data, None, None)?;
+            }
+            ColumnType::OptionalDouble => {
+                let data = column.iter().filter_map(|v| v.as_f64()).collect_vec();
+                let definition_levels = column
+                    .iter()
+                    .map(|v| i16::from(v.as_f64().is_some()))
+                    .collect_vec();
+                column_writer.typed::<DoubleType>().write_batch(
+                    &data,
+                    Some(&definition_levels),
+                    None,
+                )?;
+            }
+            ColumnType::Text => {
+                let data = column
+                    .iter()
+                    .map(|v| ByteArray::from(v.as_str().unwrap_or_default()))
+                    .collect_vec();
+                column_writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&data, None, None)?;
+            }
+        }
+
+        column_writer.close()?;
+    }
+
+    row_group.close()?;
+    writer.close()?;
+
+    Ok(())
+}
+
+fn remove_tmp_files(dir: &Path) -> eyre::Result<usize> {
+    let mut removed = 0;
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            removed += remove_tmp_files(&path)?;
+        } else if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::market_data::reader::{read_order_books, read_records},
+        strategy::test_book,
+    };
+
+    use super::*;
+
+    // 2026-10-18 12:00 UTC
+    const NOON_MS: i64 = 1_792_324_800_000;
+
+    fn root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("polymarket-eye-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    fn record(writer: &mut PartitionedWriter) {
+        writer.push_book(
+            NOON_MS,
+            &test_book(
+                "0xmarket",
+                "yes",
+                &[(0.48, 10.0)],
+                &[(0.52, 20.0), (0.55, 5.0)],
+            ),
+        );
+        // An hour later, in the next partition
+        writer.push_book(
+            NOON_MS + 3_600_000,
+            &test_book("0xmarket", "yes", &[(0.5, 7.0)], &[]),
+        );
+        writer.push_trade(TradeRow {
+            timestamp_ms: NOON_MS + 1,
+            token_id: "yes".to_string(),
+            side: "BUY".to_string(),
+            price: 0.52,
+            size: 3.0,
+            transaction_hash: "0xhash".to_string(),
+        });
+    }
+
+    fn round_trip(format: MarketDataFormat) {
+        let root = root(&format!("round-trip-{}", format.extension()));
+        let mut writer = PartitionedWriter::new(&root, &[format]).unwrap();
+
+        record(&mut writer);
+        assert_eq!(writer.flush().unwrap(), 7);
+        assert_eq!(writer.buffered_rows(), 0);
+        assert_eq!(writer.flush().unwrap(), 0);
+
+        let books = read_order_books(&root, "yes", None, None).unwrap();
+        let midpoints = read_records::<MidpointRow>(&root, "yes", None, None).unwrap();
+        let trades = read_records::<TradeRow>(&root, "yes", None, None).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(books.len(), 2);
+        let (timestamp_ms, book) = &books[0];
+        assert_eq!(*timestamp_ms, NOON_MS);
+        assert_eq!(book.market, "0xmarket");
+        let levels = |orders: &[crate::polymarket::api::clob::schemas::Order]| {
+            orders.iter().map(|o| (o.price, o.size)).collect_vec()
+        };
+        assert_eq!(levels(&book.bids), [(0.48, 10.0)]);
+        assert_eq!(levels(&book.asks), [(0.52, 20.0), (0.55, 5.0)]);
+        assert!(books[1].1.asks.is_empty());
+
+        assert_eq!(midpoints.len(), 2);
+        assert_eq!(midpoints[0].midpoint, Some(0.5));
+        assert_eq!(midpoints[1].best_ask, None);
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].transaction_hash, "0xhash");
+    }
+
+    #[test]
+    fn csv_rows_read_back_as_written() {
+        round_trip(MarketDataFormat::Csv);
+    }
+
+    #[test]
+    fn parquet_rows_read_back_as_written() {
+        round_trip(MarketDataFormat::Parquet);
+    }
+
+    #[test]
+    fn failed_flushes_are_written_once_when_retried() {
+        let root = root("failed-flush");
+        let mut writer =
+            PartitionedWriter::new(&root, &[MarketDataFormat::Csv, MarketDataFormat::Parquet])
+                .unwrap();
+        record(&mut writer);
+
+        // Midpoints can't be written, the books staged before them must not land either
+        fs::write(root.join(MidpointRow::KIND), "").unwrap();
+        assert!(writer.flush().is_err());
+        assert_eq!(writer.buffered_rows(), 7);
+        assert!(read_order_books(&root, "yes", None, None)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_file(root.join(MidpointRow::KIND)).unwrap();
+        assert_eq!(writer.flush().unwrap(), 7);
+
+        let books = read_order_books(&root, "yes", None, None).unwrap();
+        let removed = remove_tmp_files(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].1.asks.len(), 2);
+        assert_eq!(removed, 0);
+    }
+}
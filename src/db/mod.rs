@@ -1,3 +1,6 @@
 pub mod account;
-mod constants;
+pub mod allowlist;
+pub mod constants;
 pub mod database;
+pub mod market_data;
+pub mod relayer_transactions;
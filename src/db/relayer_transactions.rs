@@ -0,0 +1,317 @@
+use std::{fmt::Display, fs::File, io::ErrorKind, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{polymarket::api::relayer::schemas::TransactionState, utils::metrics::METRICS};
+
+use super::constants::RELAYER_TRANSACTIONS_FILE_PATH;
+
+// Serializes read-modify-write cycles on the store file between concurrent tasks
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayerTransactionKind {
+    EnableTrading,
+    Approve,
+    Withdraw,
+    ConvertPositions,
+    Revoke,
+    Redeem,
+    Merge,
+}
+
+impl Display for RelayerTransactionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::EnableTrading => "enable trading",
+            Self::Approve => "approve",
+            Self::Withdraw => "withdraw",
+            Self::ConvertPositions => "convert positions",
+            Self::Revoke => "revoke approvals",
+            Self::Redeem => "redeem positions",
+            Self::Merge => "merge positions",
+        };
+
+        write!(f, "{kind}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StateChange {
+    pub state: TransactionState,
+    pub at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptSummary {
+    pub block_number: Option<u64>,
+    pub gas_used: u128,
+    pub success: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RelayerTransactionRecord {
+    pub transaction_id: String,
+    pub kind: RelayerTransactionKind,
+    pub from: String,
+    pub proxy_wallet: String,
+    pub to: String,
+    pub data: String,
+    pub operation: u8,
+    pub nonce: Option<u64>,
+    pub created_at: String,
+    pub state_history: Vec<StateChange>,
+    pub transaction_hash: Option<String>,
+    pub receipt: Option<ReceiptSummary>,
+    pub resubmission_of: Option<String>,
+    pub superseded: bool,
+}
+
+impl RelayerTransactionRecord {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        transaction_id: &str,
+        kind: RelayerTransactionKind,
+        from: &str,
+        proxy_wallet: &str,
+        to: &str,
+        data: &str,
+        operation: u8,
+        nonce: Option<u64>,
+    ) -> Self {
+        let now = Utc::now().to_rfc3339();
+
+        Self {
+            transaction_id: transaction_id.to_string(),
+            kind,
+            from: from.to_string(),
+            proxy_wallet: proxy_wallet.to_string(),
+            to: to.to_string(),
+            data: data.to_string(),
+            operation,
+            nonce,
+            created_at: now.clone(),
+            state_history: vec![StateChange {
+                state: TransactionState::New,
+                at: now,
+            }],
+            transaction_hash: None,
+            receipt: None,
+            resubmission_of: None,
+            superseded: false,
+        }
+    }
+
+    pub fn with_response(mut self, state: TransactionState, transaction_hash: &str) -> Self {
+        if self.last_state() != state {
+            self.state_history.push(StateChange {
+                state,
+                at: Utc::now().to_rfc3339(),
+            });
+        }
+
+        if !transaction_hash.is_empty() {
+            self.transaction_hash = Some(transaction_hash.to_string());
+        }
+
+        self
+    }
+
+    pub fn with_resubmission_of(mut self, transaction_id: Option<&str>) -> Self {
+        self.resubmission_of = transaction_id.map(|id| id.to_string());
+        self
+    }
+
+    pub fn last_state(&self) -> TransactionState {
+        self.state_history
+            .last()
+            .map_or(TransactionState::New, |change| change.state)
+    }
+
+    pub fn age_minutes(&self) -> i64 {
+        DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|created_at| (Utc::now() - created_at.with_timezone(&Utc)).num_minutes())
+            .unwrap_or_default()
+    }
+
+    pub fn is_stuck(&self, stuck_after_minutes: i64) -> bool {
+        !self.superseded
+            && !self.last_state().is_final()
+            && self.age_minutes() >= stuck_after_minutes
+    }
+
+    pub fn needs_attention(&self, stuck_after_minutes: i64) -> bool {
+        !self.superseded && (self.last_state().is_failed() || self.is_stuck(stuck_after_minutes))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RelayerTransactionStore(pub Vec<RelayerTransactionRecord>);
+
+impl RelayerTransactionStore {
+    // A missing file is an empty store, anything unreadable is an error so it is never overwritten
+    fn read_from_file(file_path: &str) -> eyre::Result<Self> {
+        let contents = match std::fs::read_to_string(file_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => eyre::bail!("Failed to read the relayer store {file_path}: {e}"),
+        };
+
+        serde_json::from_str::<Self>(&contents)
+            .map_err(|e| eyre::eyre!("Relayer store {file_path} is corrupt: {e}"))
+    }
+
+    // Written next to the store and renamed over it, so a failed write leaves the old file intact
+    fn write_to_file(&self, file_path: &str) -> eyre::Result<()> {
+        let tmp_path = format!("{file_path}.tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        serde_json::to_writer_pretty(&mut file, &self)?;
+        file.sync_all()?;
+
+        std::fs::rename(&tmp_path, file_path)?;
+
+        Ok(())
+    }
+
+    pub fn read() -> eyre::Result<Self> {
+        Self::read_from_file(RELAYER_TRANSACTIONS_FILE_PATH)
+    }
+
+    pub fn update(&self) -> eyre::Result<()> {
+        self.write_to_file(RELAYER_TRANSACTIONS_FILE_PATH)
+    }
+
+    // Tracking happens after the relayer accepted a transaction, a failure to persist it is
+    // logged instead of failing the transaction
+    fn modify(f: impl FnOnce(&mut Self)) {
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let result = Self::read().and_then(|mut store| {
+            f(&mut store);
+            store.update()
+        });
+
+        if let Err(e) = result {
+            tracing::error!("Failed to update the relayer store: {e}");
+        }
+    }
+
+    pub fn record(record: RelayerTransactionRecord) {
+        for change in &record.state_history {
+            METRICS.record_relayer_state(&record.kind.to_string(), &change.state.to_string());
+        }
+
+        Self::modify(|store| store.0.push(record));
+    }
+
+    // Appends `state` to the history only when it differs from the last known one
+    pub fn record_state(transaction_id: &str, state: TransactionState, transaction_hash: &str) {
+        Self::modify(|store| {
+            if let Some(record) = store.get_mut(transaction_id) {
+                if record.last_state() != state {
+                    METRICS.record_relayer_state(&record.kind.to_string(), &state.to_string());
+
+                    record.state_history.push(StateChange {
+                        state,
+                        at: Utc::now().to_rfc3339(),
+                    });
+                }
+
+                if !transaction_hash.is_empty() {
+                    record.transaction_hash = Some(transaction_hash.to_string());
+                }
+            }
+        });
+    }
+
+    pub fn record_receipt(transaction_id: &str, receipt: ReceiptSummary) {
+        Self::modify(|store| {
+            if let Some(record) = store.get_mut(transaction_id) {
+                record.receipt = Some(receipt);
+            }
+        });
+    }
+
+    pub fn mark_superseded(transaction_id: &str) {
+        Self::modify(|store| {
+            if let Some(record) = store.get_mut(transaction_id) {
+                record.superseded = true;
+            }
+        });
+    }
+
+    fn get_mut(&mut self, transaction_id: &str) -> Option<&mut RelayerTransactionRecord> {
+        self.0
+            .iter_mut()
+            .find(|record| record.transaction_id == transaction_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn store_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("polymarket-eye-{name}-{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn record(transaction_id: &str) -> RelayerTransactionRecord {
+        RelayerTransactionRecord::new(
+            transaction_id,
+            RelayerTransactionKind::Withdraw,
+            "0xfrom",
+            "0xproxy",
+            "0xto",
+            "0x",
+            0,
+            Some(1),
+        )
+    }
+
+    #[test]
+    fn missing_stores_are_empty() {
+        let store = RelayerTransactionStore::read_from_file(&store_path("missing")).unwrap();
+
+        assert!(store.0.is_empty());
+    }
+
+    #[test]
+    fn stores_survive_a_write_and_read() {
+        let path = store_path("round-trip");
+
+        RelayerTransactionStore(vec![record("first"), record("second")])
+            .write_to_file(&path)
+            .unwrap();
+        let store = RelayerTransactionStore::read_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let ids = store
+            .0
+            .iter()
+            .map(|record| record.transaction_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["first", "second"]);
+        assert!(!Path::new(&format!("{path}.tmp")).exists());
+    }
+
+    #[test]
+    fn corrupt_stores_are_errors() {
+        let path = store_path("corrupt");
+        std::fs::write(&path, "[{\"transaction_id\": ").unwrap();
+
+        let result = RelayerTransactionStore::read_from_file(&path);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(contents, "[{\"transaction_id\": ");
+    }
+}
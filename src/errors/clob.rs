@@ -0,0 +1,273 @@
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+// What a caller should do about a rejected order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClobRemedy {
+    // Send the order again, rebuilt from fresh market data
+    Retry,
+    // Send a smaller order
+    Resize,
+    // The order can't go through as it is
+    Abort,
+}
+
+// Known reasons the CLOB rejects an order, parsed from the `errorMsg` of the response or from the
+// body of an HTTP 400
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClobRejection {
+    NotEnoughBalance,
+    InvalidTickSize,
+    MinSizeNotMet,
+    CrossesBook,
+    MarketClosed,
+    MarketNotReady,
+    Duplicated,
+    FokNotFilled,
+    InvalidExpiration,
+    InvalidSignature,
+    ExecutionFailed,
+    Other(String),
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl ClobRejection {
+    pub fn parse(message: &str) -> Self {
+        let lowercase = message.to_lowercase();
+        let contains = |patterns: &[&str]| patterns.iter().any(|p| lowercase.contains(p));
+
+        if contains(&["not enough balance", "allowance"]) {
+            Self::NotEnoughBalance
+        } else if contains(&["tick size", "min_tick_size"]) {
+            Self::InvalidTickSize
+        } else if contains(&["lower than the minimum", "min_size", "minimum size"]) {
+            Self::MinSizeNotMet
+        } else if contains(&["crosses book", "cross the book", "crosses the book"]) {
+            Self::CrossesBook
+        } else if contains(&["not yet ready", "market_not_ready"]) {
+            Self::MarketNotReady
+        } else if contains(&["market is closed", "closed market", "does not exist"]) {
+            Self::MarketClosed
+        } else if contains(&["duplicated", "already been placed"]) {
+            Self::Duplicated
+        } else if contains(&["fully filled", "fok_order_not_filled"]) {
+            Self::FokNotFilled
+        } else if contains(&["expiration"]) {
+            Self::InvalidExpiration
+        } else if contains(&["signature", "signer", "api key"]) {
+            Self::InvalidSignature
+        } else if contains(&["could not insert order", "could not run the execution"]) {
+            Self::ExecutionFailed
+        } else {
+            Self::Other(message.to_string())
+        }
+    }
+
+    // Invalid orders come back as HTTP 400 with `{"error": "..."}`
+    pub fn from_http_error(status: StatusCode, text: &str) -> Option<Self> {
+        if status != StatusCode::BAD_REQUEST {
+            return None;
+        }
+
+        let message = serde_json::from_str::<ErrorBody>(text)
+            .map(|body| body.error)
+            .unwrap_or_else(|_| text.to_string());
+
+        Some(Self::parse(&message))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotEnoughBalance => "not_enough_balance",
+            Self::InvalidTickSize => "invalid_tick_size",
+            Self::MinSizeNotMet => "min_size_not_met",
+            Self::CrossesBook => "crosses_book",
+            Self::MarketClosed => "market_closed",
+            Self::MarketNotReady => "market_not_ready",
+            Self::Duplicated => "duplicated",
+            Self::FokNotFilled => "fok_not_filled",
+            Self::InvalidExpiration => "invalid_expiration",
+            Self::InvalidSignature => "invalid_signature",
+            Self::ExecutionFailed => "execution_failed",
+            Self::Other(_) => "other",
+        }
+    }
+
+    pub fn remedy(&self) -> ClobRemedy {
+        match self {
+            Self::InvalidTickSize
+            | Self::CrossesBook
+            | Self::MarketNotReady
+            | Self::ExecutionFailed => ClobRemedy::Retry,
+            Self::FokNotFilled => ClobRemedy::Resize,
+            Self::NotEnoughBalance
+            | Self::MinSizeNotMet
+            | Self::MarketClosed
+            | Self::Duplicated
+            | Self::InvalidExpiration
+            | Self::InvalidSignature
+            | Self::Other(_) => ClobRemedy::Abort,
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::NotEnoughBalance => {
+                "top up USDC.e or the position, and check the exchange approvals in the audit"
+            }
+            Self::InvalidTickSize => "the tick size changed, rebuild the order with the new one",
+            Self::MinSizeNotMet => "the order is below the market's minimum size",
+            Self::CrossesBook => "a post-only order would match, reprice it",
+            Self::MarketClosed => "the market no longer accepts orders",
+            Self::MarketNotReady => "the market is not open for orders yet, try again shortly",
+            Self::Duplicated => "the same order was already placed",
+            Self::FokNotFilled => "the book can't fill the whole order, place a smaller one",
+            Self::InvalidExpiration => "the expiration is in the past or too close",
+            Self::InvalidSignature => "signer or API key mismatch, run registration again",
+            Self::ExecutionFailed => "the CLOB failed to process the order, try again",
+            Self::Other(_) => "unknown rejection",
+        }
+    }
+}
+
+impl Display for ClobRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Other(message) => write!(f, "{message}"),
+            _ => write!(f, "{} ({})", self.as_str(), self.hint()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_error_messages_are_parsed() {
+        let cases = [
+            (
+                "not enough balance / allowance",
+                ClobRejection::NotEnoughBalance,
+            ),
+            (
+                "INVALID_ORDER_MIN_TICK_SIZE: order is invalid. Price (0.555), min: 0.01 - max: 0.99",
+                ClobRejection::InvalidTickSize,
+            ),
+            (
+                "INVALID_ORDER_MIN_SIZE: order 0x12 is invalid. Size (2) lower than the minimum: 5",
+                ClobRejection::MinSizeNotMet,
+            ),
+            (
+                "invalid post-only order: order crosses book",
+                ClobRejection::CrossesBook,
+            ),
+            (
+                "the market is not yet ready to process new orders",
+                ClobRejection::MarketNotReady,
+            ),
+            (
+                "the orderbook 7123 does not exist",
+                ClobRejection::MarketClosed,
+            ),
+            (
+                "INVALID_ORDER_DUPLICATED: order is invalid. Duplicated.",
+                ClobRejection::Duplicated,
+            ),
+            (
+                "order couldn't be fully filled. FOK orders are fully filled or killed.",
+                ClobRejection::FokNotFilled,
+            ),
+            (
+                "INVALID_ORDER_EXPIRATION: invalid expiration",
+                ClobRejection::InvalidExpiration,
+            ),
+            ("invalid signature", ClobRejection::InvalidSignature),
+            (
+                "the order signer address has to be the address of the API KEY",
+                ClobRejection::InvalidSignature,
+            ),
+            (
+                "could not run the execution",
+                ClobRejection::ExecutionFailed,
+            ),
+        ];
+
+        for (message, rejection) in cases {
+            assert_eq!(ClobRejection::parse(message), rejection, "{message}");
+        }
+    }
+
+    #[test]
+    fn unknown_messages_are_kept_as_they_are() {
+        let rejection = ClobRejection::parse("order delayed by the matching engine");
+
+        assert_eq!(
+            rejection,
+            ClobRejection::Other("order delayed by the matching engine".to_string())
+        );
+        assert_eq!(
+            rejection.to_string(),
+            "order delayed by the matching engine"
+        );
+    }
+
+    #[test]
+    fn bad_requests_are_parsed_from_their_error_body() {
+        assert_eq!(
+            ClobRejection::from_http_error(
+                StatusCode::BAD_REQUEST,
+                r#"{"error": "not enough balance / allowance"}"#
+            ),
+            Some(ClobRejection::NotEnoughBalance)
+        );
+
+        // Bodies that aren't JSON are parsed as the message
+        assert_eq!(
+            ClobRejection::from_http_error(StatusCode::BAD_REQUEST, "invalid signature"),
+            Some(ClobRejection::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn other_statuses_are_not_rejections() {
+        for status in [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ] {
+            assert_eq!(
+                ClobRejection::from_http_error(status, r#"{"error": "invalid signature"}"#),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn rejections_map_to_their_remedy() {
+        let cases = [
+            (ClobRejection::InvalidTickSize, ClobRemedy::Retry),
+            (ClobRejection::CrossesBook, ClobRemedy::Retry),
+            (ClobRejection::MarketNotReady, ClobRemedy::Retry),
+            (ClobRejection::ExecutionFailed, ClobRemedy::Retry),
+            (ClobRejection::FokNotFilled, ClobRemedy::Resize),
+            (ClobRejection::NotEnoughBalance, ClobRemedy::Abort),
+            (ClobRejection::MinSizeNotMet, ClobRemedy::Abort),
+            (ClobRejection::MarketClosed, ClobRemedy::Abort),
+            (ClobRejection::Duplicated, ClobRemedy::Abort),
+            (ClobRejection::InvalidExpiration, ClobRemedy::Abort),
+            (ClobRejection::InvalidSignature, ClobRemedy::Abort),
+            (ClobRejection::Other(String::new()), ClobRemedy::Abort),
+        ];
+
+        for (rejection, remedy) in cases {
+            assert_eq!(rejection.remedy(), remedy, "{rejection:?}");
+        }
+    }
+}
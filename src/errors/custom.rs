@@ -1,6 +1,10 @@
 use eyre::Report;
 use thiserror::Error;
 
+use crate::polymarket::api::clob::typedefs::Side;
+
+use super::clob::ClobRejection;
+
 #[derive(Error, Debug)]
 #[allow(unused)]
 pub enum CustomError {
@@ -25,9 +29,33 @@ pub enum CustomError {
     #[error("Timeout error: {0}")]
     Timeout(String),
 
-    #[error("Clob API error: {0}")]
-    ClobApiError(String),
+    #[error("Relayer transaction failed: {0}")]
+    RelayerTransactionFailed(String),
+
+    #[error("Order rejected by the CLOB: {0}")]
+    ClobRejected(ClobRejection),
+
+    #[error("Unsupported tick size: {0}")]
+    UnsupportedTickSize(String),
+
+    #[error("Order of {size} shares is below the market minimum of {min_size}")]
+    OrderBelowMinSize { size: f64, min_size: f64 },
+
+    #[error("No liquidity on the book to fill a {0} order")]
+    NoLiquidity(Side),
+
+    #[error("Order blocked by risk checks: {0}")]
+    RiskRejected(String),
 
     #[error("Unexpected error: {0}")]
     Unexpected(#[from] Report),
 }
+
+impl CustomError {
+    pub fn clob_rejection(&self) -> Option<&ClobRejection> {
+        match self {
+            Self::ClobRejected(rejection) => Some(rejection),
+            _ => None,
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod clob;
 pub mod custom;
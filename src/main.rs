@@ -1,4 +1,7 @@
+use config::Config;
 use modules::menu::menu;
+use notifier::{init_notifier, notify_now, Severity};
+use risk::init_risk_manager;
 
 use utils::logger::init_default_logger;
 
@@ -6,16 +9,27 @@ mod config;
 mod db;
 mod errors;
 mod modules;
+mod notifier;
 mod onchain;
 mod polymarket;
+mod risk;
+mod strategy;
 mod utils;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
-    let _guard = init_default_logger();
+    let config = Config::read_default().await;
+    let _guard = init_default_logger(&config);
 
-    if let Err(e) = menu().await {
+    if let Err(e) = init_notifier(&config) {
+        tracing::error!("Notifications disabled, invalid configuration: {e}");
+    }
+
+    init_risk_manager(&config);
+
+    if let Err(e) = menu(config).await {
         tracing::error!("Execution stopped with error: {e}");
+        notify_now(Severity::Error, "Execution stopped", e.to_string()).await;
     }
 
     Ok(())
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use alloy::primitives::U256;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use itertools::Itertools;
+use tabled::{builder::Builder, settings::Style};
+use tracing::Instrument;
+
+use crate::{
+    config::Config,
+    db::{account::Account, database::Database},
+    onchain::{
+        constants::POLYGON_EXPLORER_TX_BASE_URL,
+        multicall::{multicall_audit_wallets, ApprovalState, WalletAudit, APPROVAL_TARGETS},
+        rpc::PolygonProvider,
+        types::token::Token,
+    },
+    polymarket::api::{
+        relayer::{common::approve_tokens, endpoints::wait_for_transaction_confirmation},
+        typedefs::AmpCookie,
+    },
+    utils::misc::pretty_sleep,
+};
+
+pub async fn audit_wallets(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let wallets =
+        db.0.iter()
+            .map(|account| (account.get_proxy_address(), account.signer().address()))
+            .collect_vec();
+
+    let audits = multicall_audit_wallets(&wallets, provider).await?;
+
+    print_audits(&audits);
+
+    let to_repair =
+        db.0.iter()
+            .zip(&audits)
+            .filter(|(_, audit)| !audit.missing_approvals().is_empty())
+            .collect_vec();
+
+    if to_repair.is_empty() {
+        tracing::info!("All {} wallets have every approval set", audits.len());
+        return Ok(());
+    }
+
+    for (account, audit) in &to_repair {
+        tracing::warn!(
+            "{} | Missing approvals: {}",
+            account.proxy_address,
+            audit
+                .missing_approvals()
+                .iter()
+                .map(|target| target.label)
+                .join(", ")
+        );
+    }
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Repair approvals of {} wallets through the relayer?",
+            to_repair.len()
+        ))
+        .default(false)
+        .interact()?;
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    for (i, (account, _)) in to_repair.iter().enumerate() {
+        if let Err(e) = repair_approvals(account).instrument(account.span()).await {
+            tracing::error!("{} | Approval repair failed: {e}", account.proxy_address);
+        }
+
+        if i + 1 < to_repair.len() {
+            pretty_sleep(config.registration_sleep_range).await;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_audits(audits: &[WalletAudit]) {
+    let fmt_balance = |balance: Option<_>, token: Token| {
+        balance.map_or("?".to_string(), |balance| token.to_ui_amount(balance))
+    };
+
+    let mut builder = Builder::default();
+
+    builder.push_record(
+        ["Proxy wallet", "USDC.e", "EOA POL"]
+            .into_iter()
+            .chain(APPROVAL_TARGETS.iter().map(|target| target.label)),
+    );
+
+    for audit in audits {
+        let approvals = audit.approvals.iter().map(|(_, state)| match state {
+            ApprovalState::Allowance(allowance) if *allowance == U256::MAX => "max".to_string(),
+            ApprovalState::Allowance(allowance) if allowance.is_zero() => "missing".to_string(),
+            ApprovalState::Allowance(allowance) => Token::USDCE.to_ui_amount(*allowance),
+            ApprovalState::Operator(true) => "yes".to_string(),
+            ApprovalState::Operator(false) => "missing".to_string(),
+            ApprovalState::Unknown => "?".to_string(),
+        });
+
+        builder.push_record(
+            [
+                audit.proxy_wallet.to_string(),
+                fmt_balance(audit.usdce_balance, Token::USDCE),
+                fmt_balance(audit.pol_balance, Token::POL),
+            ]
+            .into_iter()
+            .chain(approvals),
+        );
+    }
+
+    let mut table = builder.build();
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+async fn repair_approvals(account: &Account) -> eyre::Result<()> {
+    let mut amp_cookie = AmpCookie::new();
+    let proxy = account.proxy();
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    tracing::info!("{} | Giving token approvals", account.proxy_address);
+
+    let tx_id = approve_tokens(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+    )
+    .await?;
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Approval succeded: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    Ok(())
+}
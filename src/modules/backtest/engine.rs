@@ -0,0 +1,286 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::{
+    polymarket::api::clob::{schemas::OrderBookData, typedefs::TickSize},
+    risk::RiskLimits,
+    strategy::{
+        execution::check_intent,
+        simulator::{simulate_fill, SimulatedFill},
+        OrderIntent, Portfolio, Strategy,
+    },
+};
+
+pub struct BacktestSettings {
+    pub initial_cash: f64,
+    pub fee_rate_bps: f64,
+    pub tick_size: TickSize,
+    pub timer_interval_ms: i64,
+    pub limits: RiskLimits,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TradeRecord {
+    pub timestamp_ms: i64,
+    pub token_id: String,
+    pub side: String,
+    pub status: &'static str,
+    pub reason: String,
+    pub requested: f64,
+    pub limit_price: f64,
+    pub price: f64,
+    pub size: f64,
+    pub notional: f64,
+    pub fee: f64,
+    pub cash_after: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EquityPoint {
+    pub timestamp_ms: i64,
+    pub cash: f64,
+    pub positions_value: f64,
+    pub equity: f64,
+    pub drawdown: f64,
+}
+
+pub struct BacktestReport {
+    pub strategy: &'static str,
+    pub initial_cash: f64,
+    pub trades: Vec<TradeRecord>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl BacktestReport {
+    pub fn final_equity(&self) -> f64 {
+        self.equity_curve
+            .last()
+            .map_or(self.initial_cash, |point| point.equity)
+    }
+
+    pub fn return_percentage(&self) -> f64 {
+        (self.final_equity() / self.initial_cash - 1.0) * 100.0
+    }
+
+    pub fn max_drawdown(&self) -> f64 {
+        self.equity_curve
+            .iter()
+            .map(|point| point.drawdown)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn total_fees(&self) -> f64 {
+        self.trades
+            .iter()
+            .map(|trade| trade.fee)
+            .fold(0.0, |total, fee| total + fee)
+    }
+
+    pub fn count(&self, status: &str) -> usize {
+        self.trades
+            .iter()
+            .filter(|trade| trade.status == status)
+            .count()
+    }
+}
+
+// Replays the books in timestamp order, every intent is filled against the latest book of its
+// token. Fills don't consume the recorded liquidity, the next snapshot shows the book as it was.
+// Timers fire on recorded time, at most once per snapshot
+pub fn run_backtest(
+    strategy: &mut dyn Strategy,
+    books: &[(i64, OrderBookData)],
+    settings: &BacktestSettings,
+) -> BacktestReport {
+    let mut portfolio = Portfolio::new(settings.initial_cash, HashMap::new());
+    let mut latest_books: HashMap<&str, &OrderBookData> = HashMap::new();
+    let mut trades = vec![];
+    let mut equity_curve: Vec<EquityPoint> = vec![];
+    let mut peak = settings.initial_cash;
+    let mut next_timer_ms = books.first().map_or(0, |(timestamp_ms, _)| *timestamp_ms);
+
+    for (timestamp_ms, book) in books {
+        latest_books.insert(&book.asset_id, book);
+        portfolio.update_mark(book);
+
+        let mut intents = VecDeque::from(strategy.on_book(&portfolio.context(*timestamp_ms), book));
+
+        if *timestamp_ms >= next_timer_ms {
+            intents.extend(strategy.on_timer(&portfolio.context(*timestamp_ms)));
+            next_timer_ms = timestamp_ms + settings.timer_interval_ms;
+        }
+
+        while let Some(intent) = intents.pop_front() {
+            let result =
+                check_intent(&intent, &portfolio, &settings.limits).and_then(
+                    |_| match latest_books.get(intent.token_id.as_str()) {
+                        Some(book) => simulate_fill(
+                            &intent,
+                            book,
+                            &portfolio,
+                            settings.fee_rate_bps,
+                            settings.tick_size,
+                        ),
+                        None => Err("no recorded book for the token yet".to_string()),
+                    },
+                );
+
+            let trade = trade_record(*timestamp_ms, &intent, &result, &mut portfolio);
+            trades.push(trade);
+
+            if let Ok(simulated) = result {
+                intents
+                    .extend(strategy.on_fill(&portfolio.context(*timestamp_ms), &simulated.fill));
+            }
+        }
+
+        let positions_value = portfolio.positions_value();
+        let equity = portfolio.cash + positions_value;
+        peak = peak.max(equity);
+
+        equity_curve.push(EquityPoint {
+            timestamp_ms: *timestamp_ms,
+            cash: portfolio.cash,
+            positions_value,
+            equity,
+            drawdown: if peak > 0.0 {
+                (peak - equity) / peak
+            } else {
+                0.0
+            },
+        });
+    }
+
+    BacktestReport {
+        strategy: strategy.name(),
+        initial_cash: settings.initial_cash,
+        trades,
+        equity_curve,
+    }
+}
+
+// Applies a simulated fill to the portfolio and logs the outcome of the intent
+fn trade_record(
+    timestamp_ms: i64,
+    intent: &OrderIntent,
+    result: &Result<SimulatedFill, String>,
+    portfolio: &mut Portfolio,
+) -> TradeRecord {
+    let mut record = TradeRecord {
+        timestamp_ms,
+        token_id: intent.token_id.clone(),
+        side: intent.side.to_string(),
+        status: "rejected",
+        reason: String::new(),
+        requested: intent.amount,
+        limit_price: 0.0,
+        price: 0.0,
+        size: 0.0,
+        notional: 0.0,
+        fee: 0.0,
+        cash_after: portfolio.cash,
+    };
+
+    let simulated = match result {
+        Ok(simulated) => simulated,
+        Err(reason) => {
+            record.reason = reason.clone();
+            return record;
+        }
+    };
+
+    portfolio.apply_fill(&simulated.fill);
+
+    record.status = if simulated.partial {
+        "partial"
+    } else {
+        "filled"
+    };
+    record.limit_price = simulated.limit_price;
+    record.price = simulated.fill.price;
+    record.size = simulated.fill.size;
+    record.notional = simulated.fill.notional;
+    record.fee = simulated.fill.fee;
+    record.cash_after = portfolio.cash;
+
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strategy::{test_book, StrategyContext};
+
+    use super::*;
+
+    // Buys once with the first book it sees
+    struct BuyOnce(f64);
+
+    impl Strategy for BuyOnce {
+        fn name(&self) -> &'static str {
+            "buy_once"
+        }
+
+        fn on_book(&mut self, _ctx: &StrategyContext, book: &OrderBookData) -> Vec<OrderIntent> {
+            match std::mem::take(&mut self.0) {
+                amount if amount > 0.0 => vec![OrderIntent::buy(&book.asset_id, amount)],
+                _ => vec![],
+            }
+        }
+    }
+
+    fn book_at(mid: f64) -> OrderBookData {
+        test_book(
+            "0xmarket",
+            "yes",
+            &[(mid - 0.01, 100.0)],
+            &[(mid + 0.01, 100.0)],
+        )
+    }
+
+    fn settings() -> BacktestSettings {
+        BacktestSettings {
+            initial_cash: 100.0,
+            fee_rate_bps: 0.0,
+            tick_size: "0.01".parse().unwrap(),
+            timer_interval_ms: 1000,
+            limits: RiskLimits::default(),
+        }
+    }
+
+    #[test]
+    fn drawdown_is_measured_from_the_equity_peak() {
+        let books = [(0, book_at(0.5)), (1, book_at(0.7)), (2, book_at(0.3))];
+
+        let report = run_backtest(&mut BuyOnce(10.0), &books, &settings());
+
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.count("filled"), 1);
+
+        // Bought at the 0.51 ask, the position is marked at the midpoint
+        let size = 10.0 / 0.51;
+        let peak = 90.0 + size * 0.7;
+        let low = 90.0 + size * 0.3;
+
+        assert!((report.equity_curve[1].equity - peak).abs() < 1e-6);
+        assert!((report.final_equity() - low).abs() < 1e-6);
+        assert!((report.max_drawdown() - (peak - low) / peak).abs() < 1e-6);
+        assert!(report.return_percentage() < 0.0);
+    }
+
+    #[test]
+    fn intents_over_the_risk_limits_are_rejected() {
+        let settings = BacktestSettings {
+            limits: RiskLimits {
+                max_order_usdc: 5.0,
+                ..Default::default()
+            },
+            ..settings()
+        };
+
+        let report = run_backtest(&mut BuyOnce(10.0), &[(0, book_at(0.5))], &settings);
+
+        assert_eq!(report.count("rejected"), 1);
+        assert_eq!(report.final_equity(), 100.0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use chrono::{NaiveDate, Utc};
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect};
+use itertools::Itertools;
+use tabled::{builder::Builder, settings::Style};
+
+use crate::{
+    config::Config,
+    db::{
+        constants::{BACKTESTS_FOLDER_PATH, MARKET_DATA_FOLDER_PATH},
+        market_data::{
+            reader::{read_order_books, recorded_tokens},
+            BookLevelRow,
+        },
+    },
+    polymarket::api::clob::typedefs::TickSize,
+    risk::RiskLimits,
+    strategy::available_strategies,
+};
+
+use engine::{run_backtest, BacktestReport, BacktestSettings};
+
+mod engine;
+
+pub async fn run_backtests(config: &Config) -> eyre::Result<()> {
+    let tokens = recorded_tokens::<BookLevelRow>(MARKET_DATA_FOLDER_PATH)?;
+
+    if tokens.is_empty() {
+        tracing::warn!("No recorded order books in {MARKET_DATA_FOLDER_PATH}, run the market data recorder first");
+        return Ok(());
+    }
+
+    let selected_tokens = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Tokens to replay (space to select):")
+        .items(&tokens)
+        .interact()?;
+
+    if selected_tokens.is_empty() {
+        tracing::warn!("No tokens selected");
+        return Ok(());
+    }
+
+    let from = prompt_date("From date (YYYY-MM-DD, empty for the first recorded day):")?;
+    let to = prompt_date("To date (YYYY-MM-DD, empty for the last recorded day):")?;
+
+    let mut strategies = available_strategies(config);
+    let names = strategies
+        .iter()
+        .map(|strategy| strategy.name())
+        .collect_vec();
+
+    let configured = names
+        .iter()
+        .map(|name| *name == config.strategy)
+        .collect_vec();
+
+    let selected_strategies = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Strategies (space to select):")
+        .items(&names)
+        .defaults(&configured)
+        .interact()?;
+
+    let settings = BacktestSettings {
+        initial_cash: config.backtest_initial_cash,
+        fee_rate_bps: config.backtest_fee_rate_bps,
+        tick_size: config.backtest_tick_size.parse::<TickSize>()?,
+        timer_interval_ms: config.strategy_timer_interval_secs as i64 * 1000,
+        limits: RiskLimits::from_config(config),
+    };
+
+    let mut books = vec![];
+    for i in selected_tokens {
+        books.extend(read_order_books(
+            MARKET_DATA_FOLDER_PATH,
+            &tokens[i],
+            from,
+            to,
+        )?);
+    }
+    books.sort_by_key(|(timestamp_ms, _)| *timestamp_ms);
+
+    if books.is_empty() {
+        tracing::warn!("No recorded order books in the selected range");
+        return Ok(());
+    }
+
+    tracing::info!("Replaying {} book snapshots", books.len());
+
+    let mut reports = vec![];
+
+    for i in selected_strategies {
+        let report = run_backtest(strategies[i].as_mut(), &books, &settings);
+
+        match save_report(&report) {
+            Ok(dir) => tracing::info!(
+                "{} | Trade log and equity curve saved to {dir}",
+                report.strategy
+            ),
+            Err(e) => tracing::error!("{} | Failed to save the report: {e}", report.strategy),
+        }
+
+        reports.push(report);
+    }
+
+    print_reports(&reports);
+
+    Ok(())
+}
+
+fn prompt_date(prompt: &str) -> eyre::Result<Option<NaiveDate>> {
+    let input: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            if input.trim().is_empty()
+                || NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").is_ok()
+            {
+                Ok(())
+            } else {
+                Err("Expected a YYYY-MM-DD date")
+            }
+        })
+        .interact_text()?;
+
+    match input.trim() {
+        "" => Ok(None),
+        date => Ok(Some(NaiveDate::parse_from_str(date, "%Y-%m-%d")?)),
+    }
+}
+
+fn print_reports(reports: &[BacktestReport]) {
+    let mut builder = Builder::default();
+
+    builder.push_record([
+        "Strategy",
+        "Filled",
+        "Partial",
+        "Rejected",
+        "Fees",
+        "Final equity",
+        "Return",
+        "Max drawdown",
+    ]);
+
+    for report in reports {
+        builder.push_record([
+            report.strategy.to_string(),
+            report.count("filled").to_string(),
+            report.count("partial").to_string(),
+            report.count("rejected").to_string(),
+            format!("{:.4}", report.total_fees()),
+            format!("{:.2}", report.final_equity()),
+            format!("{:.2}%", report.return_percentage()),
+            format!("{:.2}%", report.max_drawdown() * 100.0),
+        ]);
+    }
+
+    let mut table = builder.build();
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+// `<strategy>-<run time>/trades.csv` and `equity.csv`
+fn save_report(report: &BacktestReport) -> eyre::Result<String> {
+    let dir = Path::new(BACKTESTS_FOLDER_PATH).join(format!(
+        "{}-{}",
+        report.strategy,
+        Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::create_dir_all(&dir)?;
+
+    let mut trades = csv::Writer::from_path(dir.join("trades.csv"))?;
+    for trade in &report.trades {
+        trades.serialize(trade)?;
+    }
+    trades.flush()?;
+
+    let mut equity = csv::Writer::from_path(dir.join("equity.csv"))?;
+    for point in &report.equity_curve {
+        equity.serialize(point)?;
+    }
+    equity.flush()?;
+
+    Ok(dir.display().to_string())
+}
@@ -1,15 +1,22 @@
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use reqwest::Proxy;
 
 use crate::{
     config::Config,
     errors::custom::CustomError,
-    polymarket::api::events::{
-        endpoints::get_events,
-        schemas::{Event, Market},
+    polymarket::api::{
+        clob::{analytics::BookAnalytics, endpoints::get_order_books},
+        events::{
+            endpoints::get_events,
+            schemas::{Event, Market},
+        },
     },
 };
 
+const BOOKS_BATCH_SIZE: usize = 100;
+
 pub async fn get_filtered_events(
     proxy: Option<&Proxy>,
     config: &Config,
@@ -28,34 +35,74 @@ pub async fn get_filtered_events(
             break;
         }
 
-        filtered_events.extend(events.into_iter().filter_map(
-            |event| match event.markets.as_slice() {
-                [market]
-                    if market_fits_filters(
-                        market,
-                        config.price_difference_threshold,
-                        config.spread_threshold,
-                    ) =>
-                {
-                    Some(event)
-                }
-                _ => None,
-            },
-        ));
+        filtered_events.extend(events.into_iter().filter_map(|mut event| {
+            if !config.allow_multi_market_events && event.markets.len() != 1 {
+                return None;
+            }
+
+            event.markets.retain(|market| {
+                market.is_accepting_orders()
+                    && !market.ends_within(config.resolution_exit_hours)
+                    && market.is_binary()
+                    && market_fits_filters(market, config.price_difference_threshold)
+            });
+
+            (!event.markets.is_empty()).then_some(event)
+        }));
 
         offset += 20;
     }
 
+    retain_tight_spreads(&mut filtered_events, config.spread_threshold, proxy).await?;
+
     Ok(filtered_events)
 }
 
-fn market_fits_filters(market: &Market, max_price_diff: f64, min_spread: f64) -> bool {
-    let outcome_prices = market.outcome_prices.unwrap_or([0.0, 1000.0]);
+// Gamma's cached spread lags the book, so the spread is checked against the live YES book
+async fn retain_tight_spreads(
+    events: &mut Vec<Event>,
+    max_spread: f64,
+    proxy: Option<&Proxy>,
+) -> Result<(), CustomError> {
+    let token_ids = events
+        .iter()
+        .flat_map(|event| {
+            event
+                .markets
+                .iter()
+                .filter_map(|market| market.yes_token_id())
+        })
+        .collect_vec();
 
-    let price_diff = (outcome_prices[0] - outcome_prices[1]).abs();
+    let mut spreads = HashMap::new();
 
-    let price_diff_suitable = price_diff <= max_price_diff;
-    let min_spread_suitable = market.spread <= min_spread;
+    for batch in token_ids.chunks(BOOKS_BATCH_SIZE) {
+        for book in get_order_books(batch, proxy).await? {
+            let token_id = book.asset_id.clone();
 
-    min_spread_suitable && price_diff_suitable
+            if let Some(spread) = BookAnalytics::new(book).spread() {
+                spreads.insert(token_id, spread);
+            }
+        }
+    }
+
+    for event in events.iter_mut() {
+        event.markets.retain(|market| {
+            market
+                .yes_token_id()
+                .and_then(|token_id| spreads.get(token_id))
+                .is_some_and(|spread| *spread <= max_spread)
+        });
+    }
+
+    events.retain(|event| !event.markets.is_empty());
+
+    Ok(())
+}
+
+fn market_fits_filters(market: &Market, max_price_diff: f64) -> bool {
+    match market.outcome_prices.as_deref() {
+        Some([first, second]) => (first - second).abs() <= max_price_diff,
+        _ => false,
+    }
 }
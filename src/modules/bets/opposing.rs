@@ -1,39 +1,49 @@
-use core::f64;
-use std::{cmp::min, sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use alloy::{
-    primitives::{utils::format_units, Address, U256},
-    providers::ProviderBuilder,
-};
+use alloy::primitives::{utils::format_units, Address, U256};
 use itertools::Itertools;
 use rand::{seq::SliceRandom, thread_rng};
-use reqwest::{Proxy, Url};
+use reqwest::Proxy;
 use tokio::task::JoinSet;
 
 use crate::{
     config::Config,
     db::{account::Account, database::Database},
+    errors::clob::ClobRemedy,
     modules::registration::create_or_derive_api_key,
-    onchain::{multicall::multicall_balance_of, types::token::Token},
+    notifier::{notify, Severity},
+    onchain::{multicall::multicall_balance_of, rpc::PolygonProvider, types::token::Token},
     polymarket::api::{
         clob::{
-            endpoints::{get_neg_risk, get_order_book, place_order},
+            endpoints::{get_order_book, place_order, place_orders},
             math::calculate_market_price,
+            metadata::get_market_metadata,
             order_builder::OrderBuilder,
             schemas::{OrderRequest, OrderType, PlaceOrderResponseBody},
-            typedefs::{
-                CreateOrderOptions, Side, SignedOrder, TickSize, UserMarketOrder, UserOrder,
-            },
+            typedefs::{CreateOrderOptions, Side, SignedOrder, UserMarketOrder, UserOrder},
+            validation::validate_order_size,
         },
         events::schemas::Event,
         user::{endpoints::get_user_positions, schemas::UserPosition},
     },
+    strategy::{
+        execution::{
+            remedy_for, retry_after_rejection, ExecutionMode, MAX_ORDER_ATTEMPTS, ORDER_RETRY_DELAY,
+        },
+        opposing::OpposingStrategy,
+        runtime::{run_strategy, StrategyWallet},
+        Portfolio,
+    },
     utils::misc::random_in_range,
 };
 
 use super::events_monitor::get_filtered_events;
 
-pub async fn opposing_bets(db: Database, config: &Config) -> eyre::Result<()> {
+pub async fn opposing_bets(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
     let proxy = db.0.first().and_then(|account| account.proxy());
 
     tracing::info!("Scanning events");
@@ -44,50 +54,28 @@ pub async fn opposing_bets(db: Database, config: &Config) -> eyre::Result<()> {
         return Ok(());
     }
 
-    let addresses =
-        db.0.iter()
-            .map(|account| account.get_proxy_address())
-            .collect_vec();
-
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_http(Url::parse(&config.polygon_rpc_url)?),
-    );
-
-    let bet_amounts = multicall_balance_of(&addresses, Token::USDCE, provider)
-        .await?
-        .chunks(2)
-        .map(|pair| min(pair[0], pair[1]))
-        .map(|max_bet| {
-            let multiplier = random_in_range(config.bet_balance_percentage);
-            max_bet * U256::from(multiplier) / U256::from(100)
-        })
-        .collect_vec();
-
+    let config = Arc::new(config.clone());
     let mut handles = JoinSet::new();
 
     for _ in 0..config.cycle_count {
-        for (accounts_pair, bet_amount) in db.0.chunks(2).zip(bet_amounts.iter()) {
+        let addresses =
+            db.0.iter()
+                .map(|account| account.get_proxy_address())
+                .collect_vec();
+        let balances = multicall_balance_of(&addresses, Token::USDCE, provider.clone()).await?;
+
+        for (accounts_pair, balances) in db.0.chunks_exact(2).zip(balances.chunks_exact(2)) {
             let event = filtered_events.choose(&mut thread_rng()).unwrap().clone();
             let spawn_delay = random_in_range(config.batch_delay_range);
-            let sell_delay_range = config.sell_delay_range;
 
-            let first_account = accounts_pair[0].clone();
-            let second_account = accounts_pair[1].clone();
-            let amount = *bet_amount;
+            let accounts = [accounts_pair[0].clone(), accounts_pair[1].clone()];
+            let balances = [balances[0], balances[1]];
+            let config = config.clone();
 
             handles.spawn(async move {
                 tokio::time::sleep(Duration::from_secs(spawn_delay)).await;
 
-                place_opposing_bets_with_timeout(
-                    first_account,
-                    second_account,
-                    event,
-                    amount,
-                    sell_delay_range,
-                )
-                .await
+                place_opposing_bets(accounts, balances, event, &config).await
             });
         }
 
@@ -106,108 +94,71 @@ pub async fn opposing_bets(db: Database, config: &Config) -> eyre::Result<()> {
     Ok(())
 }
 
-async fn place_opposing_bets_with_timeout(
-    first_account: Account,
-    second_account: Account,
+// The first wallet buys YES and the second NO, both legs run through one opposing strategy so a leg
+// that fails to fill unwinds the other
+#[tracing::instrument(skip_all, fields(market = tracing::field::Empty))]
+async fn place_opposing_bets(
+    accounts: [Account; 2],
+    balances: [U256; 2],
     event: Event,
-    amount: U256,
-    sell_delay_range: [u64; 2],
+    config: &Config,
 ) -> eyre::Result<String> {
-    tracing::info!(
-        "{} - {} | Event chosen: {event}",
-        first_account.proxy_address,
-        second_account.proxy_address
+    let pair = format!(
+        "{} - {}",
+        accounts[0].proxy_address, accounts[1].proxy_address
     );
 
-    let market = event.markets.first().unwrap();
+    let market = event.markets.choose(&mut thread_rng()).unwrap();
+    tracing::Span::current().record("market", market.label());
 
-    let token_ids = market
-        .clob_token_ids
-        .iter()
-        .map(|token_id| token_id.as_str())
-        .collect_vec();
-
-    let tick_size = TickSize::from_str(&market.order_price_min_tick_size.to_string()).unwrap();
-
-    let float_amount = format_units(amount, "mwei")?.parse::<f64>()?;
-
-    let futures_results = tokio::join!(
-        create_and_place_buy_market_order(
-            &first_account,
-            token_ids[0],
-            &event,
-            float_amount,
-            tick_size
-        ),
-        create_and_place_buy_market_order(
-            &second_account,
-            token_ids[1],
-            &event,
-            float_amount,
-            tick_size
-        ),
-    );
+    tracing::info!("{pair} | Event chosen: {event}. Market: {}", market.label());
 
-    match futures_results {
-        (Ok(_), Err(e)) => {
-            tracing::info!(
-                "{} - {} | Failed to place an order: {e}. Selling position on {}", // first succeeded, second failed -> sell the position on the first account
-                first_account.proxy_address,
-                second_account.proxy_address,
-                first_account.proxy_address,
-            );
+    let (Some(yes_token_id), Some(no_token_id)) = (market.yes_token_id(), market.no_token_id())
+    else {
+        eyre::bail!("{pair} | {} has no outcome tokens", market.label());
+    };
 
-            create_and_place_sell_market_order(&first_account, token_ids[0], tick_size).await?;
-        }
-        (Err(e), Ok(_)) => {
-            tracing::info!(
-                "{} - {} | Failed to place an order: {e}. Selling position on {}", // second succeeded, first failed -> sell the position on the second account
-                first_account.proxy_address,
-                second_account.proxy_address,
-                second_account.proxy_address,
-            );
+    let mut wallets = vec![];
+    for ((account, balance), token_id) in accounts
+        .iter()
+        .zip(balances)
+        .zip([yes_token_id, no_token_id])
+    {
+        wallets.push(StrategyWallet {
+            account,
+            portfolio: Portfolio::new(
+                format_units(balance, "mwei")?.parse::<f64>()?,
+                HashMap::new(),
+            ),
+            token_ids: vec![token_id.to_string()],
+        });
+    }
 
-            create_and_place_sell_market_order(&second_account, token_ids[1], tick_size).await?;
-        }
-        (Ok(_), Ok(_)) => {
-            let delay = Duration::from_secs(random_in_range(sell_delay_range)); // both landed, then sleep for random delay and sell
-
-            tracing::info!(
-                "{} - {} | Both orders placed, sleeping for {} seconds",
-                first_account.proxy_address,
-                second_account.proxy_address,
-                delay.as_secs()
-            );
-            tokio::time::sleep(delay).await;
+    let mut strategy = OpposingStrategy::new(
+        random_in_range(config.bet_balance_percentage) as f64,
+        random_in_range(config.sell_delay_range) as i64 * 1000,
+        1,
+    );
 
-            let _ = tokio::join!(
-                create_and_place_sell_market_order(&first_account, token_ids[0], tick_size),
-                create_and_place_sell_market_order(&second_account, token_ids[1], tick_size),
-            );
-        }
-        _ => {
-            tracing::error!(
-                "{} - {} | Failed to place both orders",
-                first_account.proxy_address,
-                second_account.proxy_address
-            )
-        } // Case of both fails (tbh don't really care about them)
+    let fills = run_strategy(config, ExecutionMode::Live, &mut strategy, &mut wallets).await?;
+
+    if fills == 0 {
+        tracing::error!("{pair} | Failed to place both orders");
+        notify(
+            Severity::Error,
+            "Failed to place both orders",
+            format!("{pair} | {}", market.label()),
+        );
     }
 
-    Ok(format!(
-        "{} - {}",
-        first_account.proxy_address, second_account.proxy_address
-    ))
+    Ok(pair)
 }
 
+#[tracing::instrument(name = "order", skip_all, fields(token_id = %token_id, order_id = tracing::field::Empty))]
 pub async fn create_and_place_sell_market_order(
     account: &Account,
     token_id: &str,
-    tick_size: TickSize,
 ) -> eyre::Result<PlaceOrderResponseBody> {
-    let signed_order =
-        build_market_sell_signed_order_for_account(account, token_id, tick_size).await?;
-
     let api_key = {
         let maybe_key = account.api_key.read().unwrap().clone();
         if let Some(key) = maybe_key {
@@ -220,26 +171,41 @@ pub async fn create_and_place_sell_market_order(
         }
     };
 
-    let order_request = OrderRequest::new(signed_order, &api_key, Some(OrderType::Gtc));
+    let mut attempt = 1;
+
+    let place_order_result = loop {
+        // Rebuilt on every attempt so a retry picks up the current book and tick size
+        let signed_order = build_market_sell_signed_order_for_account(account, token_id).await?;
+        let order_request = OrderRequest::new(signed_order, &api_key, Some(OrderType::Gtc));
 
-    let place_order_result = place_order(account, order_request).await?;
+        match place_order(account, order_request).await {
+            Ok(result) => break result,
+            Err(e) => match remedy_for(&e, attempt) {
+                Some(ClobRemedy::Retry) => {
+                    retry_after_rejection(&account.proxy_address, &e, attempt).await
+                }
+                _ => return Err(e.into()),
+            },
+        }
+
+        attempt += 1;
+    };
+
+    if let Some(order_id) = &place_order_result.order_id {
+        tracing::Span::current().record("order_id", order_id.as_str());
+    }
 
     place_order_result.log_successful_placement(Side::Sell, &account.proxy_address);
 
     Ok(place_order_result)
 }
 
-async fn create_and_place_buy_market_order(
+#[tracing::instrument(name = "order", skip_all, fields(token_id = %token_id, order_id = tracing::field::Empty))]
+pub async fn create_and_place_buy_market_order(
     account: &Account,
     token_id: &str,
-    event: &Event,
     amount_in: f64,
-    tick_size: TickSize,
 ) -> eyre::Result<PlaceOrderResponseBody> {
-    let signed_order =
-        build_market_buy_signed_order_for_account(account, token_id, event, amount_in, tick_size)
-            .await?;
-
     let api_key = {
         let maybe_key = account.api_key.read().unwrap().clone();
         if let Some(key) = maybe_key {
@@ -252,53 +218,141 @@ async fn create_and_place_buy_market_order(
         }
     };
 
-    let order_request = OrderRequest::new(signed_order, &api_key, None);
+    let mut amount = amount_in;
+    let mut attempt = 1;
+
+    let place_order_result = loop {
+        let signed_order =
+            build_market_buy_signed_order_for_account(account, token_id, amount).await?;
+        let order_request = OrderRequest::new(signed_order, &api_key, None);
+
+        match place_order(account, order_request).await {
+            Ok(result) => break result,
+            Err(e) => match remedy_for(&e, attempt) {
+                Some(ClobRemedy::Retry) => {
+                    retry_after_rejection(&account.proxy_address, &e, attempt).await
+                }
+                Some(ClobRemedy::Resize) => {
+                    amount /= 2.0;
+                    retry_after_rejection(&account.proxy_address, &e, attempt).await
+                }
+                _ => return Err(e.into()),
+            },
+        }
 
-    let place_order_result = place_order(account, order_request).await?;
+        attempt += 1;
+    };
+
+    if let Some(order_id) = &place_order_result.order_id {
+        tracing::Span::current().record("order_id", order_id.as_str());
+    }
 
     place_order_result.log_successful_placement(Side::Buy, &account.proxy_address);
 
     Ok(place_order_result)
 }
 
+// Sells the whole position of every token in batch requests, results come back in the order of
+// `token_ids`. Orders a rebuild can fix are rebuilt and sent again together.
+pub async fn create_and_place_sell_market_orders(
+    account: &Account,
+    token_ids: &[&str],
+) -> eyre::Result<Vec<eyre::Result<PlaceOrderResponseBody>>> {
+    let api_key = {
+        let maybe_key = account.api_key.read().unwrap().clone();
+        if let Some(key) = maybe_key {
+            key
+        } else {
+            let response =
+                create_or_derive_api_key(account.signer(), account.proxy().as_ref()).await?;
+            account.update_credentials(response);
+            account.api_key.read().unwrap().as_ref().unwrap().clone()
+        }
+    };
+
+    let mut results = token_ids.iter().map(|_| None).collect_vec();
+    let mut pending = (0..token_ids.len()).collect_vec();
+    let mut attempt = 1;
+
+    while !pending.is_empty() {
+        let mut indices = vec![];
+        let mut order_requests = vec![];
+
+        for index in pending {
+            match build_market_sell_signed_order_for_account(account, token_ids[index]).await {
+                Ok(signed_order) => {
+                    indices.push(index);
+                    order_requests.push(OrderRequest::new(
+                        signed_order,
+                        &api_key,
+                        Some(OrderType::Gtc),
+                    ));
+                }
+                Err(e) => results[index] = Some(Err(e)),
+            }
+        }
+
+        let mut retries = vec![];
+
+        for (index, result) in indices
+            .into_iter()
+            .zip(place_orders(account, order_requests).await)
+        {
+            match result {
+                Ok(response) => {
+                    response.log_successful_placement(Side::Sell, &account.proxy_address);
+                    results[index] = Some(Ok(response));
+                }
+                Err(e) if remedy_for(&e, attempt) == Some(ClobRemedy::Retry) => {
+                    tracing::warn!("{} | {e}", account.proxy_address);
+                    retries.push(index);
+                }
+                Err(e) => results[index] = Some(Err(e.into())),
+            }
+        }
+
+        if !retries.is_empty() {
+            tracing::warn!(
+                "{} | Retrying {} orders, attempt {}/{MAX_ORDER_ATTEMPTS} in {} seconds",
+                account.proxy_address,
+                retries.len(),
+                attempt + 1,
+                ORDER_RETRY_DELAY.as_secs()
+            );
+            tokio::time::sleep(ORDER_RETRY_DELAY).await;
+        }
+
+        pending = retries;
+        attempt += 1;
+    }
+
+    Ok(results.into_iter().map(Option::unwrap).collect())
+}
+
 async fn build_market_buy_signed_order_for_account(
     account: &Account,
     token_id: &str,
-    event: &Event,
     amount_in: f64,
-    tick_size: TickSize,
 ) -> eyre::Result<SignedOrder> {
-    let build_order_args = |price: f64,
-                            token_id: String,
-                            amount: f64,
-                            tick_size: TickSize,
-                            neg_risk: bool|
-     -> (CreateOrderOptions, UserMarketOrder) {
-        let order = UserMarketOrder::new(token_id, amount, Some(price), None, None, None);
-        let options = CreateOrderOptions::new(tick_size, Some(neg_risk));
-
-        (options, order)
-    };
-
     let proxy_wallet_address = account.get_proxy_address().to_string();
     let proxy = account.proxy();
 
+    let metadata = get_market_metadata(token_id, proxy.as_ref()).await?;
     let order_book = get_order_book(token_id, proxy.as_ref()).await?;
     let market_price = calculate_market_price(Side::Buy, order_book, amount_in, None);
+    validate_order_size(&Side::Buy, amount_in, market_price, &metadata)?;
 
     let order_builder = OrderBuilder::new(account.signer(), 137, None, Some(&proxy_wallet_address));
 
-    let neg_risk = event
-        .neg_risk
-        .unwrap_or(get_neg_risk(token_id, proxy.as_ref()).await?);
-
-    let (order_options, order) = build_order_args(
-        market_price,
+    let order = UserMarketOrder::new(
         token_id.to_string(),
         amount_in,
-        tick_size,
-        neg_risk,
+        Some(market_price),
+        None,
+        None,
+        None,
     );
+    let order_options = CreateOrderOptions::from_metadata(&metadata);
 
     let signed_order = order_builder
         .build_signed_market_buy_order(order, order_options)
@@ -310,7 +364,6 @@ async fn build_market_buy_signed_order_for_account(
 async fn build_market_sell_signed_order_for_account(
     account: &Account,
     token_id: &str,
-    tick_size: TickSize,
 ) -> eyre::Result<SignedOrder> {
     let proxy = account.proxy();
     let proxy_wallet_address = account.get_proxy_address().to_string();
@@ -320,8 +373,10 @@ async fn build_market_sell_signed_order_for_account(
         wait_for_matching_user_position(&proxy_wallet_address, proxy.clone(), token_id, None)
             .await?;
 
+    let metadata = get_market_metadata(token_id, proxy.as_ref()).await?;
     let order_book = get_order_book(token_id, proxy.as_ref()).await?;
     let market_price = calculate_market_price(Side::Sell, order_book, position.size, None);
+    validate_order_size(&Side::Sell, position.size, market_price, &metadata)?;
 
     let order = UserOrder::default()
         .with_token_id(token_id)
@@ -330,7 +385,7 @@ async fn build_market_sell_signed_order_for_account(
         .with_size(position.size)
         .with_taker(Address::ZERO.to_string());
 
-    let order_options = CreateOrderOptions::new(tick_size, Some(position.negative_risk));
+    let order_options = CreateOrderOptions::from_metadata(&metadata);
 
     let signed_order = order_builder
         .build_signed_order(order, order_options)
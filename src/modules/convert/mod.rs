@@ -0,0 +1,149 @@
+use std::{str::FromStr, sync::Arc};
+
+use alloy::primitives::{Address, U256};
+use alloy_chains::NamedChain;
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use itertools::Itertools;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    config::Config,
+    db::database::Database,
+    onchain::{
+        client::EvmClient,
+        dex::{quote_swap, swap_exact_tokens},
+        multicall::multicall_balance_of,
+        rpc::PolygonProvider,
+        types::{gas::GasSettings, token::Token},
+    },
+    utils::misc::pretty_sleep,
+};
+
+#[derive(Tabled)]
+struct WalletBalancesRow {
+    #[tabled(rename = "Wallet")]
+    address: String,
+    #[tabled(rename = "POL")]
+    pol: String,
+    #[tabled(rename = "USDC")]
+    usdc: String,
+    #[tabled(rename = "USDC.e")]
+    usdce: String,
+    #[tabled(rename = "Quote")]
+    quote: String,
+}
+
+pub async fn convert_stablecoins(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let router = Address::from_str(&config.dex_router_address)?;
+
+    let direction = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Convert:")
+        .items(&["USDC -> USDC.e", "USDC.e -> USDC", "Back"])
+        .default(0)
+        .interact()?;
+
+    let (from, to) = match direction {
+        0 => (Token::USDC, Token::USDCE),
+        1 => (Token::USDCE, Token::USDC),
+        _ => return Ok(()),
+    };
+
+    let addresses =
+        db.0.iter()
+            .map(|account| account.signer().address())
+            .collect_vec();
+
+    let (pol_balances, usdc_balances, usdce_balances) = tokio::try_join!(
+        multicall_balance_of(&addresses, Token::POL, provider.clone()),
+        multicall_balance_of(&addresses, Token::USDC, provider.clone()),
+        multicall_balance_of(&addresses, Token::USDCE, provider.clone())
+    )?;
+
+    let mut rows = vec![];
+    let mut swaps = vec![];
+
+    for (i, account) in db.0.iter().enumerate() {
+        let amount_in = match from == Token::USDC {
+            true => usdc_balances[i],
+            false => usdce_balances[i],
+        };
+
+        let quote = match amount_in.is_zero() {
+            true => "-".to_string(),
+            false if pol_balances[i].is_zero() => "no POL for gas".to_string(),
+            false => match quote_swap(provider.clone(), router, &from, &to, amount_in).await {
+                Ok(amount_out) => {
+                    swaps.push((account, amount_in));
+                    format!("{} {}", to.to_ui_amount(amount_out), to.symbol)
+                }
+                Err(e) => {
+                    tracing::error!("{} | Failed to quote the swap: {e}", addresses[i]);
+                    "no quote, skipped".to_string()
+                }
+            },
+        };
+
+        rows.push(WalletBalancesRow {
+            address: addresses[i].to_string(),
+            pol: Token::POL.to_ui_amount(pol_balances[i]),
+            usdc: Token::USDC.to_ui_amount(usdc_balances[i]),
+            usdce: Token::USDCE.to_ui_amount(usdce_balances[i]),
+            quote,
+        });
+    }
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+
+    if swaps.is_empty() {
+        tracing::warn!("No wallets hold {} to convert", from.symbol);
+        return Ok(());
+    }
+
+    let total = swaps.iter().map(|(_, amount)| *amount).sum::<U256>();
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Convert {} {} to {} across {} wallets?",
+            from.to_ui_amount(total),
+            from.symbol,
+            to.symbol,
+            swaps.len()
+        ))
+        .default(false)
+        .interact()?;
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    for (account, amount_in) in swaps {
+        let client = EvmClient::new(
+            provider.clone(),
+            account.get_private_key(),
+            NamedChain::Polygon,
+        )
+        .with_gas_settings(GasSettings::from(config));
+
+        if let Err(e) = swap_exact_tokens(
+            &client,
+            router,
+            &from,
+            &to,
+            amount_in,
+            config.swap_slippage_percent,
+        )
+        .await
+        {
+            tracing::error!("{} | Conversion failed: {e}", client.address());
+        }
+
+        pretty_sleep(config.deposit_sleep_range).await;
+    }
+
+    Ok(())
+}
@@ -1,30 +1,60 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use alloy::{
     network::Ethereum,
-    primitives::{utils::format_units, U256},
-    providers::{Provider, ProviderBuilder},
+    primitives::{utils::format_units, Address, U256},
+    providers::Provider,
     transports::Transport,
 };
 use alloy_chains::NamedChain;
-use reqwest::Url;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use itertools::Itertools;
+use tracing::Instrument;
 
 use crate::{
     config::Config,
     db::{account::Account, database::Database},
-    onchain::{client::EvmClient, types::token::Token},
+    onchain::{
+        client::EvmClient,
+        dex::swap_exact_tokens,
+        multicall::multicall_balance_of,
+        rpc::PolygonProvider,
+        types::{gas::GasSettings, token::Token},
+    },
     utils::misc::{pretty_sleep, random_in_range},
 };
 
-pub async fn deposit_to_accounts(mut db: Database, config: &Config) -> eyre::Result<()> {
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_http(Url::parse(&config.polygon_rpc_url)?),
-    );
+// Covers an approve and a swap from native USDC on top of the USDC.e transfer itself
+const DEPOSIT_GAS_UNITS: u128 = 300_000;
+
+pub async fn deposit_to_accounts(
+    mut db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let underfunded = preflight_gas_balances(&db, provider.clone(), config).await?;
+
+    if !underfunded.is_empty() {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Skip {} wallets without enough POL for gas and continue?",
+                underfunded.len()
+            ))
+            .default(false)
+            .interact()?;
 
-    while let Some(account) = db.get_random_account_with_filter(|a| !a.get_funded()) {
-        process_account(provider.clone(), account, config).await?;
+        if !confirmed {
+            return Ok(());
+        }
+    }
+
+    while let Some(account) = db.get_random_account_with_filter(|a| {
+        !a.get_funded() && !underfunded.contains(&a.proxy_address)
+    }) {
+        let span = account.span();
+        process_account(provider.clone(), account, config)
+            .instrument(span)
+            .await?;
         db.update();
 
         pretty_sleep(config.deposit_sleep_range).await;
@@ -33,6 +63,50 @@ pub async fn deposit_to_accounts(mut db: Database, config: &Config) -> eyre::Res
     Ok(())
 }
 
+// Proxy addresses of unfunded accounts whose EOA can't cover a worst-case deposit at the capped gas price
+async fn preflight_gas_balances<P, T>(
+    db: &Database,
+    provider: Arc<P>,
+    config: &Config,
+) -> eyre::Result<Vec<String>>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let accounts = db.0.iter().filter(|a| !a.get_funded()).collect_vec();
+    let addresses = accounts
+        .iter()
+        .map(|account| account.signer().address())
+        .collect_vec();
+
+    let (balances, eip1559_fees) = tokio::try_join!(
+        multicall_balance_of(&addresses, Token::POL, provider.clone()),
+        async { Ok(provider.estimate_eip1559_fees(None).await?) }
+    )?;
+
+    let (max_fee_per_gas, _) = GasSettings::from(config).cap_fees(
+        eip1559_fees.max_fee_per_gas,
+        eip1559_fees.max_priority_fee_per_gas,
+    );
+    let required = U256::from(DEPOSIT_GAS_UNITS * max_fee_per_gas);
+
+    let underfunded = accounts
+        .iter()
+        .zip(addresses.iter().zip(balances))
+        .filter(|(_, (_, balance))| *balance < required)
+        .map(|(account, (address, balance))| {
+            tracing::warn!(
+                "Wallet `{address}` holds {} POL, a deposit needs up to {} POL for gas",
+                Token::POL.to_ui_amount(balance),
+                Token::POL.to_ui_amount(required)
+            );
+            account.proxy_address.clone()
+        })
+        .collect_vec();
+
+    Ok(underfunded)
+}
+
 async fn process_account<P, T>(
     provider: Arc<P>,
     account: &mut Account,
@@ -50,7 +124,8 @@ where
         provider.clone(),
         account.get_private_key(),
         NamedChain::Polygon,
-    );
+    )
+    .with_gas_settings(GasSettings::from(config));
 
     tracing::info!(
         "Wallet address: `{}`. Proxy wallet address: `{}`",
@@ -58,13 +133,50 @@ where
         proxy_wallet_address
     );
 
-    let (proxy_wallet_balance, wallet_balance) = tokio::try_join!(
+    let (proxy_wallet_balance, mut wallet_balance, native_usdc_balance) = tokio::try_join!(
         client.get_token_balance(&token, Some(proxy_wallet_address)),
-        client.get_token_balance(&token, None)
+        client.get_token_balance(&token, None),
+        client.get_token_balance(&Token::USDC, None)
     )?;
 
     let mut value = token.to_wei(amount);
 
+    // Polymarket only takes USDC.e, so cover a shortfall from the native USDC the wallet holds
+    if value > wallet_balance && native_usdc_balance > U256::ZERO {
+        let shortfall = (value - wallet_balance).min(native_usdc_balance);
+
+        match config.convert_native_usdc_on_deposit {
+            true => {
+                let router = Address::from_str(&config.dex_router_address)?;
+                swap_exact_tokens(
+                    &client,
+                    router,
+                    &Token::USDC,
+                    &token,
+                    shortfall,
+                    config.swap_slippage_percent,
+                )
+                .await?;
+
+                wallet_balance = client.get_token_balance(&token, None).await?;
+            }
+            false => tracing::warn!(
+                "Wallet holds {} native {}, which Polymarket doesn't accept. Convert it to {} first",
+                Token::USDC.to_ui_amount(native_usdc_balance),
+                Token::USDC,
+                token
+            ),
+        }
+    }
+
+    if wallet_balance.is_zero() {
+        eyre::bail!(
+            "Wallet `{}` holds no {} to deposit",
+            client.address(),
+            token
+        );
+    }
+
     if value > wallet_balance {
         value = wallet_balance;
     }
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use itertools::Itertools;
+use reqwest::Proxy;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    db::database::Database,
+    modules::bets::opposing::{
+        create_and_place_buy_market_order, create_and_place_sell_market_order,
+    },
+    polymarket::api::{
+        clob::{
+            analytics::BookAnalytics,
+            endpoints::{get_order_book, get_order_books},
+            math::{calculate_fee, calculate_market_price},
+            metadata::{get_market_metadata, MarketMetadata},
+            schemas::OrderBookData,
+            typedefs::Side,
+            validation::validate_order_size,
+        },
+        events::{
+            endpoints::search_events,
+            schemas::{Event, EventsOrder, EventsQuery},
+        },
+        user::endpoints::get_user_positions,
+    },
+};
+
+const PAGE_SIZE: u64 = 50;
+const MAX_RESULTS: usize = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NegRiskFilter {
+    Any,
+    Only,
+    Exclude,
+}
+
+struct MarketFilters {
+    text: Option<String>,
+    neg_risk: NegRiskFilter,
+    query: EventsQuery,
+}
+
+impl MarketFilters {
+    fn matches(&self, event: &Event) -> bool {
+        let text_matches = self.text.as_ref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            event.title.to_lowercase().contains(&text)
+                || event
+                    .markets
+                    .iter()
+                    .any(|market| market.question.to_lowercase().contains(&text))
+        });
+
+        let neg_risk = event.neg_risk.unwrap_or(false);
+        let neg_risk_matches = match self.neg_risk {
+            NegRiskFilter::Any => true,
+            NegRiskFilter::Only => neg_risk,
+            NegRiskFilter::Exclude => !neg_risk,
+        };
+
+        text_matches && neg_risk_matches
+    }
+}
+
+#[derive(Tabled)]
+struct EventRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Markets")]
+    markets: usize,
+    #[tabled(rename = "Volume")]
+    volume: String,
+    #[tabled(rename = "24h volume")]
+    volume24hr: String,
+    #[tabled(rename = "Liquidity")]
+    liquidity: String,
+    #[tabled(rename = "End date")]
+    end_date: String,
+    #[tabled(rename = "Neg risk")]
+    neg_risk: bool,
+}
+
+#[derive(Tabled)]
+struct OutcomeRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "Market")]
+    question: String,
+    #[tabled(rename = "Outcome")]
+    outcome: String,
+    #[tabled(rename = "Bid")]
+    bid: String,
+    #[tabled(rename = "Ask")]
+    ask: String,
+    #[tabled(rename = "Mid")]
+    mid: String,
+}
+
+#[derive(Tabled)]
+struct ProbabilityRow {
+    #[tabled(rename = "Outcome")]
+    outcome: String,
+    #[tabled(rename = "Implied probability")]
+    probability: String,
+}
+
+#[derive(Tabled)]
+struct OrderPreviewRow {
+    #[tabled(rename = "Side")]
+    side: String,
+    #[tabled(rename = "Shares")]
+    size: String,
+    #[tabled(rename = "Price")]
+    price: String,
+    #[tabled(rename = "Notional")]
+    notional: String,
+    #[tabled(rename = "Expected fee")]
+    fee: String,
+    #[tabled(rename = "Receive")]
+    receive: String,
+}
+
+struct OutcomeChoice {
+    token_id: String,
+    label: String,
+}
+
+pub async fn browse_markets(db: Database) -> eyre::Result<()> {
+    let proxy = db.0.first().and_then(|account| account.proxy());
+
+    let filters = prompt_filters()?;
+    let events = fetch_matching_events(&filters, proxy.as_ref()).await?;
+
+    if events.is_empty() {
+        tracing::warn!("No markets match the selected filters");
+        return Ok(());
+    }
+
+    print_events(&events);
+
+    let mut items = events.iter().map(|event| event.title.clone()).collect_vec();
+    items.push("Back".to_string());
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Event:")
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    let Some(event) = events.get(selection) else {
+        return Ok(());
+    };
+
+    let outcomes = display_event_outcomes(event, proxy.as_ref()).await?;
+
+    let place_order = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Place an order on this event?")
+        .default(false)
+        .interact()?;
+
+    if place_order {
+        place_order_from_browser(db, &outcomes).await?;
+    }
+
+    Ok(())
+}
+
+fn prompt_filters() -> eyre::Result<MarketFilters> {
+    let theme = ColorfulTheme::default();
+
+    let text: String = Input::with_theme(&theme)
+        .with_prompt("Search text (empty for any):")
+        .allow_empty(true)
+        .interact_text()?;
+
+    let tag_slug: String = Input::with_theme(&theme)
+        .with_prompt("Tag or category slug, e.g. politics (empty for any):")
+        .allow_empty(true)
+        .interact_text()?;
+
+    let ends_before: String = Input::with_theme(&theme)
+        .with_prompt("Ends before, YYYY-MM-DD (empty for any):")
+        .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), String> {
+            if input.is_empty() || NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok() {
+                Ok(())
+            } else {
+                Err("Expected a date in YYYY-MM-DD format".to_string())
+            }
+        })
+        .interact_text()?;
+
+    let liquidity_min: f64 = Input::with_theme(&theme)
+        .with_prompt("Min liquidity:")
+        .default(0.0)
+        .interact_text()?;
+
+    let volume_min: f64 = Input::with_theme(&theme)
+        .with_prompt("Min volume:")
+        .default(0.0)
+        .interact_text()?;
+
+    let neg_risk = match Select::with_theme(&theme)
+        .with_prompt("Neg risk events:")
+        .items(&["Any", "Only neg risk", "Exclude neg risk"])
+        .default(0)
+        .interact()?
+    {
+        1 => NegRiskFilter::Only,
+        2 => NegRiskFilter::Exclude,
+        _ => NegRiskFilter::Any,
+    };
+
+    let order = EventsOrder::ALL[Select::with_theme(&theme)
+        .with_prompt("Sort by:")
+        .items(&EventsOrder::ALL)
+        .default(0)
+        .interact()?];
+
+    let ascending = Select::with_theme(&theme)
+        .with_prompt("Order:")
+        .items(&["Descending", "Ascending"])
+        .default(0)
+        .interact()?
+        == 1;
+
+    let mut query = EventsQuery::default()
+        .with_limit(PAGE_SIZE)
+        .with_order(order, ascending)
+        .with_end_date_range(
+            None,
+            (!ends_before.is_empty()).then(|| format!("{ends_before}T00:00:00Z")),
+        );
+
+    if !tag_slug.trim().is_empty() {
+        query = query.with_tag_slug(tag_slug.trim());
+    }
+
+    if liquidity_min > 0.0 {
+        query = query.with_liquidity_min(liquidity_min);
+    }
+
+    if volume_min > 0.0 {
+        query = query.with_volume_min(volume_min);
+    }
+
+    Ok(MarketFilters {
+        text: (!text.trim().is_empty()).then(|| text.trim().to_string()),
+        neg_risk,
+        query,
+    })
+}
+
+async fn fetch_matching_events(
+    filters: &MarketFilters,
+    proxy: Option<&Proxy>,
+) -> eyre::Result<Vec<Event>> {
+    let mut query = filters.query.clone();
+    let mut matching_events = vec![];
+    let mut scanned = 0;
+
+    tracing::info!("Searching markets");
+
+    // Gamma can't search by text, so pages are filtered here until the API runs out of events
+    loop {
+        let events = search_events(&query, proxy).await?;
+        let exhausted = (events.len() as u64) < query.limit;
+        scanned += events.len();
+
+        matching_events.extend(events.into_iter().filter(|event| filters.matches(event)));
+
+        if matching_events.len() >= MAX_RESULTS {
+            matching_events.truncate(MAX_RESULTS);
+            break;
+        }
+
+        if exhausted {
+            break;
+        }
+
+        let offset = query.offset + PAGE_SIZE;
+        query = query.with_offset(offset);
+    }
+
+    tracing::info!(
+        "Found {} matching events out of {scanned} scanned",
+        matching_events.len()
+    );
+
+    Ok(matching_events)
+}
+
+fn print_events(events: &[Event]) {
+    let fmt_opt = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.0}"));
+
+    let rows = events
+        .iter()
+        .enumerate()
+        .map(|(index, event)| EventRow {
+            index,
+            title: event.title.clone(),
+            markets: event.markets.len(),
+            volume: format!("{:.0}", event.volume),
+            volume24hr: fmt_opt(event.volume24hr),
+            liquidity: fmt_opt(event.liquidity),
+            end_date: event
+                .end_date
+                .as_deref()
+                .map_or("-".to_string(), |date| date.chars().take(10).collect()),
+            neg_risk: event.neg_risk.unwrap_or(false),
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+async fn display_event_outcomes(
+    event: &Event,
+    proxy: Option<&Proxy>,
+) -> eyre::Result<Vec<OutcomeChoice>> {
+    let token_ids = event
+        .tradable_markets()
+        .flat_map(|market| market.clob_token_ids.iter())
+        .filter(|token_id| !token_id.is_empty())
+        .map(|token_id| token_id.as_str())
+        .collect_vec();
+
+    let books: HashMap<String, OrderBookData> = match token_ids.is_empty() {
+        true => HashMap::new(),
+        false => get_order_books(&token_ids, proxy)
+            .await?
+            .into_iter()
+            .map(|book| (book.asset_id.clone(), book))
+            .collect(),
+    };
+
+    let fmt_opt = |value: Option<f64>| value.map_or("-".to_string(), |v| v.to_string());
+
+    let mut rows = vec![];
+    let mut choices = vec![];
+
+    for market in event.tradable_markets() {
+        for (outcome, token_id) in market.outcomes.iter().zip(market.clob_token_ids.iter()) {
+            let analytics = books.get(token_id).cloned().map(BookAnalytics::new);
+
+            rows.push(OutcomeRow {
+                index: choices.len(),
+                question: market.label().to_string(),
+                outcome: outcome.clone(),
+                bid: fmt_opt(
+                    analytics
+                        .as_ref()
+                        .and_then(|a| a.best_bid().map(|o| o.price)),
+                ),
+                ask: fmt_opt(
+                    analytics
+                        .as_ref()
+                        .and_then(|a| a.best_ask().map(|o| o.price)),
+                ),
+                mid: fmt_opt(analytics.as_ref().and_then(|a| a.midpoint())),
+            });
+
+            choices.push(OutcomeChoice {
+                token_id: token_id.clone(),
+                label: format!("{} - {}", market.label(), outcome),
+            });
+        }
+    }
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+
+    if event.is_multi_outcome() {
+        print_implied_probabilities(event);
+    }
+
+    Ok(choices)
+}
+
+fn print_implied_probabilities(event: &Event) {
+    let rows = event
+        .implied_probabilities()
+        .into_iter()
+        .sorted_by(|a, b| b.1.total_cmp(&a.1))
+        .map(|(label, price)| ProbabilityRow {
+            outcome: label.to_string(),
+            probability: format!("{:.1}%", price * 100.0),
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+    println!(
+        "Implied probabilities sum to {:.1}%",
+        event.implied_probability_sum() * 100.0
+    );
+}
+
+async fn place_order_from_browser(db: Database, outcomes: &[OutcomeChoice]) -> eyre::Result<()> {
+    let theme = ColorfulTheme::default();
+
+    if outcomes.is_empty() || db.0.is_empty() {
+        tracing::warn!("Nothing to trade");
+        return Ok(());
+    }
+
+    let outcome_index = Select::with_theme(&theme)
+        .with_prompt("Outcome:")
+        .items(&outcomes.iter().map(|o| o.label.as_str()).collect_vec())
+        .default(0)
+        .interact()?;
+    let outcome = &outcomes[outcome_index];
+
+    let account_index = Select::with_theme(&theme)
+        .with_prompt("Account:")
+        .items(&db.0.iter().map(|a| a.proxy_address.as_str()).collect_vec())
+        .default(0)
+        .interact()?;
+    let account = &db.0[account_index];
+
+    let is_buy = Select::with_theme(&theme)
+        .with_prompt("Side:")
+        .items(&["Buy", "Sell entire position"])
+        .default(0)
+        .interact()?
+        == 0;
+
+    let proxy = account.proxy();
+    let metadata = get_market_metadata(&outcome.token_id, proxy.as_ref()).await?;
+    let book = get_order_book(&outcome.token_id, proxy.as_ref()).await?;
+
+    // Buys are sized in USDC.e, sells in shares
+    let (side, amount) = match is_buy {
+        true => {
+            let amount: f64 = Input::with_theme(&theme)
+                .with_prompt("Amount (USDC):")
+                .interact_text()?;
+
+            (Side::Buy, amount)
+        }
+        false => {
+            let size = get_user_positions(&account.proxy_address, proxy.as_ref())
+                .await?
+                .into_iter()
+                .find(|position| position.asset == outcome.token_id)
+                .map_or(0.0, |position| position.size);
+
+            (Side::Sell, size)
+        }
+    };
+
+    if amount <= 0.0 {
+        tracing::warn!("Nothing to trade");
+        return Ok(());
+    }
+
+    let price = calculate_market_price(side.clone(), book, amount, None);
+
+    if price <= 0.0 {
+        tracing::warn!("No liquidity to fill the order");
+        return Ok(());
+    }
+
+    let size = match validate_order_size(&side, amount, price, &metadata) {
+        Ok(size) => size,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return Ok(());
+        }
+    };
+
+    print_order_preview(&side, size, price, &metadata);
+
+    let confirmed = Confirm::with_theme(&theme)
+        .with_prompt("Place the order?")
+        .default(true)
+        .interact()?;
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    match side {
+        Side::Buy => {
+            create_and_place_buy_market_order(account, &outcome.token_id, amount).await?;
+        }
+        Side::Sell => {
+            create_and_place_sell_market_order(account, &outcome.token_id).await?;
+        }
+    }
+
+    db.update();
+
+    Ok(())
+}
+
+// Buy fees are taken in shares and sell fees in USDC.e, both at the fee rate of the market
+fn print_order_preview(side: &Side, size: f64, price: f64, metadata: &MarketMetadata) {
+    let notional = size * price;
+    let fee = calculate_fee(metadata.fee_rate_bps, price, size);
+
+    let row = OrderPreviewRow {
+        side: side.to_string(),
+        size: format!("{size:.2}"),
+        price: format!("{price}"),
+        notional: format!("{notional:.2} USDC.e"),
+        fee: format!("{fee:.4} USDC.e ({} bps)", metadata.fee_rate_bps),
+        receive: match side {
+            Side::Buy => format!("{:.2} shares", size - fee / price),
+            Side::Sell => format!("{:.2} USDC.e", notional - fee),
+        },
+    };
+
+    let mut table = Table::new([row]);
+    println!("{}", table.with(Style::modern_rounded()));
+}
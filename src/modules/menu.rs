@@ -5,10 +5,20 @@ use super::{
 use crate::{
     config::Config,
     db::database::Database,
-    modules::{sell::sell_all::sell_all_open_positions, withdraw::withdraw_for_all},
+    modules::{
+        audit::audit_wallets, backtest::run_backtests, convert::convert_stablecoins,
+        markets::browse_markets, order_book::display_order_book, positions::manage_positions,
+        recorder::record_market_data, relayer_tracker::track_relayer_transactions,
+        resolution_watch::watch_resolutions, retire::retire_wallet,
+        sell::sell_all::sell_all_open_positions, strategy_runner::run_configured_strategy,
+        withdraw::withdraw_for_all,
+    },
+    onchain::rpc::FailoverTransport,
+    utils::metrics::serve_metrics,
 };
 use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Select};
+use std::{sync::Arc, time::Duration};
 
 const LOGO: &str = r#"
     ___                                                  __
@@ -29,7 +39,7 @@ const LOGO: &str = r#"
                      t.me/fragment_software
 "#;
 
-pub async fn menu() -> eyre::Result<()> {
+pub async fn menu(config: Config) -> eyre::Result<()> {
     async fn read_or_create_db() -> eyre::Result<Database> {
         match Database::read().await {
             Ok(db) => Ok(db),
@@ -37,11 +47,25 @@ pub async fn menu() -> eyre::Result<()> {
         }
     }
 
-    let config = Config::read_default().await;
     let logo = LOGO.red();
 
     println!("{logo}");
 
+    if !config.metrics_listen_address.is_empty() {
+        if let Err(e) = serve_metrics(&config.metrics_listen_address).await {
+            tracing::warn!("Metrics endpoint disabled: {e}");
+        }
+    }
+
+    let transport =
+        FailoverTransport::new(&config.polygon_rpc_urls, config.rpc_max_head_lag_blocks)?;
+    transport.check_health().await;
+    transport.print_health();
+    transport.spawn_health_checks(Duration::from_secs(config.rpc_health_check_interval_secs));
+
+    // Shared by every module so endpoint health is tracked across the whole session
+    let provider = Arc::new(transport.into_provider());
+
     loop {
         let options = vec![
             "Accounts registration",
@@ -50,6 +74,17 @@ pub async fn menu() -> eyre::Result<()> {
             "Proxy wallets stats check",
             "Sell all open positions",
             "Withdraw",
+            "Order book analytics",
+            "Market browser",
+            "Positions",
+            "Relayer transactions",
+            "Convert USDC <-> USDC.e",
+            "Balance & approval audit",
+            "Retire wallet",
+            "Market data recorder",
+            "Backtest strategies",
+            "Run strategy",
+            "Resolution watcher",
             "Exit",
         ];
 
@@ -63,31 +98,74 @@ pub async fn menu() -> eyre::Result<()> {
         match selection {
             0 => {
                 let db = Database::new().await?;
-                register_accounts(db, &config).await?;
+                register_accounts(db, &config, provider.clone()).await?;
             }
             1 => {
                 let db = Database::new().await?;
-                deposit_to_accounts(db, &config).await?;
+                deposit_to_accounts(db, &config, provider.clone()).await?;
             }
             2 => {
                 let mut db = read_or_create_db().await?;
                 db.shuffle();
 
-                opposing_bets(db, &config).await?;
+                opposing_bets(db, &config, provider.clone()).await?;
             }
             3 => {
                 let db = read_or_create_db().await?;
-                check_and_display_stats(db, &config).await?;
+                check_and_display_stats(db, &config, provider.clone()).await?;
             }
             4 => {
                 let db = read_or_create_db().await?;
-                sell_all_open_positions(db, &config).await?;
+                sell_all_open_positions(db, &config, provider.clone()).await?;
             }
             5 => {
-                let mut db = read_or_create_db().await?;
-                withdraw_for_all(&mut db, &config).await?;
+                let db = read_or_create_db().await?;
+                withdraw_for_all(&db, &config, provider.clone()).await?;
             }
             6 => {
+                let db = read_or_create_db().await?;
+                display_order_book(db, &config).await?;
+            }
+            7 => {
+                let db = read_or_create_db().await?;
+                browse_markets(db).await?;
+            }
+            8 => {
+                let db = read_or_create_db().await?;
+                manage_positions(db, provider.clone()).await?;
+            }
+            9 => {
+                let db = read_or_create_db().await?;
+                track_relayer_transactions(db, &config, provider.clone()).await?;
+            }
+            10 => {
+                let db = read_or_create_db().await?;
+                convert_stablecoins(db, &config, provider.clone()).await?;
+            }
+            11 => {
+                let db = read_or_create_db().await?;
+                audit_wallets(db, &config, provider.clone()).await?;
+            }
+            12 => {
+                let db = read_or_create_db().await?;
+                retire_wallet(db, &config, provider.clone()).await?;
+            }
+            13 => {
+                let db = read_or_create_db().await?;
+                record_market_data(db, &config).await?;
+            }
+            14 => {
+                run_backtests(&config).await?;
+            }
+            15 => {
+                let db = read_or_create_db().await?;
+                run_configured_strategy(db, &config, provider.clone()).await?;
+            }
+            16 => {
+                let db = read_or_create_db().await?;
+                watch_resolutions(db, &config).await?;
+            }
+            17 => {
                 return Ok(());
             }
             _ => tracing::error!("Invalid selection"),
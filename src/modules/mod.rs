@@ -1,7 +1,18 @@
+mod audit;
+mod backtest;
 mod bets;
+mod convert;
 mod deposit;
+mod markets;
 pub mod menu;
-mod registration;
+mod order_book;
+mod positions;
+mod recorder;
+pub mod registration;
+mod relayer_tracker;
+pub mod resolution_watch;
+mod retire;
 mod sell;
 mod stats_check;
+mod strategy_runner;
 mod withdraw;
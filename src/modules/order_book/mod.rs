@@ -0,0 +1,145 @@
+use dialoguer::{theme::ColorfulTheme, Input};
+use itertools::{EitherOrBoth, Itertools};
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    config::Config,
+    db::database::Database,
+    polymarket::api::clob::{
+        analytics::{BookAnalytics, FillEstimate},
+        endpoints::get_order_book,
+        typedefs::Side,
+    },
+};
+
+const LEVELS_TO_DISPLAY: usize = 10;
+const DEPTH_CURVE_STEPS: [f64; 5] = [1.0, 2.0, 5.0, 10.0, 20.0];
+
+#[derive(Tabled)]
+struct BookRow {
+    #[tabled(rename = "Bid size")]
+    bid_size: String,
+    #[tabled(rename = "Bid")]
+    bid_price: String,
+    #[tabled(rename = "Ask")]
+    ask_price: String,
+    #[tabled(rename = "Ask size")]
+    ask_size: String,
+}
+
+#[derive(Tabled)]
+struct DepthRow {
+    #[tabled(rename = "Within")]
+    within: String,
+    #[tabled(rename = "Bid size")]
+    bid_size: String,
+    #[tabled(rename = "Bid value")]
+    bid_value: String,
+    #[tabled(rename = "Ask size")]
+    ask_size: String,
+    #[tabled(rename = "Ask value")]
+    ask_value: String,
+}
+
+pub async fn display_order_book(db: Database, config: &Config) -> eyre::Result<()> {
+    let proxy = db.0.first().and_then(|account| account.proxy());
+
+    let token_id: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Token ID:")
+        .interact_text()?;
+
+    let notional: f64 = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Notional to estimate fills for (USDC):")
+        .default(100.0)
+        .interact_text()?;
+
+    let order_book = get_order_book(token_id.trim(), proxy.as_ref()).await?;
+    let analytics = BookAnalytics::new(order_book);
+
+    print_book_ladder(&analytics);
+    print_depth_curve(&analytics);
+    print_summary(&analytics, notional, config.book_imbalance_range_cents);
+
+    Ok(())
+}
+
+fn print_book_ladder(analytics: &BookAnalytics) {
+    let rows = analytics
+        .bids
+        .iter()
+        .take(LEVELS_TO_DISPLAY)
+        .zip_longest(analytics.asks.iter().take(LEVELS_TO_DISPLAY))
+        .map(|pair| {
+            let (bid, ask) = match pair {
+                EitherOrBoth::Both(bid, ask) => (Some(bid), Some(ask)),
+                EitherOrBoth::Left(bid) => (Some(bid), None),
+                EitherOrBoth::Right(ask) => (None, Some(ask)),
+            };
+
+            BookRow {
+                bid_size: bid.map_or(String::new(), |b| format!("{:.2}", b.size)),
+                bid_price: bid.map_or(String::new(), |b| b.price.to_string()),
+                ask_price: ask.map_or(String::new(), |a| a.price.to_string()),
+                ask_size: ask.map_or(String::new(), |a| format!("{:.2}", a.size)),
+            }
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+fn print_depth_curve(analytics: &BookAnalytics) {
+    let bids = analytics.depth_curve(Side::Buy, &DEPTH_CURVE_STEPS);
+    let asks = analytics.depth_curve(Side::Sell, &DEPTH_CURVE_STEPS);
+
+    let rows = bids
+        .into_iter()
+        .zip(asks)
+        .map(|((cents, bid), (_, ask))| DepthRow {
+            within: format!("{cents}¢"),
+            bid_size: format!("{:.2}", bid.size),
+            bid_value: format!("${:.2}", bid.value),
+            ask_size: format!("{:.2}", ask.size),
+            ask_value: format!("${:.2}", ask.value),
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+fn print_summary(analytics: &BookAnalytics, notional: f64, imbalance_range_cents: f64) {
+    let fmt_opt = |value: Option<f64>| value.map_or("-".to_string(), |v| v.to_string());
+
+    println!("Midpoint: {}", fmt_opt(analytics.midpoint()));
+    println!("Microprice: {}", fmt_opt(analytics.microprice()));
+    println!("Spread: {}", fmt_opt(analytics.spread()));
+    println!(
+        "Imbalance within {imbalance_range_cents}¢: {}",
+        fmt_opt(analytics.imbalance(imbalance_range_cents))
+    );
+
+    for side in [Side::Buy, Side::Sell] {
+        match analytics.vwap(side.clone(), notional) {
+            Some(estimate) => println!("[{side}] ${notional}: {}", format_fill(&estimate)),
+            None => println!("[{side}] ${notional}: no liquidity"),
+        }
+    }
+}
+
+fn format_fill(estimate: &FillEstimate) -> String {
+    let mut line = format!(
+        "VWAP {} for {} shares, worst price {}, impact {}",
+        estimate.vwap,
+        estimate.filled_size,
+        estimate.worst_price,
+        estimate.impact.map_or("-".to_string(), |v| v.to_string())
+    );
+
+    if !estimate.fully_filled {
+        line.push_str(&format!(" (book only absorbs ${})", estimate.filled_value));
+    }
+
+    line
+}
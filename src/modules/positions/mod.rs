@@ -0,0 +1,505 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use alloy::primitives::{utils::parse_units, B256, U256};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use itertools::Itertools;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    db::{account::Account, database::Database},
+    onchain::{
+        constants::POLYGON_EXPLORER_TX_BASE_URL, neg_risk::get_neg_risk_fee_bips,
+        rpc::PolygonProvider,
+    },
+    polymarket::api::{
+        clob::math::ClobPrecision,
+        events::{
+            endpoints::get_event_by_slug,
+            schemas::{Event, Market},
+        },
+        relayer::{
+            common::{convert_positions, merge_positions, redeem_positions},
+            endpoints::wait_for_transaction_confirmation,
+        },
+        typedefs::AmpCookie,
+        user::{endpoints::get_user_positions, schemas::UserPosition},
+    },
+};
+
+const NO_OUTCOME_INDEX: u8 = 1;
+
+#[derive(Tabled)]
+struct PositionRow {
+    #[tabled(rename = "Market")]
+    title: String,
+    #[tabled(rename = "Outcome")]
+    outcome: String,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Price")]
+    price: String,
+    #[tabled(rename = "Value")]
+    value: String,
+    #[tabled(rename = "Neg risk")]
+    neg_risk: bool,
+}
+
+pub struct ConversionPreview {
+    pub fee_bips: u64,
+    pub usdc_out: f64,
+    pub yes_received: Vec<String>,
+    pub no_burned: Vec<String>,
+}
+
+pub async fn manage_positions(db: Database, provider: Arc<PolygonProvider>) -> eyre::Result<()> {
+    let theme = ColorfulTheme::default();
+
+    if db.0.is_empty() {
+        tracing::warn!("No accounts found");
+        return Ok(());
+    }
+
+    let account_index = Select::with_theme(&theme)
+        .with_prompt("Account:")
+        .items(&db.0.iter().map(|a| a.proxy_address.as_str()).collect_vec())
+        .default(0)
+        .interact()?;
+    let account = &db.0[account_index];
+
+    let positions = get_user_positions(&account.proxy_address, account.proxy().as_ref()).await?;
+
+    if positions.is_empty() {
+        tracing::info!("{} has no open positions", account.proxy_address);
+        return Ok(());
+    }
+
+    print_positions(&positions);
+
+    let action = Select::with_theme(&theme)
+        .with_prompt("Action:")
+        .items(&["Convert NO positions (neg risk)", "Back"])
+        .default(0)
+        .interact()?;
+
+    if action == 0 {
+        convert_no_positions(account, &positions, provider).await?;
+    }
+
+    Ok(())
+}
+
+fn print_positions(positions: &[UserPosition]) {
+    let fmt_opt = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{v:.3}"));
+
+    let rows = positions
+        .iter()
+        .map(|position| PositionRow {
+            title: position.title.clone().unwrap_or(position.asset.clone()),
+            outcome: position.outcome.clone().unwrap_or_default(),
+            size: format!("{:.2}", position.size),
+            price: fmt_opt(position.cur_price),
+            value: fmt_opt(position.current_value),
+            neg_risk: position.negative_risk,
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+async fn convert_no_positions(
+    account: &Account,
+    positions: &[UserPosition],
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let theme = ColorfulTheme::default();
+    let proxy = account.proxy();
+
+    let no_positions_by_event = positions
+        .iter()
+        .filter(|position| {
+            position.negative_risk && position.outcome_index == Some(NO_OUTCOME_INDEX)
+        })
+        .filter_map(|position| Some((position.event_slug.clone()?, position)))
+        .into_group_map();
+
+    if no_positions_by_event.is_empty() {
+        tracing::warn!("No NO positions in neg risk events to convert");
+        return Ok(());
+    }
+
+    let event_slugs = no_positions_by_event.keys().sorted().collect_vec();
+
+    let slug_index = Select::with_theme(&theme)
+        .with_prompt("Event:")
+        .items(&event_slugs)
+        .default(0)
+        .interact()?;
+    let slug = event_slugs[slug_index];
+
+    let event = get_event_by_slug(slug, proxy.as_ref())
+        .await?
+        .ok_or_else(|| eyre::eyre!("Event `{slug}` not found"))?;
+
+    let market_id = event
+        .neg_risk_market_id
+        .as_deref()
+        .ok_or_else(|| eyre::eyre!("Event `{slug}` is not a neg risk event"))
+        .and_then(|id| Ok(B256::from_str(id)?))?;
+
+    let candidates = no_positions_by_event[slug]
+        .iter()
+        .filter_map(|position| {
+            let market = find_position_market(&event, position)?;
+            Some((*position, market, market.neg_risk_question_index()?))
+        })
+        .collect_vec();
+
+    if candidates.is_empty() {
+        tracing::warn!("Could not match positions to markets of `{slug}`");
+        return Ok(());
+    }
+
+    let selected = MultiSelect::with_theme(&theme)
+        .with_prompt("NO positions to convert:")
+        .items(
+            &candidates
+                .iter()
+                .map(|(position, market, _)| format!("{} ({:.2})", market.label(), position.size))
+                .collect_vec(),
+        )
+        .interact()?;
+
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let selected = selected.into_iter().map(|i| &candidates[i]).collect_vec();
+
+    let max_amount = selected
+        .iter()
+        .map(|(position, _, _)| position.size)
+        .fold(f64::MAX, f64::min)
+        .round_down(6);
+
+    let amount: f64 = Input::with_theme(&theme)
+        .with_prompt(format!(
+            "Shares of each position to convert (max {max_amount}):"
+        ))
+        .default(max_amount)
+        .validate_with(|amount: &f64| -> Result<(), String> {
+            match *amount > 0.0 && *amount <= max_amount {
+                true => Ok(()),
+                false => Err(format!("Expected an amount in (0, {max_amount}]")),
+            }
+        })
+        .interact_text()?;
+
+    let selected_markets = selected.iter().map(|(_, market, _)| *market).collect_vec();
+    let fee_bips = get_neg_risk_fee_bips(provider, market_id).await?;
+    let preview = preview_conversion(&event, &selected_markets, amount, fee_bips);
+    print_conversion_preview(&preview);
+
+    let confirmed = Confirm::with_theme(&theme)
+        .with_prompt("Submit conversion?")
+        .default(false)
+        .interact()?;
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    let index_set = selected.iter().fold(U256::ZERO, |set, (_, _, index)| {
+        set | (U256::from(1) << *index)
+    });
+    let raw_amount: U256 = parse_units(&format!("{amount:.6}"), 6)?.into();
+
+    let mut amp_cookie = AmpCookie::new();
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    tracing::info!("{} | Converting NO positions", account.proxy_address);
+
+    let tx_id = convert_positions(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        market_id,
+        index_set,
+        raw_amount,
+    )
+    .await?;
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Positions converted: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    Ok(())
+}
+
+pub async fn redeem(
+    account: &Account,
+    condition_id: &str,
+    positions: &[&UserPosition],
+) -> eyre::Result<()> {
+    let mut amp_cookie = AmpCookie::new();
+    let proxy = account.proxy();
+    let (polymarket_nonce, polymarket_session) = session(account)?;
+
+    // The adapter burns exact amounts per outcome, `size` is in whole shares with 6 decimals
+    let neg_risk_amounts = positions
+        .iter()
+        .any(|position| position.negative_risk)
+        .then(|| {
+            let amounts = positions
+                .iter()
+                .map(|position| {
+                    (
+                        position.outcome_index.unwrap_or_default(),
+                        U256::from((position.size * 1e6).floor() as u64),
+                    )
+                })
+                .collect::<HashMap<_, _>>();
+
+            [0, 1].map(|index| amounts.get(&index).copied().unwrap_or_default())
+        });
+
+    tracing::info!(
+        "{} | Redeeming {}",
+        account.proxy_address,
+        positions[0].title.as_deref().unwrap_or(condition_id)
+    );
+
+    let tx_id = redeem_positions(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        B256::from_str(condition_id)?,
+        neg_risk_amounts,
+    )
+    .await?;
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Redeemed: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    Ok(())
+}
+
+// Both outcomes of the condition have to be held, as much of them as the smaller one is merged
+pub async fn merge(
+    account: &Account,
+    condition_id: &str,
+    positions: &[&UserPosition],
+) -> eyre::Result<()> {
+    let mut amp_cookie = AmpCookie::new();
+    let proxy = account.proxy();
+    let (polymarket_nonce, polymarket_session) = session(account)?;
+
+    let size = positions
+        .iter()
+        .map(|position| position.size)
+        .fold(f64::INFINITY, f64::min);
+    let amount = U256::from((size * 1e6).floor() as u64);
+
+    if positions.len() < 2 || amount.is_zero() {
+        eyre::bail!("Both outcomes of {condition_id} have to be held to merge them");
+    }
+
+    tracing::info!(
+        "{} | Merging {size} shares of both outcomes of {}",
+        account.proxy_address,
+        positions[0].title.as_deref().unwrap_or(condition_id)
+    );
+
+    let tx_id = merge_positions(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        B256::from_str(condition_id)?,
+        amount,
+        positions.iter().any(|position| position.negative_risk),
+    )
+    .await?;
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Merged: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    Ok(())
+}
+
+fn session(account: &Account) -> eyre::Result<(&str, &str)> {
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    Ok((polymarket_nonce, polymarket_session))
+}
+
+fn find_position_market<'a>(event: &'a Event, position: &UserPosition) -> Option<&'a Market> {
+    event.markets.iter().find(|market| {
+        market.clob_token_ids.contains(&position.asset)
+            || (position.condition_id.is_some() && market.condition_id == position.condition_id)
+    })
+}
+
+// Converting NO on k of the event's n questions burns k NO sets and pays out
+// (k - 1) USDC per share plus one YES share on each of the remaining n - k questions.
+// The adapter keeps its fee of the converted amount before paying out either
+pub fn preview_conversion(
+    event: &Event,
+    selected: &[&Market],
+    amount: f64,
+    fee_bips: u64,
+) -> ConversionPreview {
+    let amount_out = amount * (1.0 - fee_bips as f64 / 10_000.0);
+    let usdc_out = (selected.len().saturating_sub(1)) as f64 * amount_out;
+
+    let yes_received = event
+        .markets
+        .iter()
+        .filter(|market| !selected.iter().any(|s| s.id == market.id))
+        .map(|market| market.label().to_string())
+        .collect_vec();
+
+    ConversionPreview {
+        fee_bips,
+        usdc_out,
+        yes_received,
+        no_burned: selected.iter().map(|m| m.label().to_string()).collect(),
+    }
+}
+
+fn print_conversion_preview(preview: &ConversionPreview) {
+    println!("Burns NO on: {}", preview.no_burned.join(", "));
+    println!(
+        "Receives: {:.2} USDC.e, after the adapter's {:.2}% fee",
+        preview.usdc_out,
+        preview.fee_bips as f64 / 100.0
+    );
+
+    match preview.yes_received.is_empty() {
+        true => println!("No YES positions are received"),
+        false => println!("Receives YES on: {}", preview.yes_received.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn market(id: &str, label: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "question": format!("Will {label} win?"),
+            "active": true,
+            "conditionId": format!("0x{id}"),
+            "groupItemTitle": label,
+            "negRisk": true,
+            "outcomes": "[\"Yes\", \"No\"]",
+            "rewardsMaxSpread": 3.5,
+            "clobTokenIds": format!("[\"{id}-yes\", \"{id}-no\"]"),
+            "spread": 0.01,
+            "orderPriceMinTickSize": 0.01,
+        })
+    }
+
+    fn event() -> Event {
+        serde_json::from_value(json!({
+            "id": "1",
+            "slug": "election",
+            "title": "Election",
+            "volume": 1000.0,
+            "markets": [market("1", "Alice"), market("2", "Bob"), market("3", "Carol")],
+            "negRisk": true,
+            "negRiskMarketID": "0xneg",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn conversion_without_fee_pays_a_dollar_per_extra_no() {
+        let event = event();
+        let selected = [&event.markets[0], &event.markets[1]];
+
+        let preview = preview_conversion(&event, &selected, 10.0, 0);
+
+        assert_eq!(preview.fee_bips, 0);
+        assert_eq!(preview.usdc_out, 10.0);
+        assert_eq!(preview.no_burned, ["Alice", "Bob"]);
+        assert_eq!(preview.yes_received, ["Carol"]);
+    }
+
+    #[test]
+    fn conversion_fee_is_taken_from_the_converted_amount() {
+        let event = event();
+        let selected = event.markets.iter().collect_vec();
+
+        // 2% fee
+        let preview = preview_conversion(&event, &selected, 10.0, 200);
+
+        assert_eq!(preview.fee_bips, 200);
+        assert!((preview.usdc_out - 2.0 * 9.8).abs() < 1e-9);
+        assert_eq!(preview.no_burned, ["Alice", "Bob", "Carol"]);
+        assert!(preview.yes_received.is_empty());
+    }
+
+    #[test]
+    fn converting_a_single_no_only_swaps_it_for_yes_on_the_rest() {
+        let event = event();
+        let selected = [&event.markets[2]];
+
+        let preview = preview_conversion(&event, &selected, 10.0, 100);
+
+        assert_eq!(preview.usdc_out, 0.0);
+        assert_eq!(preview.no_burned, ["Carol"]);
+        assert_eq!(preview.yes_received, ["Alice", "Bob"]);
+    }
+}
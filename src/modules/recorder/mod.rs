@@ -0,0 +1,212 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::Utc;
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect, Select};
+use itertools::Itertools;
+use reqwest::Proxy;
+
+use crate::{
+    config::Config,
+    db::{
+        constants::MARKET_DATA_FOLDER_PATH,
+        database::Database,
+        market_data::{reader::read_records, writer::PartitionedWriter, TradeRow},
+    },
+    polymarket::api::{
+        clob::endpoints::get_order_books,
+        events::endpoints::get_event_by_slug,
+        user::{endpoints::get_market_trades, schemas::MarketTrade},
+    },
+};
+
+const TRADES_PAGE_SIZE: u64 = 100;
+
+// Newest recorded trade timestamp of a token and the trades recorded at that exact millisecond
+#[derive(Default)]
+struct TradeCursor {
+    timestamp_ms: i64,
+    transaction_hashes: HashSet<String>,
+}
+
+impl TradeCursor {
+    fn accept(&mut self, trade: &TradeRow) -> bool {
+        if trade.timestamp_ms < self.timestamp_ms {
+            return false;
+        }
+
+        if trade.timestamp_ms > self.timestamp_ms {
+            self.timestamp_ms = trade.timestamp_ms;
+            self.transaction_hashes.clear();
+        }
+
+        self.transaction_hashes
+            .insert(trade.transaction_hash.clone())
+    }
+}
+
+pub async fn record_market_data(db: Database, config: &Config) -> eyre::Result<()> {
+    let proxy = db.0.first().and_then(|account| account.proxy());
+
+    let token_ids = prompt_token_ids("Record:", proxy.as_ref()).await?;
+
+    if token_ids.is_empty() {
+        tracing::warn!("No tokens selected");
+        return Ok(());
+    }
+
+    let mut writer = PartitionedWriter::new(MARKET_DATA_FOLDER_PATH, &config.recorder_formats)?;
+    let mut cursors = seed_trade_cursors(&token_ids)?;
+    let mut markets = HashSet::new();
+
+    let mut books_interval =
+        tokio::time::interval(Duration::from_secs(config.recorder_poll_interval_secs));
+    let mut trades_interval = tokio::time::interval(Duration::from_secs(
+        config.recorder_trades_poll_interval_secs,
+    ));
+    let mut flush_interval =
+        tokio::time::interval(Duration::from_secs(config.recorder_flush_interval_secs));
+    flush_interval.tick().await;
+
+    let ids = token_ids.iter().map(|id| id.as_str()).collect_vec();
+
+    tracing::info!(
+        "Recording {} tokens to {MARKET_DATA_FOLDER_PATH}, press Ctrl+C to stop",
+        token_ids.len()
+    );
+
+    loop {
+        tokio::select! {
+            _ = books_interval.tick() => {
+                let timestamp_ms = Utc::now().timestamp_millis();
+
+                match get_order_books(&ids, proxy.as_ref()).await {
+                    Ok(books) => {
+                        for book in books {
+                            markets.insert(book.market.clone());
+                            writer.push_book(timestamp_ms, &book);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to poll order books: {e}"),
+                }
+            }
+            _ = trades_interval.tick() => {
+                for market in &markets {
+                    match get_market_trades(market, TRADES_PAGE_SIZE, proxy.as_ref()).await {
+                        Ok(trades) => push_new_trades(&mut writer, &mut cursors, trades),
+                        Err(e) => tracing::warn!("Failed to poll trades of {market}: {e}"),
+                    }
+                }
+            }
+            _ = flush_interval.tick() => flush(&mut writer),
+            _ = tokio::signal::ctrl_c() => {
+                flush(&mut writer);
+                tracing::info!("Recorder stopped");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn flush(writer: &mut PartitionedWriter) {
+    let buffered = writer.buffered_rows();
+
+    match writer.flush() {
+        Ok(written) => tracing::info!("Flushed {written} market data rows"),
+        Err(e) => tracing::error!("Failed to flush {buffered} market data rows: {e}"),
+    }
+}
+
+fn push_new_trades(
+    writer: &mut PartitionedWriter,
+    cursors: &mut HashMap<String, TradeCursor>,
+    trades: Vec<MarketTrade>,
+) {
+    // The API returns newest first, cursors have to advance oldest first
+    for trade in trades.into_iter().rev() {
+        let Some(cursor) = cursors.get_mut(&trade.asset) else {
+            continue;
+        };
+
+        let row = TradeRow {
+            timestamp_ms: trade.timestamp * 1_000,
+            token_id: trade.asset,
+            side: trade.side,
+            price: trade.price,
+            size: trade.size,
+            transaction_hash: trade.transaction_hash,
+        };
+
+        if cursor.accept(&row) {
+            writer.push_trade(row);
+        }
+    }
+}
+
+// Picks up where a previous run of today left off so restarts don't record trades twice
+fn seed_trade_cursors(token_ids: &[String]) -> eyre::Result<HashMap<String, TradeCursor>> {
+    let today = Utc::now().date_naive();
+    let mut cursors = HashMap::new();
+
+    for token_id in token_ids {
+        let mut cursor = TradeCursor::default();
+
+        for trade in read_records::<TradeRow>(MARKET_DATA_FOLDER_PATH, token_id, Some(today), None)?
+        {
+            cursor.accept(&trade);
+        }
+
+        cursors.insert(token_id.clone(), cursor);
+    }
+
+    Ok(cursors)
+}
+
+pub async fn prompt_token_ids(prompt: &str, proxy: Option<&Proxy>) -> eyre::Result<Vec<String>> {
+    let theme = ColorfulTheme::default();
+
+    let source = Select::with_theme(&theme)
+        .with_prompt(prompt)
+        .items(&["Markets of an event", "Token IDs", "Back"])
+        .default(0)
+        .interact()?;
+
+    match source {
+        0 => {
+            let slug: String = Input::with_theme(&theme)
+                .with_prompt("Event slug:")
+                .interact_text()?;
+
+            let Some(event) = get_event_by_slug(slug.trim(), proxy).await? else {
+                tracing::warn!("Event `{}` not found", slug.trim());
+                return Ok(vec![]);
+            };
+
+            let markets = event.tradable_markets().collect_vec();
+            let selected = MultiSelect::with_theme(&theme)
+                .with_prompt("Markets (space to select):")
+                .items(&markets.iter().map(|market| market.label()).collect_vec())
+                .interact()?;
+
+            Ok(selected
+                .into_iter()
+                .flat_map(|i| markets[i].clob_token_ids.clone())
+                .collect())
+        }
+        1 => {
+            let ids: String = Input::with_theme(&theme)
+                .with_prompt("Token IDs (comma separated):")
+                .interact_text()?;
+
+            Ok(ids
+                .split(',')
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .unique()
+                .collect())
+        }
+        _ => Ok(vec![]),
+    }
+}
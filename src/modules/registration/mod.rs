@@ -3,17 +3,21 @@ use std::{str::FromStr, sync::Arc};
 use alloy::{
     network::Ethereum,
     primitives::{bytes, Address, Bytes},
-    providers::{Provider, ProviderBuilder},
+    providers::Provider,
     signers::local::PrivateKeySigner,
     transports::Transport,
 };
-use reqwest::{Proxy, StatusCode, Url};
+use reqwest::{Proxy, StatusCode};
+use tracing::Instrument;
 
 use crate::{
     config::Config,
     db::{account::Account, database::Database},
     errors::custom::CustomError,
-    onchain::{constants::POLYGON_EXPLORER_TX_BASE_URL, multicall::check_token_approvals},
+    onchain::{
+        constants::POLYGON_EXPLORER_TX_BASE_URL, multicall::check_token_approvals,
+        rpc::PolygonProvider,
+    },
     polymarket::api::{
         clob::{
             endpoints::{create_api_key, derive_api_key},
@@ -36,17 +40,18 @@ use crate::{
 
 const ZERO_BYTES: Bytes = bytes!("");
 
-pub async fn register_accounts(mut db: Database, config: &Config) -> eyre::Result<()> {
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_http(Url::parse(&config.polygon_rpc_url)?),
-    );
-
+pub async fn register_accounts(
+    mut db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
     while let Some(account) =
         db.get_random_account_with_filter(|account: &Account| !account.get_is_registered())
     {
-        register_account(account, config, provider.clone()).await?;
+        let span = account.span();
+        register_account(account, config, provider.clone())
+            .instrument(span)
+            .await?;
 
         account.set_is_registered(true);
         db.update();
@@ -0,0 +1,292 @@
+use std::{str::FromStr, sync::Arc};
+
+use alloy::{
+    network::{Ethereum, ReceiptResponse},
+    primitives::B256,
+    providers::Provider,
+    transports::Transport,
+};
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use itertools::Itertools;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    config::Config,
+    db::{
+        account::Account,
+        database::Database,
+        relayer_transactions::{ReceiptSummary, RelayerTransactionRecord, RelayerTransactionStore},
+    },
+    errors::custom::CustomError,
+    onchain::{constants::POLYGON_EXPLORER_TX_BASE_URL, rpc::PolygonProvider},
+    polymarket::api::{
+        relayer::{
+            common::resubmit_safe_transaction,
+            endpoints::{get_transaction_status, wait_for_transaction_confirmation},
+        },
+        typedefs::AmpCookie,
+    },
+};
+
+#[derive(Tabled)]
+struct TransactionRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "Transaction ID")]
+    transaction_id: String,
+    #[tabled(rename = "Kind")]
+    kind: String,
+    #[tabled(rename = "Proxy wallet")]
+    proxy_wallet: String,
+    #[tabled(rename = "Nonce")]
+    nonce: String,
+    #[tabled(rename = "State")]
+    state: String,
+    #[tabled(rename = "Age (min)")]
+    age: i64,
+    #[tabled(rename = "Receipt")]
+    receipt: String,
+}
+
+pub async fn track_relayer_transactions(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    refresh_transaction_states(&db).await;
+    fetch_missing_receipts(provider).await;
+
+    let store = RelayerTransactionStore::read()?;
+    let stuck_after = config.relayer_stuck_after_minutes;
+
+    let unresolved = store
+        .0
+        .iter()
+        .filter(|record| !record.superseded && !record.last_state().is_mined())
+        .collect_vec();
+
+    tracing::info!(
+        "{} relayer transactions tracked, {} unresolved",
+        store.0.len(),
+        unresolved.len()
+    );
+
+    if unresolved.is_empty() {
+        return Ok(());
+    }
+
+    print_transactions(&unresolved);
+
+    let resubmittable = unresolved
+        .into_iter()
+        .filter(|record| record.needs_attention(stuck_after) && record.nonce.is_some())
+        .collect_vec();
+
+    if resubmittable.is_empty() {
+        return Ok(());
+    }
+
+    let mut items = resubmittable
+        .iter()
+        .map(|record| {
+            format!(
+                "{} ({}, {})",
+                record.transaction_id,
+                record.kind,
+                record.last_state()
+            )
+        })
+        .collect_vec();
+    items.push("Back".to_string());
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Resubmit a failed or stuck transaction:")
+        .items(&items)
+        .default(items.len() - 1)
+        .interact()?;
+
+    let Some(record) = resubmittable.get(selection) else {
+        return Ok(());
+    };
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Resubmit {} with Safe nonce {}?",
+            record.transaction_id,
+            record.nonce.unwrap_or_default()
+        ))
+        .default(false)
+        .interact()?;
+
+    if confirmed {
+        let account = find_account(&db, &record.proxy_wallet)
+            .ok_or_else(|| eyre::eyre!("No account owns {}", record.proxy_wallet))?;
+        resubmit(account, record).await?;
+    }
+
+    Ok(())
+}
+
+fn find_account<'a>(db: &'a Database, proxy_wallet: &str) -> Option<&'a Account> {
+    db.0.iter()
+        .find(|account| account.proxy_address.eq_ignore_ascii_case(proxy_wallet))
+}
+
+async fn refresh_transaction_states(db: &Database) {
+    let store = match RelayerTransactionStore::read() {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::error!("Failed to refresh relayer transactions: {e}");
+            return;
+        }
+    };
+
+    let pending = store
+        .0
+        .into_iter()
+        .filter(|record| !record.superseded && !record.last_state().is_final())
+        .collect_vec();
+
+    for record in pending {
+        let Some(account) = find_account(db, &record.proxy_wallet) else {
+            continue;
+        };
+
+        let (Some(polymarket_nonce), Some(polymarket_session)) =
+            (&account.polymarket_nonce, &account.polymarket_session)
+        else {
+            continue;
+        };
+
+        let mut amp_cookie = AmpCookie::new();
+
+        match get_transaction_status(
+            &record.transaction_id,
+            &mut amp_cookie,
+            polymarket_nonce,
+            polymarket_session,
+            account.proxy().as_ref(),
+        )
+        .await
+        {
+            Ok(_) | Err(CustomError::RelayerTransactionFailed(_)) => {}
+            Err(e) => tracing::error!(
+                "Failed to refresh relayer transaction {}: {e}",
+                record.transaction_id
+            ),
+        }
+    }
+}
+
+async fn fetch_missing_receipts<P, T>(provider: Arc<P>)
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let store = match RelayerTransactionStore::read() {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::error!("Failed to fetch missing receipts: {e}");
+            return;
+        }
+    };
+
+    let mined = store
+        .0
+        .into_iter()
+        .filter(|record| record.last_state().is_mined() && record.receipt.is_none())
+        .collect_vec();
+
+    for record in mined {
+        let Some(hash) = record
+            .transaction_hash
+            .as_deref()
+            .and_then(|hash| B256::from_str(hash).ok())
+        else {
+            continue;
+        };
+
+        match provider.get_transaction_receipt(hash).await {
+            Ok(Some(receipt)) => RelayerTransactionStore::record_receipt(
+                &record.transaction_id,
+                ReceiptSummary {
+                    block_number: receipt.block_number(),
+                    gas_used: receipt.gas_used,
+                    success: receipt.status(),
+                },
+            ),
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to fetch receipt for {hash}: {e}"),
+        }
+    }
+}
+
+fn print_transactions(records: &[&RelayerTransactionRecord]) {
+    let rows = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| TransactionRow {
+            index,
+            transaction_id: record.transaction_id.clone(),
+            kind: record.kind.to_string(),
+            proxy_wallet: record.proxy_wallet.clone(),
+            nonce: record.nonce.map_or("-".to_string(), |n| n.to_string()),
+            state: record.last_state().to_string(),
+            age: record.age_minutes(),
+            receipt: record.receipt.as_ref().map_or("-".to_string(), |r| {
+                format!(
+                    "{} #{}",
+                    if r.success { "success" } else { "reverted" },
+                    r.block_number.unwrap_or_default()
+                )
+            }),
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+async fn resubmit(account: &Account, record: &RelayerTransactionRecord) -> eyre::Result<()> {
+    let mut amp_cookie = AmpCookie::new();
+    let proxy = account.proxy();
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    let tx_id = resubmit_safe_transaction(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        record,
+    )
+    .await?;
+
+    tracing::info!(
+        "{} | Resubmitted {} as {tx_id}",
+        account.proxy_address,
+        record.transaction_id
+    );
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Resubmitted transaction mined: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    Ok(())
+}
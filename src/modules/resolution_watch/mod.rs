@@ -0,0 +1,202 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tracing::Instrument;
+
+use crate::{
+    config::Config,
+    db::{account::Account, database::Database},
+    modules::{bets::opposing::create_and_place_sell_market_order, positions::redeem},
+    notifier::{notify, Severity},
+    polymarket::api::{
+        events::{endpoints::get_market_by_token_id, schemas::Market},
+        user::{endpoints::get_user_positions, schemas::UserPosition},
+    },
+};
+
+// What happens to a position once its market is within `RESOLUTION_EXIT_HOURS` of its end date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionAction {
+    Warn,
+    Exit,
+}
+
+pub async fn watch_resolutions(db: Database, config: &Config) -> eyre::Result<()> {
+    if db.0.is_empty() {
+        tracing::warn!("No accounts found");
+        return Ok(());
+    }
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(config.resolution_check_interval_secs));
+    let mut warned = HashSet::new();
+
+    tracing::info!(
+        "Watching positions of {} wallets, markets ending within {} hours are handled with `{:?}`, press Ctrl+C to stop",
+        db.0.len(),
+        config.resolution_exit_hours,
+        config.resolution_action
+    );
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                // Wallets often hold the same markets, each is looked up once per check
+                let mut markets = HashMap::new();
+
+                for account in &db.0 {
+                    if let Err(e) = check_positions(account, config, &mut warned, &mut markets)
+                        .instrument(account.span())
+                        .await
+                    {
+                        tracing::error!("{} | Resolution check failed: {e}", account.proxy_address);
+                    }
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("Resolution watcher stopped");
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn check_positions(
+    account: &Account,
+    config: &Config,
+    warned: &mut HashSet<String>,
+    markets: &mut HashMap<String, Option<Market>>,
+) -> eyre::Result<()> {
+    let proxy = account.proxy();
+    let positions = get_user_positions(&account.proxy_address, proxy.as_ref()).await?;
+    let mut resolved: HashMap<String, Vec<&UserPosition>> = HashMap::new();
+
+    for position in &positions {
+        let title = position.title.as_deref().unwrap_or(&position.asset);
+        let market_key = position.condition_id.as_ref().unwrap_or(&position.asset);
+
+        let market = match markets.get(market_key) {
+            Some(market) => market.clone(),
+            None => match get_market_by_token_id(&position.asset, proxy.as_ref()).await {
+                Ok(market) => {
+                    markets.insert(market_key.clone(), market.clone());
+                    market
+                }
+                // Redeemable positions don't need their market
+                Err(_) if position.redeemable => None,
+                Err(e) => {
+                    tracing::warn!(
+                        "{} | Failed to look up the market of {title}: {e}",
+                        account.proxy_address
+                    );
+                    continue;
+                }
+            },
+        };
+
+        if position.redeemable || market.as_ref().is_some_and(|market| market.is_resolved()) {
+            if let Some(condition_id) = &position.condition_id {
+                resolved
+                    .entry(condition_id.clone())
+                    .or_default()
+                    .push(position);
+            }
+            continue;
+        }
+
+        let Some(market) = market else {
+            tracing::warn!("{} | Market of {title} not found", account.proxy_address);
+            continue;
+        };
+
+        // Closed but unresolved markets can only be waited out
+        if !market.is_accepting_orders() {
+            tracing::debug!(
+                "{} | {title} is not accepting orders, waiting for resolution",
+                account.proxy_address
+            );
+            continue;
+        }
+
+        let Some(hours_left) = market
+            .hours_until_end()
+            .filter(|hours_left| *hours_left <= config.resolution_exit_hours)
+        else {
+            continue;
+        };
+
+        if position.cur_price.unwrap_or_default() <= 0.0 {
+            continue;
+        }
+
+        match config.resolution_action {
+            ResolutionAction::Warn => {
+                if !warned.insert(position.asset.clone()) {
+                    continue;
+                }
+
+                let message = format!(
+                    "{} | {title} ends in {hours_left:.1} hours, {:.2} {} shares are still held",
+                    account.proxy_address,
+                    position.size,
+                    position.outcome.as_deref().unwrap_or_default()
+                );
+
+                tracing::warn!("{message}");
+                notify(Severity::Warning, "Market ending soon", message);
+            }
+            ResolutionAction::Exit => {
+                tracing::info!(
+                    "{} | {title} ends in {hours_left:.1} hours, selling {:.2} shares",
+                    account.proxy_address,
+                    position.size
+                );
+
+                match create_and_place_sell_market_order(account, &position.asset).await
+                {
+                    Ok(_) => notify(
+                        Severity::Info,
+                        "Position exited before market end",
+                        format!(
+                            "{} | Sold {:.2} shares of {title}, {hours_left:.1} hours before its end",
+                            account.proxy_address, position.size
+                        ),
+                    ),
+                    Err(e) => tracing::error!(
+                        "{} | Failed to exit {title}: {e}",
+                        account.proxy_address
+                    ),
+                }
+            }
+        }
+    }
+
+    if !config.resolution_auto_redeem {
+        for positions in resolved.values() {
+            if warned.insert(positions[0].asset.clone()) {
+                tracing::info!(
+                    "{} | {} resolved and can be redeemed",
+                    account.proxy_address,
+                    positions[0].title.as_deref().unwrap_or(&positions[0].asset)
+                );
+            }
+        }
+
+        return Ok(());
+    }
+
+    for (condition_id, positions) in resolved {
+        if let Err(e) = redeem(account, &condition_id, &positions).await {
+            tracing::error!(
+                "{} | Failed to redeem {condition_id}: {e}",
+                account.proxy_address
+            );
+        }
+    }
+
+    Ok(())
+}
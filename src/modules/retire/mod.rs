@@ -0,0 +1,387 @@
+use std::{collections::HashSet, sync::Arc};
+
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use itertools::Itertools;
+use tracing::Instrument;
+
+use crate::{
+    config::Config,
+    db::{account::Account, allowlist::AllowList, database::Database},
+    modules::{
+        bets::opposing::create_and_place_sell_market_orders,
+        positions::redeem,
+        registration::create_or_derive_api_key,
+        sell::dust::{is_dust_error, settle_dust},
+        withdraw::{
+            safeguards::{check_recipients, print_recipient_mapping},
+            withdraw,
+        },
+    },
+    onchain::{
+        constants::POLYGON_EXPLORER_TX_BASE_URL,
+        multicall::multicall_audit_wallets,
+        rpc::{quorum_balance_of, PolygonProvider},
+        types::token::Token,
+    },
+    polymarket::api::{
+        clob::{
+            endpoints::cancel_all_orders, metadata::get_market_metadata, typedefs::Side,
+            validation::validate_order_size,
+        },
+        relayer::{common::revoke_approvals, endpoints::wait_for_transaction_confirmation},
+        typedefs::AmpCookie,
+        user::endpoints::get_user_positions,
+    },
+};
+
+// Winding a wallet down: cancel orders, sell or redeem positions, withdraw, then revoke approvals.
+// Every step ends with a checkpoint so the flow can be stopped and resumed from the next step.
+pub async fn retire_wallet(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    if db.0.is_empty() {
+        tracing::warn!("No accounts found");
+        return Ok(());
+    }
+
+    let account_index = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Wallet to retire:")
+        .items(&db.0.iter().map(|a| a.proxy_address.as_str()).collect_vec())
+        .default(0)
+        .interact()?;
+    let account = &db.0[account_index];
+
+    let steps = [
+        "Cancel open orders",
+        "Sell or redeem positions",
+        "Withdraw USDC.e",
+        "Revoke approvals",
+    ];
+
+    let first_step = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Start from:")
+        .items(&steps)
+        .default(0)
+        .interact()?;
+
+    for (step, name) in steps.iter().enumerate().skip(first_step) {
+        tracing::info!(
+            "{} | Step {}/{}: {name}",
+            account.proxy_address,
+            step + 1,
+            steps.len()
+        );
+
+        let result = match step {
+            0 => cancel_orders(account).instrument(account.span()).await,
+            1 => close_positions(account).instrument(account.span()).await,
+            2 => {
+                withdraw_balance(account, config, provider.clone())
+                    .instrument(account.span())
+                    .await
+            }
+            _ => {
+                revoke(account, provider.clone())
+                    .instrument(account.span())
+                    .await
+            }
+        };
+
+        if let Err(e) = result {
+            tracing::error!(
+                "{} | `{name}` failed, start from it to retry: {e}",
+                account.proxy_address
+            );
+            return Ok(());
+        }
+
+        let Some(next) = steps.get(step + 1) else {
+            break;
+        };
+
+        if !checkpoint(name, next)? {
+            tracing::info!("Stopped before `{next}`, start from it to resume");
+            return Ok(());
+        }
+    }
+
+    tracing::info!("{} is retired", account.proxy_address);
+
+    Ok(())
+}
+
+fn checkpoint(done: &str, next: &str) -> eyre::Result<bool> {
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("`{done}` finished, continue with `{next}`?"))
+        .default(false)
+        .interact()?)
+}
+
+async fn cancel_orders(account: &Account) -> eyre::Result<()> {
+    if account.get_api_creds().is_none() {
+        let response = create_or_derive_api_key(account.signer(), account.proxy().as_ref()).await?;
+        account.update_credentials(response);
+    }
+
+    let response = cancel_all_orders(account).await?;
+
+    for (order_id, reason) in &response.not_canceled {
+        tracing::warn!(
+            "{} | Order {order_id} not canceled: {reason}",
+            account.proxy_address
+        );
+    }
+
+    tracing::info!(
+        "{} | Canceled {} orders",
+        account.proxy_address,
+        response.canceled.len()
+    );
+
+    if !response.not_canceled.is_empty() {
+        eyre::bail!("{} orders are still open", response.not_canceled.len());
+    }
+
+    Ok(())
+}
+
+async fn close_positions(account: &Account) -> eyre::Result<()> {
+    let proxy = account.proxy();
+    let positions = get_user_positions(&account.proxy_address, proxy.as_ref()).await?;
+
+    let (redeemable, open): (Vec<_>, Vec<_>) =
+        positions.iter().partition(|position| position.redeemable);
+
+    let mut sellable = vec![];
+    let mut dust_positions = vec![];
+
+    for position in open {
+        let price = position.cur_price.unwrap_or_default();
+
+        if price <= 0.0 {
+            tracing::warn!(
+                "{} | Skipping worthless position in {}",
+                account.proxy_address,
+                position.title.as_deref().unwrap_or(&position.asset)
+            );
+            continue;
+        }
+
+        let metadata = get_market_metadata(&position.asset, proxy.as_ref()).await?;
+
+        match validate_order_size(&Side::Sell, position.size, price, &metadata) {
+            Ok(_) => sellable.push(position),
+            Err(_) => dust_positions.push(position),
+        }
+    }
+
+    // Dust is merged before the rest is sold, while the other outcome is still held. Dust that
+    // couldn't be merged stays in the wallet and doesn't hold the retirement up.
+    let mut dust = HashSet::new();
+    let mut merged = HashSet::new();
+
+    for position in dust_positions {
+        if merged.contains(&position.condition_id) {
+            continue;
+        }
+
+        match settle_dust(account, position).await? {
+            true => merged.insert(position.condition_id.clone()),
+            false => dust.insert(position.asset.clone()),
+        };
+    }
+
+    let token_ids = sellable
+        .iter()
+        .map(|position| position.asset.as_str())
+        .collect_vec();
+    let mut failed = 0;
+
+    for (position, result) in sellable
+        .iter()
+        .zip(create_and_place_sell_market_orders(account, &token_ids).await?)
+    {
+        match result {
+            Ok(_) => {}
+            Err(e) if is_dust_error(&e) => {
+                dust.insert(position.asset.clone());
+            }
+            Err(e) => {
+                tracing::error!(
+                    "{} | Failed to sell {}: {e}",
+                    account.proxy_address,
+                    position.title.as_deref().unwrap_or(&position.asset)
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eyre::bail!("{failed} positions failed to sell");
+    }
+
+    let by_condition = redeemable
+        .into_iter()
+        .filter_map(|position| Some((position.condition_id.clone()?, position)))
+        .into_group_map();
+
+    for (condition_id, positions) in by_condition {
+        redeem(account, &condition_id, &positions).await?;
+    }
+
+    let remaining = get_user_positions(&account.proxy_address, proxy.as_ref())
+        .await?
+        .into_iter()
+        .filter(|position| position.cur_price.unwrap_or_default() > 0.0 || position.redeemable)
+        .filter(|position| !dust.contains(&position.asset))
+        .count();
+
+    if remaining > 0 {
+        eyre::bail!("{remaining} positions are still open");
+    }
+
+    if !dust.is_empty() {
+        tracing::warn!(
+            "{} | {} dust positions are left in the wallet",
+            account.proxy_address,
+            dust.len()
+        );
+    }
+
+    tracing::info!("{} | No positions left to close", account.proxy_address);
+
+    Ok(())
+}
+
+async fn withdraw_balance(
+    account: &Account,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let allow_list = AllowList::read().await?;
+    let listed_recipients = Database::read_listed_recipients().await.unwrap_or_default();
+    let checks = check_recipients(
+        std::slice::from_ref(account),
+        &allow_list,
+        &listed_recipients,
+    )?;
+
+    print_recipient_mapping(&checks);
+
+    let check = &checks[0];
+
+    if let Some(reason) = check.blocked_reason() {
+        eyre::bail!("Withdrawal blocked: {reason}");
+    }
+
+    let proxy_wallet = account.get_proxy_address();
+    let balance = quorum_balance_of(
+        &[proxy_wallet],
+        Token::USDCE,
+        provider.clone(),
+        config.rpc_balance_quorum,
+    )
+    .await?[0];
+
+    if balance.is_zero() {
+        tracing::info!("{} | Nothing to withdraw", account.proxy_address);
+        return Ok(());
+    }
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Withdraw {} USDC.e to {} ({})?",
+            Token::USDCE.to_ui_amount(balance),
+            check.recipient,
+            check.label.as_deref().unwrap_or_default()
+        ))
+        .default(false)
+        .interact()?;
+
+    if !confirmed {
+        eyre::bail!("Withdrawal declined");
+    }
+
+    withdraw(account, check.recipient, balance).await?;
+
+    let remaining = quorum_balance_of(
+        &[proxy_wallet],
+        Token::USDCE,
+        provider,
+        config.rpc_balance_quorum,
+    )
+    .await?[0];
+
+    tracing::info!(
+        "{} | {} USDC.e left in the proxy wallet",
+        account.proxy_address,
+        Token::USDCE.to_ui_amount(remaining)
+    );
+
+    Ok(())
+}
+
+async fn revoke(account: &Account, provider: Arc<PolygonProvider>) -> eyre::Result<()> {
+    let mut amp_cookie = AmpCookie::new();
+    let proxy = account.proxy();
+    let (polymarket_nonce, polymarket_session) = session(account)?;
+
+    tracing::info!("{} | Revoking token approvals", account.proxy_address);
+
+    let tx_id = revoke_approvals(
+        account.signer(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+    )
+    .await?;
+
+    let tx_hash = wait_for_transaction_confirmation(
+        &tx_id,
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy.as_ref(),
+        None,
+        None,
+    )
+    .await?;
+
+    tracing::info!("Approvals revoked: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
+
+    let audit = multicall_audit_wallets(
+        &[(account.get_proxy_address(), account.signer().address())],
+        provider,
+    )
+    .await?;
+
+    let still_granted = audit[0]
+        .approvals
+        .iter()
+        .filter(|(_, state)| state.is_granted())
+        .map(|(target, _)| target.label)
+        .collect_vec();
+
+    if !still_granted.is_empty() {
+        eyre::bail!("Approvals still granted: {}", still_granted.join(", "));
+    }
+
+    Ok(())
+}
+
+fn session(account: &Account) -> eyre::Result<(&str, &str)> {
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    Ok((polymarket_nonce, polymarket_session))
+}
@@ -0,0 +1,46 @@
+use itertools::Itertools;
+
+use crate::{
+    db::account::Account,
+    errors::custom::CustomError,
+    modules::positions::merge,
+    polymarket::api::user::{endpoints::get_user_positions, schemas::UserPosition},
+};
+
+// Sells of positions under the market's minimum order size fail before signing with this error
+pub fn is_dust_error(error: &eyre::Report) -> bool {
+    matches!(
+        error.downcast_ref::<CustomError>(),
+        Some(CustomError::OrderBelowMinSize { .. })
+    )
+}
+
+// Dust can't be sold, but merged with the complementary outcome it turns back into USDC.e.
+// Returns whether the dust was merged, without a complementary position it is left as is.
+pub async fn settle_dust(account: &Account, position: &UserPosition) -> eyre::Result<bool> {
+    let title = position.title.as_deref().unwrap_or(&position.asset);
+
+    let Some(condition_id) = &position.condition_id else {
+        tracing::warn!("{} | Skipping dust in {title}", account.proxy_address);
+        return Ok(false);
+    };
+
+    let positions = get_user_positions(&account.proxy_address, account.proxy().as_ref()).await?;
+    let pair = positions
+        .iter()
+        .filter(|held| held.condition_id.as_ref() == Some(condition_id) && held.size > 0.0)
+        .collect_vec();
+
+    if pair.len() < 2 {
+        tracing::warn!(
+            "{} | Skipping dust of {} shares in {title}, the other outcome isn't held to merge it",
+            account.proxy_address,
+            position.size
+        );
+        return Ok(false);
+    }
+
+    merge(account, condition_id, &pair).await?;
+
+    Ok(true)
+}
@@ -1 +1,2 @@
+pub mod dust;
 pub mod sell_all;
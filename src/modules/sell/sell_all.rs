@@ -1,49 +1,70 @@
-use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
+use std::sync::Arc;
+
+use rand::{seq::SliceRandom, thread_rng};
+use tracing::Instrument;
 
 use crate::{
     config::Config,
     db::{account::Account, database::Database},
-    modules::bets::opposing::create_and_place_sell_market_order,
-    polymarket::api::{
-        clob::{
-            endpoints::get_tick_size,
-            typedefs::{Side, TickSize},
-        },
-        user::endpoints::get_user_positions,
+    modules::sell::dust::settle_dust,
+    onchain::rpc::PolygonProvider,
+    polymarket::api::{clob::metadata::get_market_metadata, user::endpoints::get_user_positions},
+    strategy::{
+        execution::ExecutionMode,
+        liquidate::LiquidateStrategy,
+        runtime::{load_portfolio, run_strategy, StrategyWallet},
     },
     utils::misc::pretty_sleep,
 };
 
-pub async fn sell_all_open_positions(db: Database, config: &Config) -> eyre::Result<()> {
+pub async fn sell_all_open_positions(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
     let mut accounts = db.0.clone();
-    let mut rng = thread_rng();
-
-    while !accounts.is_empty() {
-        let index = rng.gen_range(0..accounts.len());
-        let account = &accounts[index];
-
-        match sell_random_open_positions(account, &mut rng).await {
-            Ok(res) => {
-                if !res {
-                    accounts.remove(index);
-                }
-
-                pretty_sleep(config.sell_delay_range).await;
-            }
-            Err(e) => {
-                tracing::error!("Failed to sell a random position: {e}")
-            }
+    accounts.shuffle(&mut thread_rng());
+
+    let mut unsold = 0;
+
+    for account in &accounts {
+        match sell_open_positions(account, config, provider.clone())
+            .instrument(account.span())
+            .await
+        {
+            Ok(left) => unsold += left,
+            Err(e) => tracing::error!(
+                "{} | Failed to sell open positions: {e}",
+                account.proxy_address
+            ),
         }
+
+        pretty_sleep(config.sell_delay_range).await;
     }
 
-    tracing::info!("No more open positions left");
+    match unsold {
+        0 => tracing::info!("No more open positions left"),
+        _ => tracing::warn!("{unsold} positions could not be sold"),
+    }
 
     Ok(())
 }
 
-async fn sell_random_open_positions(account: &Account, rng: &mut ThreadRng) -> eyre::Result<bool> {
+// Runs the liquidate strategy on the wallet, then merges what is left under the minimum order
+// size with the other outcome. Returns how many positions are still held
+async fn sell_open_positions(
+    account: &Account,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<usize> {
     let proxy = account.proxy();
-    let positions = get_user_positions(&account.proxy_address, proxy.as_ref()).await?;
+
+    // Resolved positions have no book to sell into, they are redeemed instead
+    let positions = get_user_positions(&account.proxy_address, proxy.as_ref())
+        .await?
+        .into_iter()
+        .filter(|position| !position.redeemable && position.size > 0.0)
+        .collect::<Vec<_>>();
 
     tracing::info!(
         "{} has {} open positions",
@@ -52,20 +73,47 @@ async fn sell_random_open_positions(account: &Account, rng: &mut ThreadRng) -> e
     );
 
     if positions.is_empty() {
-        return Ok(false);
+        return Ok(0);
     }
 
-    let position = positions.choose(rng).unwrap();
-    let tick_size = TickSize::from_str(
-        &get_tick_size(proxy.as_ref(), &position.asset)
-            .await?
-            .to_string(),
+    let mut portfolio = load_portfolio(account, config, provider).await?;
+    portfolio
+        .positions
+        .retain(|token_id, _| positions.iter().any(|position| &position.asset == token_id));
+
+    let mut wallets = [StrategyWallet {
+        account,
+        token_ids: portfolio.positions.keys().cloned().collect(),
+        portfolio,
+    }];
+
+    run_strategy(
+        config,
+        ExecutionMode::Live,
+        &mut LiquidateStrategy::default(),
+        &mut wallets,
     )
-    .unwrap();
+    .await?;
+
+    let mut left = 0;
+
+    for position in &positions {
+        let size = wallets[0].portfolio.position(&position.asset);
+        if size <= 0.0 {
+            continue;
+        }
 
-    let response = create_and_place_sell_market_order(account, &position.asset, tick_size).await?;
+        let metadata = get_market_metadata(&position.asset, proxy.as_ref()).await?;
+        let merged = size < metadata.min_order_size
+            && settle_dust(account, position).await.unwrap_or_else(|e| {
+                tracing::error!("{} | Failed to merge dust: {e}", account.proxy_address);
+                false
+            });
 
-    response.log_successful_placement(Side::Sell, &account.proxy_address);
+        if !merged {
+            left += 1;
+        }
+    }
 
-    Ok(true)
+    Ok(left)
 }
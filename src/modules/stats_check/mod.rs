@@ -1,13 +1,10 @@
 use std::{fs::File, sync::Arc};
 
-use alloy::{
-    primitives::{utils::format_units, Address},
-    providers::ProviderBuilder,
-};
+use alloy::primitives::{utils::format_units, Address, U256};
 
 use csv::WriterBuilder;
 use itertools::Itertools;
-use reqwest::{Proxy, Url};
+use reqwest::Proxy;
 use scraping::{
     scrape_open_positions, scrape_users_open_pos_value, scrape_users_pnl, scrape_users_trade_count,
     scrape_users_volume,
@@ -18,7 +15,11 @@ use tabled::{settings::Style, Table, Tabled};
 use crate::{
     config::Config,
     db::database::Database,
-    onchain::{multicall::multicall_balance_of, types::token::Token},
+    onchain::{
+        multicall::multicall_balance_of,
+        rpc::{quorum_balance_of, PolygonProvider},
+        types::token::Token,
+    },
 };
 
 mod scraping;
@@ -34,6 +35,22 @@ struct UserStats {
     #[serde(rename = "USDC.e Balance")]
     balance: String,
 
+    #[tabled(rename = "USDC Balance")]
+    #[serde(rename = "USDC Balance")]
+    native_usdc_balance: String,
+
+    #[tabled(rename = "EOA POL")]
+    #[serde(rename = "EOA POL")]
+    eoa_pol_balance: String,
+
+    #[tabled(rename = "EOA USDC.e")]
+    #[serde(rename = "EOA USDC.e")]
+    eoa_usdce_balance: String,
+
+    #[tabled(rename = "EOA USDC")]
+    #[serde(rename = "EOA USDC")]
+    eoa_native_usdc_balance: String,
+
     #[tabled(rename = "Open positions count")]
     #[serde(rename = "Open positions count")]
     open_positions_count: usize,
@@ -55,19 +72,34 @@ struct UserStats {
     trade_count: u64,
 }
 
-pub async fn check_and_display_stats(db: Database, config: &Config) -> eyre::Result<()> {
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_http(Url::parse(&config.polygon_rpc_url)?),
-    );
-
+pub async fn check_and_display_stats(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
     let (addresses, proxies): (Vec<Address>, Vec<Option<Proxy>>) =
         db.0.iter()
             .map(|account| (account.get_proxy_address(), account.proxy()))
             .unzip();
 
-    let balances = multicall_balance_of(&addresses, Token::USDCE, provider).await?;
+    let eoa_addresses =
+        db.0.iter()
+            .map(|account| account.signer().address())
+            .collect_vec();
+
+    let (balances, native_usdc_balances, eoa_pol_balances, eoa_usdce_balances, eoa_usdc_balances) =
+        tokio::try_join!(
+            quorum_balance_of(
+                &addresses,
+                Token::USDCE,
+                provider.clone(),
+                config.rpc_balance_quorum
+            ),
+            multicall_balance_of(&addresses, Token::USDC, provider.clone()),
+            multicall_balance_of(&eoa_addresses, Token::POL, provider.clone()),
+            multicall_balance_of(&eoa_addresses, Token::USDCE, provider.clone()),
+            multicall_balance_of(&eoa_addresses, Token::USDC, provider)
+        )?;
 
     let addresses = addresses
         .into_iter()
@@ -90,7 +122,7 @@ pub async fn check_and_display_stats(db: Database, config: &Config) -> eyre::Res
 
     let mut stats_entries = vec![];
 
-    for (address, balance) in addresses.iter().zip(balances.iter()) {
+    for (i, (address, balance)) in addresses.iter().zip(balances.iter()).enumerate() {
         let balance_in_usdce = format_units(*balance, 6).unwrap_or_else(|_| "0".to_string());
 
         let open_positions_count = open_positions_stats
@@ -126,6 +158,10 @@ pub async fn check_and_display_stats(db: Database, config: &Config) -> eyre::Res
         let entry = UserStats {
             address: address.to_string(),
             balance: balance_in_usdce,
+            native_usdc_balance: Token::USDC.to_ui_amount(native_usdc_balances[i]),
+            eoa_pol_balance: Token::POL.to_ui_amount(eoa_pol_balances[i]),
+            eoa_usdce_balance: Token::USDCE.to_ui_amount(eoa_usdce_balances[i]),
+            eoa_native_usdc_balance: Token::USDC.to_ui_amount(eoa_usdc_balances[i]),
             open_positions_count,
             open_positions_value,
             volume: user_volume,
@@ -160,6 +196,10 @@ pub async fn check_and_display_stats(db: Database, config: &Config) -> eyre::Res
     let total_entry = UserStats {
         address: "Total".to_string(),
         balance: format!("{:.2}", total_balance),
+        native_usdc_balance: format!("{:.2}", sum_balances(&native_usdc_balances, Token::USDC)),
+        eoa_pol_balance: format!("{:.2}", sum_balances(&eoa_pol_balances, Token::POL)),
+        eoa_usdce_balance: format!("{:.2}", sum_balances(&eoa_usdce_balances, Token::USDCE)),
+        eoa_native_usdc_balance: format!("{:.2}", sum_balances(&eoa_usdc_balances, Token::USDC)),
         open_positions_count: total_open_positions_count,
         open_positions_value: total_open_positions_value,
         volume: total_volume,
@@ -179,6 +219,13 @@ pub async fn check_and_display_stats(db: Database, config: &Config) -> eyre::Res
     Ok(())
 }
 
+fn sum_balances(balances: &[U256], token: Token) -> f64 {
+    balances
+        .iter()
+        .map(|balance| token.to_ui_amount(*balance).parse::<f64>().unwrap_or(0.0))
+        .sum()
+}
+
 fn export_stats_to_csv(entries: &[UserStats]) -> eyre::Result<()> {
     let export_file = File::create(EXPORT_FILE_PATH)?;
 
@@ -87,7 +87,7 @@ pub async fn scrape_users_pnl(
     proxies: Vec<Option<Proxy>>,
 ) -> Vec<(String, Vec<UserPnlStats>)> {
     scrape_executor(addresses, proxies, |address, proxy| {
-        Box::pin(get_user_pnl(address, proxy))
+        Box::pin(get_user_pnl(address, "all", proxy))
     })
     .await
 }
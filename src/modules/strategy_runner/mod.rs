@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use itertools::Itertools;
+use tracing::Instrument;
+
+use crate::{
+    config::Config,
+    db::database::Database,
+    onchain::rpc::PolygonProvider,
+    strategy::{
+        execution::ExecutionMode,
+        runtime::{load_portfolio, run_strategy, StrategyWallet},
+        strategy_by_name,
+    },
+};
+
+use super::recorder::prompt_token_ids;
+
+pub async fn run_configured_strategy(
+    db: Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    if db.0.is_empty() {
+        tracing::warn!("No accounts found");
+        return Ok(());
+    }
+
+    let mut strategy = strategy_by_name(&config.strategy, config)?;
+
+    let account_index = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Wallet to trade with:")
+        .items(&db.0.iter().map(|a| a.proxy_address.as_str()).collect_vec())
+        .default(0)
+        .interact()?;
+    let account = &db.0[account_index];
+
+    let token_ids = prompt_token_ids("Trade:", account.proxy().as_ref()).await?;
+
+    if token_ids.is_empty() {
+        tracing::warn!("No tokens selected");
+        return Ok(());
+    }
+
+    if config.strategy_mode == ExecutionMode::Live {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Place real orders from {} with the `{}` strategy?",
+                account.proxy_address,
+                strategy.name()
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            return Ok(());
+        }
+    }
+
+    let span = account.span();
+
+    async {
+        let portfolio = load_portfolio(account, config, provider).await?;
+
+        tracing::info!(
+            "{} | Starting with {:.2} USDC.e and {} open positions",
+            account.proxy_address,
+            portfolio.cash,
+            portfolio.positions.len()
+        );
+
+        let mut wallets = [StrategyWallet {
+            account,
+            portfolio,
+            token_ids,
+        }];
+
+        run_strategy(
+            config,
+            config.strategy_mode,
+            strategy.as_mut(),
+            &mut wallets,
+        )
+        .await?;
+
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
@@ -1,71 +1,484 @@
-use std::sync::Arc;
+use std::{fmt::Display, sync::Arc};
 
-use alloy::{
-    network::Ethereum,
-    primitives::utils::format_units,
-    providers::{Provider, ProviderBuilder},
-    transports::Transport,
-};
-use alloy_chains::NamedChain;
-use rand::{thread_rng, Rng};
-use reqwest::Url;
+use alloy::primitives::{utils::format_units, Address, U256};
+use chrono::{DateTime, Utc};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use itertools::Itertools;
+use rand::{seq::SliceRandom, thread_rng};
+use tabled::{settings::Style, Table, Tabled};
+use tracing::Instrument;
 
 use crate::{
     config::Config,
-    db::{account::Account, database::Database},
-    onchain::{client::EvmClient, constants::POLYGON_EXPLORER_TX_BASE_URL, types::token::Token},
-    polymarket::api::{relayer::common::withdraw_usdc, typedefs::AmpCookie},
+    db::{
+        account::Account,
+        allowlist::AllowList,
+        database::Database,
+        relayer_transactions::{RelayerTransactionKind, RelayerTransactionStore},
+    },
+    onchain::{
+        constants::POLYGON_EXPLORER_TX_BASE_URL,
+        rpc::{quorum_balance_of, PolygonProvider},
+        types::token::Token,
+    },
+    polymarket::api::{
+        relayer::{common::withdraw_usdc, endpoints::get_nonce},
+        typedefs::AmpCookie,
+    },
     utils::misc::pretty_sleep,
 };
 
-pub async fn withdraw_for_all(db: &mut Database, config: &Config) -> eyre::Result<()> {
-    let mut rng = thread_rng();
+use safeguards::{check_recipients, print_recipient_mapping};
 
-    let provider = Arc::new(
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_http(Url::parse(&config.polygon_rpc_url)?),
-    );
+pub mod safeguards;
+
+#[derive(Debug, Clone, Copy)]
+pub enum WithdrawAmount {
+    Full,
+    Exact(f64),
+    Percentage(f64),
+    AboveReserve(f64),
+}
+
+impl WithdrawAmount {
+    // Amount to move out of a wallet holding `balance`, `None` when there is nothing to withdraw
+    pub fn resolve(&self, balance: U256) -> Option<U256> {
+        let amount = match *self {
+            Self::Full => balance,
+            Self::Exact(amount) => {
+                let amount = Token::USDCE.to_wei(amount);
+                if amount > balance {
+                    return None;
+                }
+                amount
+            }
+            Self::Percentage(percentage) => {
+                let basis_points = U256::from((percentage * 100.0).round() as u64);
+                balance * basis_points / U256::from(10_000)
+            }
+            Self::AboveReserve(reserve) => balance.saturating_sub(Token::USDCE.to_wei(reserve)),
+        };
+
+        (!amount.is_zero()).then_some(amount)
+    }
+}
+
+impl Display for WithdrawAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "full balance"),
+            Self::Exact(amount) => write!(f, "exactly {amount} USDC.e"),
+            Self::Percentage(percentage) => write!(f, "{percentage}% of the balance"),
+            Self::AboveReserve(reserve) => write!(f, "everything above {reserve} USDC.e"),
+        }
+    }
+}
+
+struct WithdrawalPlan<'a> {
+    account: &'a Account,
+    recipient: Address,
+    label: &'a str,
+    balance: U256,
+    amount: U256,
+}
+
+enum WithdrawalOutcome {
+    Withdrawn(String),
+    Failed(String),
+    // Failed on our side, but the transaction may have been submitted anyway
+    Unverified(String),
+}
+
+#[derive(Tabled)]
+struct PlanRow {
+    #[tabled(rename = "Proxy wallet")]
+    proxy_wallet: String,
+    #[tabled(rename = "Recipient")]
+    recipient: String,
+    #[tabled(rename = "Label")]
+    label: String,
+    #[tabled(rename = "Balance")]
+    balance: String,
+    #[tabled(rename = "Withdraw")]
+    amount: String,
+}
+
+#[derive(Tabled)]
+struct ReportRow {
+    #[tabled(rename = "Proxy wallet")]
+    proxy_wallet: String,
+    #[tabled(rename = "Amount")]
+    amount: String,
+    #[tabled(rename = "Attempts")]
+    attempts: u32,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+pub async fn withdraw_for_all(
+    db: &Database,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<()> {
+    let theme = ColorfulTheme::default();
+
+    let allow_list = AllowList::read().await?;
+
+    if allow_list.0.is_empty() {
+        tracing::warn!(
+            "No withdrawal destinations are allow-listed, add them to the allow-list file first"
+        );
+        return Ok(());
+    }
+
+    let listed_recipients = Database::read_listed_recipients().await.unwrap_or_default();
+    let checks = check_recipients(&db.0, &allow_list, &listed_recipients)?;
+
+    // Dry run of where every account would send its funds, nothing moves before this is confirmed
+    print_recipient_mapping(&checks);
+
+    let allowed = checks
+        .iter()
+        .filter(|check| check.blocked_reason().is_none())
+        .collect_vec();
+
+    if allowed.is_empty() {
+        tracing::warn!("Every account is blocked from withdrawing");
+        return Ok(());
+    }
+
+    let mapping_confirmed = Confirm::with_theme(&theme)
+        .with_prompt(format!(
+            "Recipient mapping is correct, continue with {} of {} accounts?",
+            allowed.len(),
+            checks.len()
+        ))
+        .default(false)
+        .interact()?;
+
+    if !mapping_confirmed {
+        return Ok(());
+    }
+
+    let first_time_destinations = allowed
+        .iter()
+        .filter(|check| check.first_time)
+        .map(|check| (check.recipient, check.label.as_deref().unwrap_or_default()))
+        .unique()
+        .collect_vec();
+
+    if !first_time_destinations.is_empty() {
+        for (address, label) in &first_time_destinations {
+            tracing::warn!("Nothing has been withdrawn to {address} ({label}) before");
+        }
+
+        let first_time_confirmed = Confirm::with_theme(&theme)
+            .with_prompt(format!(
+                "Withdraw to {} first-time destinations?",
+                first_time_destinations.len()
+            ))
+            .default(false)
+            .interact()?;
+
+        if !first_time_confirmed {
+            return Ok(());
+        }
+    }
+
+    let withdraw_amount = prompt_withdraw_amount()?;
+
+    let mut plans = vec![];
+
+    let proxy_addresses = allowed
+        .iter()
+        .map(|check| check.account.get_proxy_address())
+        .collect_vec();
+    let balances = quorum_balance_of(
+        &proxy_addresses,
+        Token::USDCE,
+        provider,
+        config.rpc_balance_quorum,
+    )
+    .await?;
+
+    for (check, balance) in allowed.into_iter().zip(balances) {
+        let account = check.account;
+
+        match withdraw_amount.resolve(balance) {
+            Some(amount) => plans.push(WithdrawalPlan {
+                account,
+                recipient: check.recipient,
+                label: check.label.as_deref().unwrap_or_default(),
+                balance,
+                amount,
+            }),
+            None => tracing::warn!(
+                "{} | Nothing to withdraw ({withdraw_amount}) from a balance of {} USDC.e",
+                account.proxy_address,
+                format_units(balance, "mwei")?
+            ),
+        }
+    }
+
+    if plans.is_empty() {
+        tracing::warn!("No withdrawals to make");
+        return Ok(());
+    }
+
+    print_plans(&plans)?;
 
-    while !db.0.is_empty() {
-        let index = rng.gen_range(0..db.0.len());
-        let account = &db.0[index];
+    let confirmed = Confirm::with_theme(&theme)
+        .with_prompt(format!("Execute {} withdrawals?", plans.len()))
+        .default(false)
+        .interact()?;
 
-        match withdraw_full_balance(account, provider.clone()).await {
-            Ok(_) => {
-                db.0.remove(index);
-                pretty_sleep(config.withdraw_delay_range).await;
+    if !confirmed {
+        return Ok(());
+    }
+
+    plans.shuffle(&mut thread_rng());
+
+    let mut report = vec![];
+
+    for (i, plan) in plans.iter().enumerate() {
+        let mut attempts = 0;
+
+        let outcome = loop {
+            attempts += 1;
+
+            // The Safe nonce the attempt is signed with, fetched the same way `withdraw` does
+            let nonce = match relayer_nonce(plan.account).await {
+                Ok(nonce) => nonce,
+                Err(e) => break WithdrawalOutcome::Failed(e.to_string()),
+            };
+            let started_at = Utc::now();
+
+            let e = match withdraw(plan.account, plan.recipient, plan.amount)
+                .instrument(plan.account.span())
+                .await
+            {
+                Ok(tx_hash) => break WithdrawalOutcome::Withdrawn(tx_hash),
+                Err(e) => e,
+            };
+
+            tracing::error!(
+                "{} | Withdrawal attempt {attempts}/{} failed: {e}",
+                plan.account.proxy_address,
+                config.withdraw_max_retries
+            );
+
+            if attempts >= config.withdraw_max_retries {
+                break WithdrawalOutcome::Failed(e.to_string());
             }
-            Err(e) => {
-                tracing::error!("Withdrawal failed: {e}")
+
+            match find_submitted_withdrawal(plan.account, started_at, nonce).await {
+                Ok(None) => {}
+                Ok(Some(evidence)) => {
+                    break WithdrawalOutcome::Unverified(format!("{e}, but {evidence}"))
+                }
+                Err(check_error) => {
+                    break WithdrawalOutcome::Unverified(format!(
+                        "{e}, and whether it was submitted couldn't be checked: {check_error}"
+                    ))
+                }
             }
+
+            pretty_sleep(config.withdraw_delay_range).await;
+        };
+
+        report.push((plan, attempts, outcome));
+
+        if i + 1 < plans.len() {
+            pretty_sleep(config.withdraw_delay_range).await;
         }
     }
 
+    print_report(&report)?;
+
     Ok(())
 }
 
-pub async fn withdraw_full_balance<P, T>(account: &Account, provider: Arc<P>) -> eyre::Result<()>
-where
-    P: Provider<T, Ethereum>,
-    T: Transport + Clone,
-{
-    let proxy_wallet_address = account.get_proxy_address();
-    let evm_client = EvmClient::new(provider, account.get_private_key(), NamedChain::Polygon);
+fn prompt_withdraw_amount() -> eyre::Result<WithdrawAmount> {
+    let theme = ColorfulTheme::default();
+
+    let mode = Select::with_theme(&theme)
+        .with_prompt("Withdraw:")
+        .items(&[
+            "Full balance",
+            "Exact amount",
+            "Percentage of balance",
+            "Everything above a reserve",
+        ])
+        .default(0)
+        .interact()?;
+
+    let prompt_positive = |prompt: &str| -> eyre::Result<f64> {
+        Ok(Input::with_theme(&theme)
+            .with_prompt(prompt)
+            .validate_with(|value: &f64| -> Result<(), &str> {
+                match *value > 0.0 {
+                    true => Ok(()),
+                    false => Err("Expected a positive value"),
+                }
+            })
+            .interact_text()?)
+    };
+
+    let withdraw_amount = match mode {
+        1 => WithdrawAmount::Exact(prompt_positive("USDC.e amount per wallet:")?),
+        2 => {
+            let percentage: f64 = Input::with_theme(&theme)
+                .with_prompt("Percentage of balance:")
+                .validate_with(|value: &f64| -> Result<(), &str> {
+                    match *value > 0.0 && *value <= 100.0 {
+                        true => Ok(()),
+                        false => Err("Expected a percentage in (0, 100]"),
+                    }
+                })
+                .interact_text()?;
+            WithdrawAmount::Percentage(percentage)
+        }
+        3 => WithdrawAmount::AboveReserve(prompt_positive("USDC.e to keep in each wallet:")?),
+        _ => WithdrawAmount::Full,
+    };
+
+    Ok(withdraw_amount)
+}
 
-    let balance = evm_client
-        .get_token_balance(&Token::USDCE, Some(proxy_wallet_address))
-        .await?;
+fn print_plans(plans: &[WithdrawalPlan]) -> eyre::Result<()> {
+    let rows = plans
+        .iter()
+        .map(|plan| {
+            Ok(PlanRow {
+                proxy_wallet: plan.account.proxy_address.clone(),
+                recipient: plan.recipient.to_string(),
+                label: plan.label.to_string(),
+                balance: format_units(plan.balance, "mwei")?,
+                amount: format_units(plan.amount, "mwei")?,
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    let total = plans.iter().map(|plan| plan.amount).sum::<U256>();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+    println!(
+        "Total: {} USDC.e from {} wallets",
+        format_units(total, "mwei")?,
+        plans.len()
+    );
+
+    Ok(())
+}
+
+// A failed attempt is only retried when nothing shows it reached the relayer: no transaction was
+// tracked for the wallet since it started and the Safe nonce hasn't moved. The retry is then signed
+// with the same nonce, so if the attempt still sits in the relayer queue only one of them can execute.
+async fn find_submitted_withdrawal(
+    account: &Account,
+    started_at: DateTime<Utc>,
+    nonce: u64,
+) -> eyre::Result<Option<String>> {
+    let tracked = RelayerTransactionStore::read()?
+        .0
+        .into_iter()
+        .find(|record| {
+            record.kind == RelayerTransactionKind::Withdraw
+                && record
+                    .proxy_wallet
+                    .eq_ignore_ascii_case(&account.proxy_address)
+                && DateTime::parse_from_rfc3339(&record.created_at)
+                    .is_ok_and(|created_at| created_at >= started_at)
+        });
+
+    if let Some(record) = tracked {
+        return Ok(Some(format!(
+            "relayer transaction {} was submitted, not retrying",
+            record.transaction_id
+        )));
+    }
+
+    let current_nonce = relayer_nonce(account).await?;
+
+    Ok((current_nonce != nonce)
+        .then(|| format!("the Safe nonce moved from {nonce} to {current_nonce}, not retrying")))
+}
+
+async fn relayer_nonce(account: &Account) -> eyre::Result<u64> {
+    let mut amp_cookie = AmpCookie::new();
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+
+    Ok(get_nonce(
+        account.signer().address(),
+        account.proxy().as_ref(),
+        &mut amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+    )
+    .await?)
+}
+
+fn print_report(report: &[(&WithdrawalPlan, u32, WithdrawalOutcome)]) -> eyre::Result<()> {
+    let rows = report
+        .iter()
+        .map(|(plan, attempts, outcome)| {
+            Ok(ReportRow {
+                proxy_wallet: plan.account.proxy_address.clone(),
+                amount: format_units(plan.amount, "mwei")?,
+                attempts: *attempts,
+                result: match outcome {
+                    WithdrawalOutcome::Withdrawn(tx_hash) => {
+                        format!("{POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}")
+                    }
+                    WithdrawalOutcome::Failed(e) => format!("failed: {e}"),
+                    WithdrawalOutcome::Unverified(e) => {
+                        format!("check the relayer tracker: {e}")
+                    }
+                },
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    let succeeded = report
+        .iter()
+        .filter(|(_, _, outcome)| matches!(outcome, WithdrawalOutcome::Withdrawn(_)))
+        .count();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+
+    tracing::info!(
+        "Withdrawals finished: {succeeded} succeeded, {} failed",
+        report.len() - succeeded
+    );
+
+    Ok(())
+}
+
+pub async fn withdraw(account: &Account, to: Address, amount: U256) -> eyre::Result<String> {
+    let proxy_wallet_address = account.get_proxy_address();
 
     let mut amp_cookie = AmpCookie::new();
-    let polymarket_nonce = account.polymarket_nonce.as_ref().unwrap();
-    let polymarket_session = account.polymarket_session.as_ref().unwrap();
+    let polymarket_nonce = account
+        .polymarket_nonce
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
+    let polymarket_session = account
+        .polymarket_session
+        .as_ref()
+        .ok_or_else(|| eyre::eyre!("Account is not logged in, run registration first"))?;
     let proxy = account.proxy();
     let signer = account.signer();
 
-    let to = account.get_recipient_address();
-
-    let ui_amount = format_units(balance, "mwei")?;
+    let ui_amount = format_units(amount, "mwei")?;
     tracing::info!("Proxy wallet `{proxy_wallet_address}` withdrawing {ui_amount} USDC.e to {to}");
 
     let tx_hash = withdraw_usdc(
@@ -75,11 +488,11 @@ where
         polymarket_session,
         proxy.as_ref(),
         to,
-        balance,
+        amount,
     )
     .await?;
 
     tracing::info!("USDC.e withdrawn: {POLYGON_EXPLORER_TX_BASE_URL}{tx_hash}");
 
-    Ok(())
+    Ok(tx_hash)
 }
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy::{primitives::Address, sol_types::SolCall};
+use itertools::Itertools;
+use tabled::{settings::Style, Table, Tabled};
+
+use crate::{
+    db::{
+        account::Account,
+        allowlist::{parse_checksummed_address, AllowList},
+        relayer_transactions::{RelayerTransactionKind, RelayerTransactionStore},
+    },
+    onchain::client::IERC20::transferCall,
+};
+
+pub struct RecipientCheck<'a> {
+    pub account: &'a Account,
+    pub recipient: Address,
+    pub recipient_error: Option<String>,
+    pub label: Option<String>,
+    pub listed_recipient: Option<String>,
+    pub first_time: bool,
+}
+
+impl RecipientCheck<'_> {
+    // Reason the account must not withdraw, `None` when the destination passed every check
+    pub fn blocked_reason(&self) -> Option<String> {
+        if let Some(e) = &self.recipient_error {
+            return Some(e.clone());
+        }
+
+        if self.label.is_none() {
+            return Some("not allow-listed".to_string());
+        }
+
+        match self.listed_recipient.as_deref() {
+            Some(listed) => match parse_checksummed_address(listed) {
+                Ok(listed) if listed == self.recipient => None,
+                Ok(listed) => Some(format!("recipients file now lists {listed}")),
+                Err(_) => Some("recipients file entry is not checksummed".to_string()),
+            },
+            None if self.account.recipient_is_eoa() => None,
+            None => Some("missing from recipients file".to_string()),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct RecipientRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "Proxy wallet")]
+    proxy_wallet: String,
+    #[tabled(rename = "Recipient")]
+    recipient: String,
+    #[tabled(rename = "Label")]
+    label: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+pub fn check_recipients<'a>(
+    accounts: &'a [Account],
+    allow_list: &AllowList,
+    listed_recipients: &HashMap<String, String>,
+) -> eyre::Result<Vec<RecipientCheck<'a>>> {
+    let previous_destinations = previous_withdrawal_destinations()?;
+
+    let checks = accounts
+        .iter()
+        .map(|account| {
+            let (recipient, recipient_error) = match account.get_recipient_address() {
+                Ok(recipient) => (recipient, None),
+                Err(e) => (Address::ZERO, Some(e.to_string())),
+            };
+
+            RecipientCheck {
+                account,
+                recipient,
+                recipient_error,
+                label: allow_list.label_of(&recipient).map(|l| l.to_string()),
+                listed_recipient: listed_recipients.get(account.get_private_key()).cloned(),
+                first_time: !previous_destinations.contains(&recipient),
+            }
+        })
+        .collect();
+
+    Ok(checks)
+}
+
+pub fn print_recipient_mapping(checks: &[RecipientCheck]) {
+    let rows = checks
+        .iter()
+        .enumerate()
+        .map(|(index, check)| {
+            let mut status = match check.blocked_reason() {
+                Some(reason) => vec![format!("BLOCKED: {reason}")],
+                None => vec!["ok".to_string()],
+            };
+
+            if check.account.recipient_is_eoa() {
+                status.push("defaults to EOA".to_string());
+            }
+
+            if check.first_time {
+                status.push("first-time destination".to_string());
+            }
+
+            RecipientRow {
+                index,
+                proxy_wallet: check.account.proxy_address.clone(),
+                recipient: match check.recipient_error {
+                    Some(_) => "-".to_string(),
+                    None => check.recipient.to_string(),
+                },
+                label: check.label.clone().unwrap_or("-".to_string()),
+                status: status.join(", "),
+            }
+        })
+        .collect_vec();
+
+    let mut table = Table::new(&rows);
+    println!("{}", table.with(Style::modern_rounded()));
+}
+
+// Destinations of withdrawals this tool has already landed on chain
+fn previous_withdrawal_destinations() -> eyre::Result<HashSet<Address>> {
+    let destinations = RelayerTransactionStore::read()?
+        .0
+        .iter()
+        .filter(|record| {
+            record.kind == RelayerTransactionKind::Withdraw && record.last_state().is_mined()
+        })
+        .filter_map(|record| const_hex::decode(&record.data).ok())
+        .filter_map(|data| transferCall::abi_decode(&data, true).ok())
+        .map(|call| call.to)
+        .collect();
+
+    Ok(destinations)
+}
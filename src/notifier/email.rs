@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+use super::{Notification, NotificationSink};
+
+pub struct EmailSink {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailSink {
+    // Uses STARTTLS, recipients are a comma separated list
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+        from: &str,
+        to: &str,
+    ) -> eyre::Result<Self> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?.port(port);
+
+        if !username.is_empty() {
+            builder =
+                builder.credentials(Credentials::new(username.to_string(), password.to_string()));
+        }
+
+        let to = to
+            .split(',')
+            .map(|address| address.trim().parse::<Mailbox>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if to.is_empty() {
+            eyre::bail!("At least one email notification recipient has to be configured");
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: from.parse()?,
+            to,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn send(&self, notification: &Notification) -> eyre::Result<()> {
+        let mut message = Message::builder().from(self.from.clone()).subject(format!(
+            "[{}] {}",
+            notification.severity, notification.title
+        ));
+
+        for recipient in &self.to {
+            message = message.to(recipient.clone());
+        }
+
+        let message = message.body(format!(
+            "{}\n\n{}",
+            notification.message, notification.timestamp
+        ))?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use std::{fmt::Display, sync::OnceLock, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+use email::EmailSink;
+use telegram::TelegramSink;
+use webhook::WebhookSink;
+
+mod email;
+mod telegram;
+mod webhook;
+
+static NOTIFIER: OnceLock<Notifier> = OnceLock::new();
+
+const BLOCKING_NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARNING",
+            Self::Error => "ERROR",
+        };
+
+        write!(f, "{severity}")
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, notification: &Notification) -> eyre::Result<()>;
+}
+
+struct RoutedSink {
+    sink: Box<dyn NotificationSink>,
+    min_severity: Severity,
+}
+
+// Fans notifications out to every configured sink whose minimum severity they reach
+#[derive(Default)]
+pub struct Notifier {
+    sinks: Vec<RoutedSink>,
+    large_fill_usdc: f64,
+}
+
+impl Notifier {
+    pub fn from_config(config: &Config) -> eyre::Result<Self> {
+        let mut notifier = Self {
+            large_fill_usdc: config.notify_large_fill_usdc,
+            ..Default::default()
+        };
+
+        if !config.notify_webhook_url.is_empty() {
+            notifier = notifier.with_sink(
+                WebhookSink::new(&config.notify_webhook_url),
+                config.notify_webhook_min_severity,
+            );
+        }
+
+        if !config.notify_telegram_bot_token.is_empty() {
+            notifier = notifier.with_sink(
+                TelegramSink::new(
+                    &config.notify_telegram_api_url,
+                    &config.notify_telegram_bot_token,
+                    &config.notify_telegram_chat_id,
+                ),
+                config.notify_telegram_min_severity,
+            );
+        }
+
+        if !config.notify_smtp_host.is_empty() {
+            notifier = notifier.with_sink(
+                EmailSink::new(
+                    &config.notify_smtp_host,
+                    config.notify_smtp_port,
+                    &config.notify_smtp_username,
+                    &config.notify_smtp_password,
+                    &config.notify_smtp_from,
+                    &config.notify_smtp_to,
+                )?,
+                config.notify_smtp_min_severity,
+            );
+        }
+
+        Ok(notifier)
+    }
+
+    pub fn with_sink(
+        mut self,
+        sink: impl NotificationSink + 'static,
+        min_severity: Severity,
+    ) -> Self {
+        self.sinks.push(RoutedSink {
+            sink: Box::new(sink),
+            min_severity,
+        });
+        self
+    }
+
+    pub async fn dispatch(&self, notification: &Notification) {
+        for routed in &self.sinks {
+            if notification.severity < routed.min_severity {
+                continue;
+            }
+
+            if let Err(e) = routed.sink.send(notification).await {
+                tracing::warn!(
+                    "Failed to send a notification to {}: {e}",
+                    routed.sink.name()
+                );
+            }
+        }
+    }
+}
+
+pub fn init_notifier(config: &Config) -> eyre::Result<()> {
+    let notifier = Notifier::from_config(config)?;
+
+    if !notifier.sinks.is_empty() {
+        tracing::info!(
+            "Notifications go to: {}",
+            notifier
+                .sinks
+                .iter()
+                .map(|routed| format!("{} (>= {})", routed.sink.name(), routed.min_severity))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let _ = NOTIFIER.set(notifier);
+
+    install_panic_hook();
+
+    Ok(())
+}
+
+fn build_notification(
+    severity: Severity,
+    title: impl Into<String>,
+    message: impl Into<String>,
+) -> Option<(&'static Notifier, Notification)> {
+    let notifier = NOTIFIER
+        .get()
+        .filter(|notifier| !notifier.sinks.is_empty())?;
+
+    Some((
+        notifier,
+        Notification {
+            severity,
+            title: title.into(),
+            message: message.into(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+    ))
+}
+
+pub fn is_large_fill(usdc_amount: f64) -> bool {
+    NOTIFIER
+        .get()
+        .is_some_and(|notifier| usdc_amount >= notifier.large_fill_usdc)
+}
+
+// Fire and forget, a slow or failing sink never holds up trading
+pub fn notify(severity: Severity, title: impl Into<String>, message: impl Into<String>) {
+    let Some((notifier, notification)) = build_notification(severity, title, message) else {
+        return;
+    };
+
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(async move { notifier.dispatch(&notification).await });
+        }
+        Err(_) => tracing::warn!(
+            "No runtime to send the `{}` notification on",
+            notification.title
+        ),
+    }
+}
+
+// Waits for delivery, for notifications sent right before the process exits
+pub async fn notify_now(severity: Severity, title: impl Into<String>, message: impl Into<String>) {
+    if let Some((notifier, notification)) = build_notification(severity, title, message) {
+        notifier.dispatch(&notification).await;
+    }
+}
+
+// A panic on the main thread tears the runtime down before a spawned notification would run, so the
+// hook delivers it from a thread and runtime of its own and waits for that before returning
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        notify_blocking(Severity::Error, "Panic", info.to_string());
+        default_hook(info);
+    }));
+}
+
+fn notify_blocking(severity: Severity, title: impl Into<String>, message: impl Into<String>) {
+    let Some((notifier, notification)) = build_notification(severity, title, message) else {
+        return;
+    };
+
+    let delivery = std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        runtime.block_on(tokio::time::timeout(
+            BLOCKING_NOTIFICATION_TIMEOUT,
+            notifier.dispatch(&notification),
+        ))?;
+
+        eyre::Ok(())
+    });
+
+    if let Ok(Err(e)) = delivery.join() {
+        eprintln!("Failed to send the panic notification: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    };
+
+    use super::*;
+
+    struct CapturedRequest {
+        path: String,
+        body: Value,
+    }
+
+    // Local HTTP stand-in answering every request with 200 and passing what it got to the receiver
+    async fn serve() -> (String, UnboundedReceiver<CapturedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 4096];
+
+                let body_start = loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+
+                    if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                        break end + 4;
+                    }
+                };
+
+                let head = String::from_utf8_lossy(&request[..body_start]).to_string();
+                let content_length = head
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or_default();
+
+                while request.len() < body_start + content_length {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+
+                // Captured before answering, so the request is in by the time the sender sees the response
+                let _ = sender.send(CapturedRequest {
+                    path: head.split_whitespace().nth(1).unwrap().to_string(),
+                    body: serde_json::from_slice(&request[body_start..]).unwrap(),
+                });
+
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await
+                    .unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    fn notification(severity: Severity) -> Notification {
+        Notification {
+            severity,
+            title: "Order rejected".to_string(),
+            message: "0xabc | BUY order rejected".to_string(),
+            timestamp: "2026-10-18T12:00:00+00:00".to_string(),
+        }
+    }
+
+    fn drain(receiver: &mut UnboundedReceiver<CapturedRequest>) -> Vec<CapturedRequest> {
+        std::iter::from_fn(|| receiver.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_the_notification_as_json() {
+        let (url, mut receiver) = serve().await;
+        let sink = WebhookSink::new(&format!("{url}/hooks/alerts"));
+
+        sink.send(&notification(Severity::Warning)).await.unwrap();
+
+        let request = receiver.recv().await.unwrap();
+        assert_eq!(request.path, "/hooks/alerts");
+        assert_eq!(
+            request.body,
+            serde_json::json!({
+                "severity": "warning",
+                "title": "Order rejected",
+                "message": "0xabc | BUY order rejected",
+                "timestamp": "2026-10-18T12:00:00+00:00",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn telegram_calls_send_message_of_the_bot() {
+        let (url, mut receiver) = serve().await;
+        let sink = TelegramSink::new(&format!("{url}/"), "123:token", "-10042");
+
+        sink.send(&notification(Severity::Error)).await.unwrap();
+
+        let request = receiver.recv().await.unwrap();
+        assert_eq!(request.path, "/bot123:token/sendMessage");
+        assert_eq!(request.body["chat_id"], "-10042");
+        assert_eq!(
+            request.body["text"],
+            "[ERROR] Order rejected\n0xabc | BUY order rejected"
+        );
+        assert_eq!(request.body["disable_web_page_preview"], true);
+    }
+
+    #[tokio::test]
+    async fn notifications_only_reach_sinks_they_are_severe_enough_for() {
+        let (webhook_url, mut webhook_requests) = serve().await;
+        let (telegram_url, mut telegram_requests) = serve().await;
+
+        let notifier = Notifier::default()
+            .with_sink(WebhookSink::new(&webhook_url), Severity::Warning)
+            .with_sink(
+                TelegramSink::new(&telegram_url, "token", "1"),
+                Severity::Error,
+            );
+
+        for severity in [Severity::Info, Severity::Warning, Severity::Error] {
+            notifier.dispatch(&notification(severity)).await;
+        }
+
+        let webhook_severities = drain(&mut webhook_requests)
+            .into_iter()
+            .map(|request| request.body["severity"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(webhook_severities, ["warning", "error"]);
+
+        let telegram_texts = drain(&mut telegram_requests)
+            .into_iter()
+            .map(|request| request.body["text"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(telegram_texts.len(), 1);
+        assert!(telegram_texts[0].starts_with("[ERROR]"));
+    }
+
+    #[tokio::test]
+    async fn failing_sinks_dont_stop_the_others() {
+        let (url, mut receiver) = serve().await;
+
+        // Nothing listens on the port of a dropped listener
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let notifier = Notifier::default()
+            .with_sink(WebhookSink::new(&closed_url), Severity::Info)
+            .with_sink(WebhookSink::new(&url), Severity::Info);
+
+        notifier.dispatch(&notification(Severity::Info)).await;
+
+        assert_eq!(drain(&mut receiver).len(), 1);
+    }
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde_json::json;
+
+use super::{Notification, NotificationSink};
+
+// Anything speaking the Bot API `sendMessage` method works, `api_url` can point at a self-hosted server
+pub struct TelegramSink {
+    client: Client,
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(api_url: &str, bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            bot_token: bot_token.to_string(),
+            chat_id: chat_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send(&self, notification: &Notification) -> eyre::Result<()> {
+        let url = format!("{}/bot{}/sendMessage", self.api_url, self.bot_token);
+
+        self.client
+            .request(Method::POST, url)
+            .json(&json!({
+                "chat_id": self.chat_id,
+                "text": format!(
+                    "[{}] {}\n{}",
+                    notification.severity, notification.title, notification.message
+                ),
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
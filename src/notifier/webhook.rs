@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+
+use super::{Notification, NotificationSink};
+
+// POSTs the notification as JSON: `{"severity", "title", "message", "timestamp"}`
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, notification: &Notification) -> eyre::Result<()> {
+        self.client
+            .request(Method::POST, &self.url)
+            .json(notification)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
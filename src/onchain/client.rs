@@ -1,17 +1,28 @@
-use super::types::token::Token;
+use super::types::{
+    gas::{GasSettings, StuckTransactionAction},
+    token::Token,
+};
 use alloy::{
     network::{Ethereum, EthereumWallet, TransactionBuilder},
-    primitives::{Address, Bytes, U256},
+    primitives::{Address, Bytes, TxHash, U256},
     providers::Provider,
-    rpc::types::TransactionRequest,
+    rpc::types::{TransactionReceipt, TransactionRequest},
     signers::local::PrivateKeySigner,
     sol,
     sol_types::SolCall,
     transports::Transport,
 };
 use alloy_chains::NamedChain;
-use std::{marker::PhantomData, str::FromStr, sync::Arc};
-use IERC20::transferCall;
+use std::{
+    marker::PhantomData,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use IERC20::{approveCall, transferCall};
+
+const CANCEL_GAS_LIMIT: u128 = 21_000;
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(3);
 
 sol! {
     #[sol(rpc)]
@@ -35,6 +46,7 @@ where
     chain: NamedChain,
     signer: PrivateKeySigner,
     wallet: EthereumWallet,
+    gas_settings: GasSettings,
     _marker: PhantomData<T>,
 }
 
@@ -52,16 +64,25 @@ where
             wallet,
             signer,
             chain,
+            gas_settings: GasSettings::default(),
             _marker: PhantomData,
         }
     }
 
+    pub fn with_gas_settings(mut self, gas_settings: GasSettings) -> Self {
+        self.gas_settings = gas_settings;
+        self
+    }
+
     pub fn address(&self) -> Address {
         self.signer.address()
     }
 
-    #[allow(unused)]
-    async fn get_allowance(
+    pub fn provider(&self) -> Arc<P> {
+        self.provider.clone()
+    }
+
+    pub async fn get_allowance(
         &self,
         token: &Token,
         owner: Option<Address>,
@@ -100,6 +121,10 @@ where
         value: U256,
     ) -> eyre::Result<bool> {
         let eip1559_fees = self.provider.estimate_eip1559_fees(None).await?;
+        let (mut max_fee_per_gas, mut max_priority_fee_per_gas) = self.gas_settings.cap_fees(
+            eip1559_fees.max_fee_per_gas,
+            eip1559_fees.max_priority_fee_per_gas,
+        );
 
         let nonce = self
             .provider
@@ -107,8 +132,8 @@ where
             .await?;
 
         let mut tx_request = TransactionRequest::default()
-            .with_max_fee_per_gas(eip1559_fees.max_fee_per_gas)
-            .with_max_priority_fee_per_gas(eip1559_fees.max_priority_fee_per_gas)
+            .with_max_fee_per_gas(max_fee_per_gas)
+            .with_max_priority_fee_per_gas(max_priority_fee_per_gas)
             .with_to(to)
             .with_value(value)
             .with_nonce(nonce)
@@ -122,18 +147,84 @@ where
         let gas_limit = self.provider.estimate_gas(&tx_request).await?;
         tx_request.set_gas_limit(gas_limit);
 
-        let signed_transaction = tx_request.build(&self.wallet).await?;
-        let pending_tx = self.provider.send_tx_envelope(signed_transaction).await?;
-        let receipt = pending_tx.get_receipt().await?;
-
         let (_, url) = self.chain.etherscan_urls().unwrap_or(("", ""));
 
-        let tx_status = receipt.status();
+        // Every transaction sent with this nonce, a replacement may lose the race to the one it replaces
+        let mut sent = vec![(self.submit(tx_request.clone()).await?, false)];
+        let mut replacements = 0;
+
+        let receipt = loop {
+            if let Some(receipt) = self.wait_for_receipt(&sent).await {
+                break receipt;
+            }
+
+            // Without replacements the transaction is waited on for as long as it takes
+            if self.gas_settings.max_replacements == 0 {
+                continue;
+            }
+
+            let (last_hash, _) = sent[sent.len() - 1];
+
+            if replacements >= self.gas_settings.max_replacements {
+                eyre::bail!(
+                    "Transaction {url}/tx/{last_hash} wasn't mined after {replacements} replacements"
+                );
+            }
+
+            let Some((bumped_max_fee, bumped_priority_fee)) = self
+                .gas_settings
+                .bump_fees(max_fee_per_gas, max_priority_fee_per_gas)
+            else {
+                eyre::bail!(
+                    "Transaction {url}/tx/{last_hash} is stuck and the fee caps leave no room to replace it"
+                );
+            };
+
+            max_fee_per_gas = bumped_max_fee;
+            max_priority_fee_per_gas = bumped_priority_fee;
+            replacements += 1;
+
+            let is_cancel = self.gas_settings.stuck_action == StuckTransactionAction::Cancel;
+            let replacement = match is_cancel {
+                true => TransactionRequest::default()
+                    .with_to(self.address())
+                    .with_value(U256::ZERO)
+                    .with_nonce(nonce)
+                    .with_chain_id(self.chain as u64)
+                    .with_from(self.address())
+                    .with_gas_limit(CANCEL_GAS_LIMIT),
+                false => tx_request.clone(),
+            }
+            .with_max_fee_per_gas(max_fee_per_gas)
+            .with_max_priority_fee_per_gas(max_priority_fee_per_gas);
+
+            tracing::warn!(
+                "Transaction {url}/tx/{last_hash} not mined within {}s, {} it with nonce {nonce} at {max_fee_per_gas} wei",
+                self.gas_settings.receipt_timeout.as_secs(),
+                if is_cancel { "cancelling" } else { "speeding up" }
+            );
+
+            match self.submit(replacement).await {
+                Ok(tx_hash) => sent.push((tx_hash, is_cancel)),
+                Err(e) => tracing::warn!("Replacement transaction rejected: {e}"),
+            }
+        };
+
+        let cancelled = sent
+            .iter()
+            .any(|(tx_hash, is_cancel)| *is_cancel && *tx_hash == receipt.transaction_hash);
+
+        let tx_status = receipt.status() && !cancelled;
         if tx_status {
             tracing::info!(
                 "Transaction successful: {url}/tx/{}",
                 receipt.transaction_hash
             );
+        } else if cancelled {
+            tracing::warn!(
+                "Transaction cancelled: {url}/tx/{}",
+                receipt.transaction_hash
+            );
         } else {
             tracing::error!("Transaction failed: {url}/tx/{}", receipt.transaction_hash);
         }
@@ -141,6 +232,44 @@ where
         Ok(tx_status)
     }
 
+    async fn submit(&self, tx_request: TransactionRequest) -> eyre::Result<TxHash> {
+        let signed_transaction = tx_request.build(&self.wallet).await?;
+        let pending_tx = self.provider.send_tx_envelope(signed_transaction).await?;
+
+        Ok(*pending_tx.tx_hash())
+    }
+
+    // Polls until any of `sent` is mined or the receipt timeout runs out, failed polls are only logged
+    async fn wait_for_receipt(&self, sent: &[(TxHash, bool)]) -> Option<TransactionReceipt> {
+        let deadline = Instant::now() + self.gas_settings.receipt_timeout;
+
+        while Instant::now() < deadline {
+            for (tx_hash, _) in sent {
+                match self.provider.get_transaction_receipt(*tx_hash).await {
+                    Ok(Some(receipt)) => return Some(receipt),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to fetch the receipt of {tx_hash}: {e}"),
+                }
+            }
+
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+
+        None
+    }
+
+    pub async fn approve(
+        &self,
+        token: &Token,
+        spender: Address,
+        amount: U256,
+    ) -> eyre::Result<bool> {
+        let input = approveCall { spender, amount }.abi_encode();
+
+        self.send_transaction(token.contract_address, Some(input.into()), U256::ZERO)
+            .await
+    }
+
     pub async fn transfer(&self, to: Address, value: U256, token: &Token) -> eyre::Result<bool> {
         let result = match token.is_erc20 {
             true => {
@@ -1,6 +1,7 @@
 use alloy::primitives::{address, bytes, Address, Bytes};
 
 pub const USDCE_CONTRACT_ADDRESS: Address = address!("2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
+pub const USDC_CONTRACT_ADDRESS: Address = address!("3c499c542cEF5E3811e1192ce70d8cC03d5c3359");
 pub const MULTICALL_CONTRACT_ADDRESS: Address =
     address!("cA11bde05977b3631167028862bE2a173976CA11");
 pub const POLYGON_EXPLORER_TX_BASE_URL: &str = "https://polygonscan.com/tx/";
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use alloy::{
+    network::Ethereum,
+    primitives::{Address, U256},
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+    transports::Transport,
+};
+use chrono::Utc;
+
+use super::{client::EvmClient, types::token::Token};
+
+const SWAP_DEADLINE_SECONDS: i64 = 20 * 60;
+
+sol! {
+    #[sol(rpc)]
+    contract IUniswapV2Router02 {
+        function getAmountsOut(uint256 amountIn, address[] calldata path) external view returns (uint256[] memory amounts);
+        function swapExactTokensForTokens(uint256 amountIn, uint256 amountOutMin, address[] calldata path, address to, uint256 deadline) external returns (uint256[] memory amounts);
+    }
+}
+
+pub async fn quote_swap<P, T>(
+    provider: Arc<P>,
+    router: Address,
+    from: &Token,
+    to: &Token,
+    amount_in: U256,
+) -> eyre::Result<U256>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let router = IUniswapV2Router02::new(router, provider);
+    let amounts = router
+        .getAmountsOut(amount_in, vec![from.contract_address, to.contract_address])
+        .call()
+        .await?
+        .amounts;
+
+    amounts
+        .last()
+        .copied()
+        .ok_or_else(|| eyre::eyre!("Router returned no quote for {from} -> {to}"))
+}
+
+// Swaps `amount_in` of `from` into `to` on a Uniswap V2 compatible router, approving it first if needed
+pub async fn swap_exact_tokens<P, T>(
+    client: &EvmClient<P, T>,
+    router: Address,
+    from: &Token,
+    to: &Token,
+    amount_in: U256,
+    slippage_percent: f64,
+) -> eyre::Result<bool>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let quote = quote_swap(client.provider(), router, from, to, amount_in).await?;
+    let slippage_bps = U256::from((slippage_percent * 100.0).round() as u64);
+    let amount_out_min = quote * (U256::from(10_000) - slippage_bps) / U256::from(10_000);
+
+    tracing::info!(
+        "{} | Swapping {} {} for at least {} {}",
+        client.address(),
+        from.to_ui_amount(amount_in),
+        from.symbol,
+        to.to_ui_amount(amount_out_min),
+        to.symbol
+    );
+
+    let allowance = client.get_allowance(from, None, router).await?;
+
+    if allowance < amount_in && !client.approve(from, router, amount_in).await? {
+        eyre::bail!("Failed to approve {} for the DEX router", from.symbol);
+    }
+
+    let deadline = U256::from(Utc::now().timestamp() + SWAP_DEADLINE_SECONDS);
+
+    let input = IUniswapV2Router02::swapExactTokensForTokensCall {
+        amountIn: amount_in,
+        amountOutMin: amount_out_min,
+        path: vec![from.contract_address, to.contract_address],
+        to: client.address(),
+        deadline,
+    }
+    .abi_encode();
+
+    client
+        .send_transaction(router, Some(input.into()), U256::ZERO)
+        .await
+}
@@ -1,4 +1,7 @@
 pub mod client;
 pub mod constants;
+pub mod dex;
 pub mod multicall;
+pub mod neg_risk;
+pub mod rpc;
 pub mod types;
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use alloy::{
     dyn_abi::SolType,
     network::Ethereum,
-    primitives::{address, Address, U256},
+    primitives::{Address, U256},
     providers::Provider,
     sol,
     sol_types::{SolCall, SolValue},
@@ -14,8 +14,11 @@ use itertools::Itertools;
 use crate::{
     onchain::constants::EXPECTED_CHECK_APPROVALS_RESULT,
     polymarket::api::relayer::constants::{
-        CONDITIONAL_TOKENS_CONTRACT_ADDRESS, UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+        CONDITIONAL_TOKENS_CONTRACT_ADDRESS, CTF_EXCHANGE_CONTRACT_ADDRESS,
+        NEG_RISK_ADAPTER_CONTRACT_ADDRESS, NEG_RISK_CTF_EXCHANGE_CONTRACT_ADDRESS,
+        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
     },
+    utils::metrics::METRICS,
 };
 
 use super::{
@@ -41,16 +44,104 @@ sol! {
     #[sol(rpc)]
     contract Multicall3 {
         function aggregate3(Call3[] calldata calls) public payable returns (Result[] memory returnData);
+        function getEthBalance(address addr) public view returns (uint256 balance);
     }
 
     function isApprovedForAll(address owner, address operator) external view returns (bool);
 }
 
-const CTF_EXCHANGE_CONTRACT_ADDRESS: Address = address!("4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E");
-const NEG_RISK_CTF_EXCHANGE_CONTRACT_ADDRESS: Address =
-    address!("C5d563A36AE78145C45a50134d48A1215220f80a");
-const NEG_RISK_ADAPTER_CONTRACT_ADDRESS: Address =
-    address!("d91E80cF2E7be2e162c6513ceD06f1dD0dA35296");
+// Wallets audited per aggregate3 call, keeps the batched eth_call under RPC gas limits
+const AUDIT_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalKind {
+    // USDC.e `approve` of the spender
+    Allowance,
+    // ConditionalTokens `setApprovalForAll` of the spender
+    Operator,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ApprovalTarget {
+    pub label: &'static str,
+    pub kind: ApprovalKind,
+    pub spender: Address,
+}
+
+// Same seven approvals, in the same order, as the relayer approve bundle
+pub const APPROVAL_TARGETS: [ApprovalTarget; 7] = [
+    ApprovalTarget {
+        label: "USDC.e -> CTF",
+        kind: ApprovalKind::Allowance,
+        spender: CONDITIONAL_TOKENS_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "USDC.e -> Exchange",
+        kind: ApprovalKind::Allowance,
+        spender: CTF_EXCHANGE_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "CTF -> Exchange",
+        kind: ApprovalKind::Operator,
+        spender: CTF_EXCHANGE_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "USDC.e -> NegRisk Exchange",
+        kind: ApprovalKind::Allowance,
+        spender: NEG_RISK_CTF_EXCHANGE_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "USDC.e -> NegRisk Adapter",
+        kind: ApprovalKind::Allowance,
+        spender: NEG_RISK_ADAPTER_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "CTF -> NegRisk Exchange",
+        kind: ApprovalKind::Operator,
+        spender: NEG_RISK_CTF_EXCHANGE_CONTRACT_ADDRESS,
+    },
+    ApprovalTarget {
+        label: "CTF -> NegRisk Adapter",
+        kind: ApprovalKind::Operator,
+        spender: NEG_RISK_ADAPTER_CONTRACT_ADDRESS,
+    },
+];
+
+#[derive(Debug, Clone)]
+pub enum ApprovalState {
+    Allowance(U256),
+    Operator(bool),
+    Unknown,
+}
+
+impl ApprovalState {
+    // Any allowance counts, the approve bundle grants the max and trades draw it down
+    pub fn is_granted(&self) -> bool {
+        match self {
+            Self::Allowance(allowance) => !allowance.is_zero(),
+            Self::Operator(approved) => *approved,
+            Self::Unknown => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WalletAudit {
+    pub proxy_wallet: Address,
+    pub usdce_balance: Option<U256>,
+    pub pol_balance: Option<U256>,
+    pub approvals: Vec<(ApprovalTarget, ApprovalState)>,
+}
+
+impl WalletAudit {
+    pub fn missing_approvals(&self) -> Vec<&ApprovalTarget> {
+        self.approvals
+            .iter()
+            .filter(|(_, state)| !state.is_granted())
+            .map(|(target, _)| target)
+            .collect()
+    }
+}
 
 pub async fn multicall_balance_of<P, T>(
     addresses: &[Address],
@@ -63,13 +154,19 @@ where
 {
     let calls = addresses
         .iter()
-        .map(|address| {
-            let calldata = balanceOfCall::new((*address,)).abi_encode();
-            Call3 {
+        .map(|address| match token.is_erc20 {
+            true => Call3 {
                 target: token.contract_address,
                 allowFailure: false,
-                callData: calldata.into(),
-            }
+                callData: balanceOfCall::new((*address,)).abi_encode().into(),
+            },
+            false => Call3 {
+                target: MULTICALL_CONTRACT_ADDRESS,
+                allowFailure: false,
+                callData: Multicall3::getEthBalanceCall::new((*address,))
+                    .abi_encode()
+                    .into(),
+            },
         })
         .collect_vec();
 
@@ -85,6 +182,14 @@ where
         })
         .collect::<Vec<_>>();
 
+    for (address, balance) in addresses.iter().zip(&result) {
+        METRICS.set_wallet_balance(
+            token.symbol,
+            &address.to_string(),
+            token.to_ui_amount(*balance).parse().unwrap_or_default(),
+        );
+    }
+
     Ok(result)
 }
 
@@ -153,3 +258,96 @@ where
         false => Ok(false),
     }
 }
+
+// Proxy wallet USDC.e balance and approvals plus the owner's POL, batched through aggregate3
+pub async fn multicall_audit_wallets<P, T>(
+    wallets: &[(Address, Address)],
+    provider: Arc<P>,
+) -> eyre::Result<Vec<WalletAudit>>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let multicall_instance = Multicall3::new(MULTICALL_CONTRACT_ADDRESS, provider);
+    let calls_per_wallet = 2 + APPROVAL_TARGETS.len();
+
+    let mut audits = vec![];
+
+    for batch in wallets.chunks(AUDIT_BATCH_SIZE) {
+        let calls = batch
+            .iter()
+            .flat_map(|(proxy_wallet, owner)| {
+                let mut calls = vec![
+                    (
+                        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+                        balanceOfCall::new((*proxy_wallet,)).abi_encode(),
+                    ),
+                    (
+                        MULTICALL_CONTRACT_ADDRESS,
+                        Multicall3::getEthBalanceCall::new((*owner,)).abi_encode(),
+                    ),
+                ];
+
+                calls.extend(APPROVAL_TARGETS.iter().map(|target| match target.kind {
+                    ApprovalKind::Allowance => (
+                        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+                        allowanceCall::new((*proxy_wallet, target.spender)).abi_encode(),
+                    ),
+                    ApprovalKind::Operator => (
+                        CONDITIONAL_TOKENS_CONTRACT_ADDRESS,
+                        isApprovedForAllCall::new((*proxy_wallet, target.spender)).abi_encode(),
+                    ),
+                }));
+
+                calls
+            })
+            .map(|(address, calldata)| Call3 {
+                target: address,
+                allowFailure: true,
+                callData: calldata.into(),
+            })
+            .collect_vec();
+
+        let results = multicall_instance
+            .aggregate3(calls)
+            .call()
+            .await?
+            .returnData;
+
+        let decode_u256 = |result: &Result| {
+            result
+                .success
+                .then(|| <sol! { uint256 }>::abi_decode(&result.returnData, false).ok())
+                .flatten()
+        };
+
+        for ((proxy_wallet, _), results) in batch.iter().zip(results.chunks(calls_per_wallet)) {
+            let approvals = APPROVAL_TARGETS
+                .iter()
+                .zip(&results[2..])
+                .map(|(target, result)| {
+                    let state = match (target.kind, decode_u256(result)) {
+                        (_, None) => ApprovalState::Unknown,
+                        (ApprovalKind::Allowance, Some(allowance)) => {
+                            ApprovalState::Allowance(allowance)
+                        }
+                        (ApprovalKind::Operator, Some(flag)) => {
+                            ApprovalState::Operator(!flag.is_zero())
+                        }
+                    };
+
+                    (*target, state)
+                })
+                .collect();
+
+            audits.push(WalletAudit {
+                proxy_wallet: *proxy_wallet,
+                usdce_balance: decode_u256(&results[0]),
+                pol_balance: decode_u256(&results[1]),
+                approvals,
+            });
+        }
+    }
+
+    Ok(audits)
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use alloy::{
+    network::Ethereum,
+    primitives::{B256, U256},
+    providers::Provider,
+    sol,
+    transports::Transport,
+};
+
+use crate::polymarket::api::relayer::constants::NEG_RISK_ADAPTER_CONTRACT_ADDRESS;
+
+sol! {
+    #[sol(rpc)]
+    contract INegRiskAdapter {
+        function getFeeBips(bytes32 _marketId) external view returns (uint256);
+    }
+}
+
+// Share of every converted amount the adapter keeps, in bps of the market
+pub async fn get_neg_risk_fee_bips<P, T>(provider: Arc<P>, market_id: B256) -> eyre::Result<u64>
+where
+    P: Provider<T, Ethereum>,
+    T: Transport + Clone,
+{
+    let adapter = INegRiskAdapter::new(NEG_RISK_ADAPTER_CONTRACT_ADDRESS, provider);
+    let fee_bips = adapter.getFeeBips(market_id).call().await?._0;
+
+    if fee_bips > U256::from(10_000) {
+        eyre::bail!("Adapter reported a fee of {fee_bips} bps for {market_id}");
+    }
+
+    Ok(fee_bips.to::<u64>())
+}
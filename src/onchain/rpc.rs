@@ -0,0 +1,324 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    primitives::{Address, U256},
+    providers::{Provider, RootProvider},
+    rpc::{
+        client::RpcClient,
+        json_rpc::{RequestPacket, ResponsePacket},
+    },
+    transports::{
+        http::{Client, Http},
+        TransportError, TransportErrorKind, TransportFut,
+    },
+};
+use itertools::Itertools;
+use reqwest::Url;
+use tabled::{settings::Style, Table, Tabled};
+use tower::Service;
+
+use super::{multicall::multicall_balance_of, types::token::Token};
+
+pub type PolygonProvider = RootProvider<FailoverTransport>;
+
+// Weight of the latest sample in the moving averages of latency and error rate
+const SMOOTHING: f64 = 0.2;
+const UNMEASURED_LATENCY_MS: f64 = 1_000.0;
+const LAG_PENALTY_MS_PER_BLOCK: f64 = 250.0;
+const LAGGING_PENALTY_MS: f64 = 60_000.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealth {
+    pub requests: u64,
+    pub errors: u64,
+    pub latency_ms: Option<f64>,
+    pub error_rate: f64,
+    pub head_block: Option<u64>,
+    pub head_lag: Option<u64>,
+}
+
+impl EndpointHealth {
+    fn record(&mut self, latency: Duration, success: bool) {
+        let latency_ms = latency.as_secs_f64() * 1_000.0;
+
+        self.requests += 1;
+        self.errors += u64::from(!success);
+        self.error_rate += SMOOTHING * (f64::from(u8::from(!success)) - self.error_rate);
+
+        if success {
+            self.latency_ms = Some(match self.latency_ms {
+                Some(average) => average + SMOOTHING * (latency_ms - average),
+                None => latency_ms,
+            });
+        }
+    }
+
+    // Lower is better, endpoints lagging more than `max_head_lag` blocks only serve as a last resort
+    pub fn score(&self, max_head_lag: u64) -> f64 {
+        let latency_ms = self.latency_ms.unwrap_or(UNMEASURED_LATENCY_MS);
+
+        let lag_penalty = match self.head_lag {
+            Some(lag) if lag > max_head_lag => LAGGING_PENALTY_MS,
+            Some(lag) => lag as f64 * LAG_PENALTY_MS_PER_BLOCK,
+            None => 0.0,
+        };
+
+        latency_ms * (1.0 + 10.0 * self.error_rate) + lag_penalty
+    }
+}
+
+struct RpcEndpoint {
+    url: Url,
+    http: Http<Client>,
+    health: Mutex<EndpointHealth>,
+}
+
+impl RpcEndpoint {
+    fn health(&self) -> EndpointHealth {
+        self.health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn update_health(&self, f: impl FnOnce(&mut EndpointHealth)) {
+        f(&mut self.health.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+#[derive(Tabled)]
+struct EndpointRow {
+    #[tabled(rename = "RPC")]
+    url: String,
+    #[tabled(rename = "Latency (ms)")]
+    latency: String,
+    #[tabled(rename = "Head block")]
+    head_block: String,
+    #[tabled(rename = "Lag")]
+    head_lag: String,
+    #[tabled(rename = "Errors")]
+    errors: String,
+    #[tabled(rename = "Score")]
+    score: String,
+}
+
+// Sends every request to the best scored endpoint and fails over to the next one on transport errors
+#[derive(Clone)]
+pub struct FailoverTransport {
+    endpoints: Arc<Vec<RpcEndpoint>>,
+    max_head_lag: u64,
+}
+
+impl FailoverTransport {
+    pub fn new(urls: &[String], max_head_lag: u64) -> eyre::Result<Self> {
+        if urls.is_empty() {
+            eyre::bail!("At least one Polygon RPC url has to be configured");
+        }
+
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                let url = Url::parse(url)?;
+
+                Ok(RpcEndpoint {
+                    http: Http::new(url.clone()),
+                    url,
+                    health: Mutex::new(EndpointHealth::default()),
+                })
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            endpoints: Arc::new(endpoints),
+            max_head_lag,
+        })
+    }
+
+    pub fn into_provider(self) -> PolygonProvider {
+        RootProvider::new(RpcClient::new(self, false))
+    }
+
+    fn ranked_endpoints(&self) -> Vec<&RpcEndpoint> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| (endpoint, endpoint.health().score(self.max_head_lag)))
+            .sorted_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(endpoint, _)| endpoint)
+            .collect()
+    }
+
+    // Single endpoint providers of the endpoints that are currently in sync with the chain head
+    pub fn healthy_providers(&self) -> Vec<(Url, RootProvider<Http<Client>>)> {
+        self.ranked_endpoints()
+            .into_iter()
+            .filter(|endpoint| {
+                endpoint
+                    .health()
+                    .head_lag
+                    .is_none_or(|lag| lag <= self.max_head_lag)
+            })
+            .map(|endpoint| {
+                (
+                    endpoint.url.clone(),
+                    RootProvider::new(RpcClient::new(endpoint.http.clone(), false)),
+                )
+            })
+            .collect()
+    }
+
+    // Probes the head block of every endpoint, measuring latency and lag behind the highest head seen
+    pub async fn check_health(&self) {
+        for endpoint in self.endpoints.iter() {
+            let provider: RootProvider<Http<Client>> =
+                RootProvider::new(RpcClient::new(endpoint.http.clone(), false));
+
+            let started_at = Instant::now();
+            let head_block = provider.get_block_number().await;
+            let latency = started_at.elapsed();
+
+            endpoint.update_health(|health| {
+                health.record(latency, head_block.is_ok());
+                health.head_block = head_block.as_ref().ok().copied();
+            });
+
+            if let Err(e) = head_block {
+                tracing::warn!("RPC {} failed its health check: {e}", endpoint.url);
+            }
+        }
+
+        let highest_head = self
+            .endpoints
+            .iter()
+            .filter_map(|endpoint| endpoint.health().head_block)
+            .max();
+
+        for endpoint in self.endpoints.iter() {
+            endpoint.update_health(|health| {
+                health.head_lag = match (health.head_block, highest_head) {
+                    (Some(head), Some(highest)) => Some(highest.saturating_sub(head)),
+                    _ => None,
+                };
+            });
+        }
+    }
+
+    pub fn spawn_health_checks(&self, interval: Duration) {
+        let transport = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                transport.check_health().await;
+            }
+        });
+    }
+
+    pub fn print_health(&self) {
+        let rows = self
+            .ranked_endpoints()
+            .into_iter()
+            .map(|endpoint| {
+                let health = endpoint.health();
+                let fmt_opt = |value: Option<u64>| value.map_or("-".to_string(), |v| v.to_string());
+
+                EndpointRow {
+                    url: endpoint.url.to_string(),
+                    latency: health
+                        .latency_ms
+                        .map_or("-".to_string(), |latency| format!("{latency:.0}")),
+                    head_block: fmt_opt(health.head_block),
+                    head_lag: fmt_opt(health.head_lag),
+                    errors: format!("{}/{}", health.errors, health.requests),
+                    score: format!("{:.0}", health.score(self.max_head_lag)),
+                }
+            })
+            .collect_vec();
+
+        let mut table = Table::new(&rows);
+        println!("{}", table.with(Style::modern_rounded()));
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let transport = self.clone();
+
+        Box::pin(async move {
+            let mut last_error = None;
+
+            for endpoint in transport.ranked_endpoints() {
+                let started_at = Instant::now();
+                let response = (&endpoint.http).call(request.clone()).await;
+                let latency = started_at.elapsed();
+
+                endpoint.update_health(|health| health.record(latency, response.is_ok()));
+
+                match response {
+                    Ok(response) => return Ok(response),
+                    Err(e) => {
+                        tracing::warn!("RPC {} failed, failing over: {e}", endpoint.url);
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            Err(last_error
+                .unwrap_or_else(|| TransportErrorKind::custom_str("No RPC endpoints configured")))
+        })
+    }
+}
+
+// Balances agreed on by at least `quorum` healthy endpoints, plain failover reads when `quorum` is below 2
+pub async fn quorum_balance_of(
+    addresses: &[Address],
+    token: Token,
+    provider: Arc<PolygonProvider>,
+    quorum: usize,
+) -> eyre::Result<Vec<U256>> {
+    if quorum < 2 {
+        return multicall_balance_of(addresses, token, provider).await;
+    }
+
+    let mut readings = vec![];
+
+    for (url, endpoint_provider) in provider.client().transport().healthy_providers() {
+        match multicall_balance_of(addresses, token.clone(), Arc::new(endpoint_provider)).await {
+            Ok(balances) => readings.push(balances),
+            Err(e) => tracing::warn!("RPC {url} failed a quorum read: {e}"),
+        }
+    }
+
+    if readings.len() < quorum {
+        eyre::bail!(
+            "Only {} RPCs answered the balance read, {quorum} are required",
+            readings.len()
+        );
+    }
+
+    addresses
+        .iter()
+        .enumerate()
+        .map(|(i, address)| {
+            readings
+                .iter()
+                .map(|balances| balances[i])
+                .counts()
+                .into_iter()
+                .find(|(_, votes)| *votes >= quorum)
+                .map(|(balance, _)| balance)
+                .ok_or_else(|| eyre::eyre!("RPCs disagree on the {} balance of {address}", token))
+        })
+        .collect()
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::config::Config;
+
+const GWEI: f64 = 1e9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StuckTransactionAction {
+    #[default]
+    SpeedUp,
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+pub struct GasSettings {
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub receipt_timeout: Duration,
+    pub max_replacements: u32,
+    pub bump_percent: u128,
+    pub stuck_action: StuckTransactionAction,
+}
+
+// No caps and no replacements, flows that want stuck transactions replaced opt in through the config
+impl Default for GasSettings {
+    fn default() -> Self {
+        Self {
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            receipt_timeout: Duration::from_secs(90),
+            max_replacements: 0,
+            bump_percent: 15,
+            stuck_action: StuckTransactionAction::default(),
+        }
+    }
+}
+
+impl GasSettings {
+    // Estimated fees clamped to the configured caps, the priority fee never exceeds the max fee
+    pub fn cap_fees(&self, max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> (u128, u128) {
+        let max_fee_per_gas = self
+            .max_fee_per_gas
+            .map_or(max_fee_per_gas, |cap| max_fee_per_gas.min(cap));
+        let max_priority_fee_per_gas = self
+            .max_priority_fee_per_gas
+            .map_or(max_priority_fee_per_gas, |cap| {
+                max_priority_fee_per_gas.min(cap)
+            })
+            .min(max_fee_per_gas);
+
+        (max_fee_per_gas, max_priority_fee_per_gas)
+    }
+
+    // Fees for a same-nonce replacement, `None` once the caps leave no room for the bump nodes require
+    pub fn bump_fees(
+        &self,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> Option<(u128, u128)> {
+        // A percentage of a zero priority fee is still zero, which nodes don't take as a bump
+        let bump = |fee: u128| (fee + fee * self.bump_percent / 100).max(fee + GWEI as u128);
+        let (bumped_max_fee, bumped_priority_fee) =
+            (bump(max_fee_per_gas), bump(max_priority_fee_per_gas));
+
+        let (capped_max_fee, capped_priority_fee) =
+            self.cap_fees(bumped_max_fee, bumped_priority_fee);
+
+        match capped_max_fee == bumped_max_fee && capped_priority_fee == bumped_priority_fee {
+            true => Some((capped_max_fee, capped_priority_fee)),
+            false => None,
+        }
+    }
+}
+
+impl From<&Config> for GasSettings {
+    fn from(config: &Config) -> Self {
+        let to_wei = |gwei: f64| (gwei > 0.0).then_some((gwei * GWEI) as u128);
+
+        Self {
+            max_fee_per_gas: to_wei(config.max_fee_per_gas_gwei),
+            max_priority_fee_per_gas: to_wei(config.max_priority_fee_per_gas_gwei),
+            receipt_timeout: Duration::from_secs(config.receipt_timeout_secs),
+            max_replacements: config.max_gas_replacements,
+            bump_percent: config.gas_bump_percent as u128,
+            stuck_action: match config.cancel_stuck_transactions {
+                true => StuckTransactionAction::Cancel,
+                false => StuckTransactionAction::SpeedUp,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_GWEI: u128 = GWEI as u128;
+
+    #[test]
+    fn zero_priority_fee_is_bumped_by_at_least_a_gwei() {
+        let settings = GasSettings::default();
+
+        assert_eq!(
+            settings.bump_fees(100 * ONE_GWEI, 0),
+            Some((115 * ONE_GWEI, ONE_GWEI))
+        );
+    }
+
+    #[test]
+    fn bumps_past_the_caps_are_refused() {
+        let settings = GasSettings {
+            max_fee_per_gas: Some(110 * ONE_GWEI),
+            ..Default::default()
+        };
+
+        assert_eq!(settings.bump_fees(100 * ONE_GWEI, 30 * ONE_GWEI), None);
+    }
+
+    #[test]
+    fn no_replacements_by_default() {
+        assert_eq!(GasSettings::default().max_replacements, 0);
+    }
+}
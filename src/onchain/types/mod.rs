@@ -1 +1,2 @@
+pub mod gas;
 pub mod token;
@@ -1,9 +1,10 @@
 use std::fmt::Display;
 
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{utils::format_units, Address, U256};
 
-use crate::onchain::constants::USDCE_CONTRACT_ADDRESS;
+use crate::onchain::constants::{USDCE_CONTRACT_ADDRESS, USDC_CONTRACT_ADDRESS};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Token {
     pub contract_address: Address,
     pub decimals: u8,
@@ -12,16 +13,36 @@ pub struct Token {
 }
 
 impl Token {
+    // Bridged USDC, the only collateral Polymarket accepts
     pub const USDCE: Token = Token {
         contract_address: USDCE_CONTRACT_ADDRESS,
         decimals: 6,
+        symbol: "USDC.e",
+        is_erc20: true,
+    };
+
+    // Circle's native USDC, has to be converted to USDC.e before it can be deposited
+    pub const USDC: Token = Token {
+        contract_address: USDC_CONTRACT_ADDRESS,
+        decimals: 6,
         symbol: "USDC",
         is_erc20: true,
     };
 
+    pub const POL: Token = Token {
+        contract_address: Address::ZERO,
+        decimals: 18,
+        symbol: "POL",
+        is_erc20: false,
+    };
+
     pub fn to_wei(&self, amount: f64) -> U256 {
         U256::from(amount * 10f64.powi(self.decimals as i32))
     }
+
+    pub fn to_ui_amount(&self, amount: U256) -> String {
+        format_units(amount, self.decimals).unwrap_or_else(|_| "0".to_string())
+    }
 }
 
 impl Display for Token {
@@ -0,0 +1,289 @@
+use super::{
+    math::{calculate_accumulated_values, sort_orders, ClobPrecision},
+    schemas::{AccumulatedOrder, OrderBookData},
+    typedefs::Side,
+};
+
+#[derive(Debug, Clone)]
+pub struct BookAnalytics {
+    pub bids: Vec<AccumulatedOrder>,
+    pub asks: Vec<AccumulatedOrder>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Depth {
+    pub size: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FillEstimate {
+    pub vwap: f64,
+    pub filled_size: f64,
+    pub filled_value: f64,
+    pub worst_price: f64,
+    pub fully_filled: bool,
+    pub impact: Option<f64>,
+}
+
+impl BookAnalytics {
+    pub fn new(mut book_data: OrderBookData) -> Self {
+        let sorted_bids = sort_orders(Side::Buy, &mut book_data.bids);
+        let sorted_asks = sort_orders(Side::Sell, &mut book_data.asks);
+
+        Self {
+            bids: calculate_accumulated_values(&sorted_bids),
+            asks: calculate_accumulated_values(&sorted_asks),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<&AccumulatedOrder> {
+        self.bids.first()
+    }
+
+    pub fn best_ask(&self) -> Option<&AccumulatedOrder> {
+        self.asks.first()
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some((ask.price - bid.price).round_normal(4))
+    }
+
+    pub fn midpoint(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some(((bid.price + ask.price) / 2.0).round_normal(4))
+    }
+
+    // Top of book weighted by the opposite side's size, leans towards the side that is about to be taken out
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let total_size = bid.size + ask.size;
+
+        if total_size == 0.0 {
+            return self.midpoint();
+        }
+
+        Some(((bid.price * ask.size + ask.price * bid.size) / total_size).round_normal(4))
+    }
+
+    // Walks the side of the book that would be taken by an order on `side` until `notional` USDC is spent (buy) or received (sell)
+    pub fn vwap(&self, side: Side, notional: f64) -> Option<FillEstimate> {
+        let levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+
+        if levels.is_empty() || notional <= 0.0 {
+            return None;
+        }
+
+        let mut filled_size = 0.0;
+        let mut filled_value = 0.0;
+        let mut worst_price = levels[0].price;
+
+        for level in levels {
+            let remaining = notional - filled_value;
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let level_value = level.size * level.price;
+            worst_price = level.price;
+
+            if level_value >= remaining {
+                filled_size += remaining / level.price;
+                filled_value += remaining;
+                break;
+            }
+
+            filled_size += level.size;
+            filled_value += level_value;
+        }
+
+        let vwap = filled_value / filled_size;
+
+        Some(FillEstimate {
+            vwap: vwap.round_normal(4),
+            filled_size: filled_size.round_normal(2),
+            filled_value: filled_value.round_normal(2),
+            worst_price,
+            fully_filled: filled_value >= notional - f64::EPSILON,
+            impact: self
+                .midpoint()
+                .map(|mid| (vwap - mid).abs().round_normal(4)),
+        })
+    }
+
+    // Liquidity resting on `side` within `cents` of that side's best price
+    pub fn depth_within(&self, side: Side, cents: f64) -> Depth {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+
+        let Some(best) = levels.first() else {
+            return Depth::default();
+        };
+
+        let max_distance = cents / 100.0 + f64::EPSILON;
+
+        levels
+            .iter()
+            .take_while(|level| (level.price - best.price).abs() <= max_distance)
+            .last()
+            .map(|level| Depth {
+                size: level.net_size,
+                value: level.net_value,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn depth_curve(&self, side: Side, steps: &[f64]) -> Vec<(f64, Depth)> {
+        steps
+            .iter()
+            .map(|cents| (*cents, self.depth_within(side.clone(), *cents)))
+            .collect()
+    }
+
+    // (bid depth - ask depth) / total depth within `cents`, ranges from -1 (all asks) to 1 (all bids)
+    pub fn imbalance(&self, cents: f64) -> Option<f64> {
+        let bid_depth = self.depth_within(Side::Buy, cents).size;
+        let ask_depth = self.depth_within(Side::Sell, cents).size;
+        let total = bid_depth + ask_depth;
+
+        match total > 0.0 {
+            true => Some(((bid_depth - ask_depth) / total).round_normal(4)),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strategy::test_book;
+
+    use super::*;
+
+    // Levels are listed out of order on purpose, the analytics sort them
+    fn analytics() -> BookAnalytics {
+        BookAnalytics::new(test_book(
+            "0xmarket",
+            "yes",
+            &[(0.47, 100.0), (0.49, 50.0), (0.48, 30.0)],
+            &[(0.53, 40.0), (0.51, 20.0), (0.52, 10.0)],
+        ))
+    }
+
+    fn one_sided() -> BookAnalytics {
+        BookAnalytics::new(test_book("0xmarket", "yes", &[(0.4, 25.0)], &[]))
+    }
+
+    #[test]
+    fn top_of_book_uses_the_best_levels() {
+        let book = analytics();
+
+        assert_eq!(book.best_bid().unwrap().price, 0.49);
+        assert_eq!(book.best_ask().unwrap().price, 0.51);
+        assert_eq!(book.spread(), Some(0.02));
+        assert_eq!(book.midpoint(), Some(0.5));
+        // (0.49 * 20 + 0.51 * 50) / 70
+        assert_eq!(book.microprice(), Some(0.5043));
+    }
+
+    #[test]
+    fn top_of_book_needs_both_sides() {
+        for book in [
+            one_sided(),
+            BookAnalytics::new(test_book("0xm", "yes", &[], &[])),
+        ] {
+            assert_eq!(book.spread(), None);
+            assert_eq!(book.midpoint(), None);
+            assert_eq!(book.microprice(), None);
+        }
+    }
+
+    #[test]
+    fn crossed_books_have_a_negative_spread() {
+        let book = BookAnalytics::new(test_book("0xm", "yes", &[(0.6, 10.0)], &[(0.55, 10.0)]));
+
+        assert_eq!(book.spread(), Some(-0.05));
+        assert_eq!(book.midpoint(), Some(0.575));
+    }
+
+    #[test]
+    fn vwap_walks_the_opposite_side() {
+        let fill = analytics().vwap(Side::Buy, 15.0).unwrap();
+
+        // 20 shares at 0.51 for 10.2, then 4.8 USDC at 0.52
+        assert_eq!(fill.filled_size, 29.23);
+        assert_eq!(fill.filled_value, 15.0);
+        assert_eq!(fill.vwap, 0.5132);
+        assert_eq!(fill.worst_price, 0.52);
+        assert!(fill.fully_filled);
+        assert_eq!(fill.impact, Some(0.0132));
+
+        let fill = analytics().vwap(Side::Sell, 10.0).unwrap();
+        assert_eq!(fill.vwap, 0.49);
+        assert_eq!(fill.worst_price, 0.49);
+    }
+
+    #[test]
+    fn vwap_reports_a_partial_fill_when_the_book_runs_out() {
+        let fill = analytics().vwap(Side::Buy, 100.0).unwrap();
+
+        assert_eq!(fill.filled_size, 70.0);
+        assert_eq!(fill.filled_value, 36.6);
+        assert_eq!(fill.vwap, 0.5229);
+        assert_eq!(fill.worst_price, 0.53);
+        assert!(!fill.fully_filled);
+    }
+
+    #[test]
+    fn vwap_of_an_empty_side_or_amount_is_none() {
+        assert!(one_sided().vwap(Side::Buy, 10.0).is_none());
+        assert!(analytics().vwap(Side::Buy, 0.0).is_none());
+
+        let fill = one_sided().vwap(Side::Sell, 4.0).unwrap();
+        assert_eq!(fill.filled_size, 10.0);
+        assert_eq!(fill.impact, None);
+    }
+
+    #[test]
+    fn depth_counts_levels_within_the_distance_of_the_best_price() {
+        let book = analytics();
+
+        let depth = book.depth_within(Side::Buy, 1.0);
+        assert_eq!(depth.size, 80.0);
+        assert_eq!(depth.value, 38.9);
+
+        let depth = book.depth_within(Side::Sell, 0.0);
+        assert_eq!(depth.size, 20.0);
+        assert_eq!(depth.value, 10.2);
+
+        let depth = one_sided().depth_within(Side::Sell, 5.0);
+        assert_eq!((depth.size, depth.value), (0.0, 0.0));
+    }
+
+    #[test]
+    fn depth_curve_accumulates_per_step() {
+        let curve = analytics()
+            .depth_curve(Side::Sell, &[0.0, 1.0, 2.0])
+            .into_iter()
+            .map(|(cents, depth)| (cents, depth.size))
+            .collect::<Vec<_>>();
+
+        assert_eq!(curve, [(0.0, 20.0), (1.0, 30.0), (2.0, 70.0)]);
+    }
+
+    #[test]
+    fn imbalance_leans_towards_the_deeper_side() {
+        // 80 bid shares against 30 ask shares within a cent
+        assert_eq!(analytics().imbalance(1.0), Some(0.4545));
+        assert_eq!(one_sided().imbalance(1.0), Some(1.0));
+        assert_eq!(
+            BookAnalytics::new(test_book("0xm", "yes", &[], &[])).imbalance(1.0),
+            None
+        );
+    }
+}
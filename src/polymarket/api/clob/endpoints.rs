@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use alloy::signers::Signer;
 use itertools::Itertools;
@@ -6,17 +6,27 @@ use reqwest::{Method, Proxy, StatusCode};
 
 use crate::{
     db::account::Account,
-    errors::custom::CustomError,
+    errors::{clob::ClobRejection, custom::CustomError},
+    notifier::{is_large_fill, notify, Severity},
     polymarket::api::{
         clob::schemas::OrderBookData,
         typedefs::{HeaderMapSerializeable, LayerOneClobAuthHeaders, LayerTwoClobAuthHeaders},
     },
-    utils::fetch::{send_http_request_with_retries, RequestParams},
+    risk::{check_order, check_orders, record_placed_order},
+    utils::{
+        fetch::{send_http_request, send_http_request_with_retries, RequestParams},
+        metrics::METRICS,
+    },
 };
 
-use super::schemas::{
-    ClobApiKeyResponseBody, GetTickSizeResponseBody, NegRiskResponseBody, OrderRequest,
-    PlaceOrderResponseBody, TokenId,
+use super::{
+    metadata::invalidate_market_metadata,
+    schemas::{
+        CancelOrdersResponseBody, ClobApiKeyResponseBody, ClobMarket, FeeRateResponseBody,
+        GetTickSizeResponseBody, OpenOrder, OpenOrdersResponseBody, OrderRequest, OrderStatus,
+        PlaceOrderResponseBody, TokenId,
+    },
+    typedefs::SignedOrder,
 };
 
 pub async fn derive_api_key<S>(
@@ -88,7 +98,6 @@ where
     Ok(response.body.unwrap())
 }
 
-#[allow(unused)]
 pub async fn get_tick_size(proxy: Option<&Proxy>, token_id: &str) -> Result<f64, CustomError> {
     let query_args = [("token_id", token_id), ("geo_block_token", "")]
         .iter()
@@ -115,6 +124,31 @@ pub async fn get_tick_size(proxy: Option<&Proxy>, token_id: &str) -> Result<f64,
     Ok(response.body.unwrap().minimum_tick_size)
 }
 
+// Fee rate in bps orders of the token have to be signed with
+pub async fn get_fee_rate(token_id: &str, proxy: Option<&Proxy>) -> Result<f64, CustomError> {
+    let mut query_args = HashMap::new();
+    query_args.insert("token_id", token_id);
+
+    let request_params = RequestParams {
+        url: "https://clob.polymarket.com/fee-rate",
+        method: Method::GET,
+        body: None::<serde_json::Value>,
+        query_args: Some(query_args),
+    };
+
+    let response = send_http_request_with_retries::<FeeRateResponseBody>(
+        &request_params,
+        None,
+        proxy,
+        None,
+        None,
+        |_| true,
+    )
+    .await?;
+
+    Ok(response.body.unwrap().base_fee)
+}
+
 #[allow(unused)]
 pub async fn get_order_books(
     token_ids: &[&str],
@@ -172,18 +206,18 @@ pub async fn get_order_book(
     Ok(response.body.unwrap())
 }
 
-pub async fn get_neg_risk(token_id: &str, proxy: Option<&Proxy>) -> Result<bool, CustomError> {
-    let mut query_args = HashMap::new();
-    query_args.insert("token_id", token_id);
-
+pub async fn get_clob_market(
+    condition_id: &str,
+    proxy: Option<&Proxy>,
+) -> Result<ClobMarket, CustomError> {
     let request_params = RequestParams {
-        url: "https://clob.polymarket.com/neg-risk",
+        url: &format!("https://clob.polymarket.com/markets/{condition_id}"),
         method: Method::GET,
         body: None::<serde_json::Value>,
-        query_args: Some(query_args),
+        query_args: None,
     };
 
-    let response = send_http_request_with_retries::<NegRiskResponseBody>(
+    let response = send_http_request_with_retries::<ClobMarket>(
         &request_params,
         None,
         proxy,
@@ -193,13 +227,15 @@ pub async fn get_neg_risk(token_id: &str, proxy: Option<&Proxy>) -> Result<bool,
     )
     .await?;
 
-    Ok(response.body.unwrap().neg_risk)
+    Ok(response.body.unwrap())
 }
 
 pub async fn place_order(
     account: &Account,
     order: OrderRequest,
 ) -> Result<PlaceOrderResponseBody, CustomError> {
+    check_order(account, &order.order).await?;
+
     let mut query_args = HashMap::new();
     query_args.insert("geo_block_token", "");
 
@@ -215,6 +251,9 @@ pub async fn place_order(
     )
     .to_headermap();
 
+    let side = order.order.side.to_string();
+    let signed_order = order.order.clone();
+
     let request_params = RequestParams {
         url: &format!("https://clob.polymarket.com{path}"),
         method,
@@ -222,7 +261,313 @@ pub async fn place_order(
         query_args: Some(query_args),
     };
 
+    // Rejections come back as HTTP 400, sending the same order again won't change them
     let response = send_http_request_with_retries::<PlaceOrderResponseBody>(
+        &request_params,
+        Some(&headers),
+        account.proxy().as_ref(),
+        None,
+        None,
+        |err| match err {
+            CustomError::HttpStatusError { status, .. } => status != &StatusCode::BAD_REQUEST,
+            _ => true,
+        },
+    )
+    .await
+    .map_err(|e| match &e {
+        CustomError::HttpStatusError { status, text } => {
+            ClobRejection::from_http_error(*status, text).map_or(e, CustomError::ClobRejected)
+        }
+        _ => e,
+    })
+    .inspect_err(|e| match e.clob_rejection() {
+        Some(rejection) => handle_rejection(account, &signed_order, rejection),
+        None => {
+            METRICS.record_order(&side, "failed", "request");
+            notify(
+                Severity::Warning,
+                "Order failed",
+                format!("{} | {side} order failed: {e}", account.proxy_address),
+            );
+        }
+    })?;
+
+    handle_response(account, &signed_order, response.body.unwrap())
+}
+
+// The CLOB takes at most this many orders per batch request
+const MAX_BATCH_ORDERS: usize = 15;
+
+// Places the orders over `/orders` in as few requests as the batch limit allows, with one result per
+// order in the order they were given. The batch is risk checked as a whole before it is sent,
+// blocked orders are left out of it.
+pub async fn place_orders(
+    account: &Account,
+    orders: Vec<OrderRequest>,
+) -> Vec<Result<PlaceOrderResponseBody, CustomError>> {
+    let mut results = (0..orders.len()).map(|_| None).collect_vec();
+    let mut checked = vec![];
+
+    let checks = check_orders(
+        account,
+        &orders.iter().map(|order| &order.order).collect_vec(),
+    )
+    .await;
+
+    for (index, (order, check)) in orders.into_iter().zip(checks).enumerate() {
+        match check {
+            Ok(()) => checked.push((index, order)),
+            Err(e) => results[index] = Some(Err(e)),
+        }
+    }
+
+    for chunk in &checked.into_iter().chunks(MAX_BATCH_ORDERS) {
+        let (indices, orders): (Vec<_>, Vec<_>) = chunk.unzip();
+
+        for (index, result) in indices
+            .into_iter()
+            .zip(place_order_batch(account, orders).await)
+        {
+            results[index] = Some(result);
+        }
+    }
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+// Attempts for orders of a batch the CLOB didn't answer for. Orders are only sent again once they
+// are confirmed not to be placed, resending accepted ones would get them rejected as duplicates.
+const MAX_BATCH_ATTEMPTS: usize = 3;
+const BATCH_RETRY_DELAY: Duration = Duration::from_secs(3);
+
+async fn place_order_batch(
+    account: &Account,
+    orders: Vec<OrderRequest>,
+) -> Vec<Result<PlaceOrderResponseBody, CustomError>> {
+    let mut results = (0..orders.len()).map(|_| None).collect_vec();
+    let mut pending = orders.into_iter().enumerate().collect_vec();
+    let mut attempt = 1;
+
+    while !pending.is_empty() {
+        let batch = pending.iter().map(|(_, order)| order).collect_vec();
+
+        let error = match send_order_batch(account, &batch).await {
+            // Results come back in the order the orders were sent
+            Ok(bodies) => {
+                let mut bodies = bodies.into_iter();
+
+                for (index, order) in pending {
+                    results[index] = Some(match bodies.next() {
+                        Some(body) => handle_response(account, &order.order, body),
+                        None => {
+                            METRICS.record_order(
+                                &order.order.side.to_string(),
+                                "failed",
+                                "request",
+                            );
+                            Err(CustomError::PolymarketApi(
+                                "order missing from the batch response".to_string(),
+                            ))
+                        }
+                    });
+                }
+
+                break;
+            }
+            Err(e) => e,
+        };
+
+        // A batch rejected as a whole rejects every order in it
+        if let CustomError::HttpStatusError { status, text } = &error {
+            if let Some(rejection) = ClobRejection::from_http_error(*status, text) {
+                for (index, order) in pending {
+                    handle_rejection(account, &order.order, &rejection);
+                    results[index] = Some(Err(CustomError::ClobRejected(rejection.clone())));
+                }
+
+                break;
+            }
+        }
+
+        let mut unplaced = vec![];
+
+        for (index, order) in pending {
+            let side = order.order.side.to_string();
+
+            match get_order(account, &order.order.hash).await {
+                Ok(Some(placed)) => {
+                    let body = PlaceOrderResponseBody::from_placed_order(&order.order, &placed);
+                    results[index] = Some(handle_response(account, &order.order, body));
+                }
+                Ok(None) if attempt < MAX_BATCH_ATTEMPTS => unplaced.push((index, order)),
+                Ok(None) => {
+                    METRICS.record_order(&side, "failed", "request");
+                    results[index] = Some(Err(CustomError::PolymarketApi(format!(
+                        "batch request failed: {error}"
+                    ))));
+                }
+                Err(e) => {
+                    METRICS.record_order(&side, "failed", "request");
+                    results[index] = Some(Err(CustomError::PolymarketApi(format!(
+                        "batch request failed: {error}, and whether the order was placed is unknown: {e}"
+                    ))));
+                }
+            }
+        }
+
+        if unplaced.is_empty() {
+            notify(
+                Severity::Warning,
+                "Order batch failed",
+                format!("{} | Order batch failed: {error}", account.proxy_address),
+            );
+        } else {
+            tracing::warn!(
+                "{} | Order batch failed: {error}, sending {} unplaced orders again, attempt {}/{MAX_BATCH_ATTEMPTS}",
+                account.proxy_address,
+                unplaced.len(),
+                attempt + 1
+            );
+            METRICS.record_http_retry("https://clob.polymarket.com/orders");
+            tokio::time::sleep(BATCH_RETRY_DELAY).await;
+        }
+
+        pending = unplaced;
+        attempt += 1;
+    }
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+// Sent once, a failure other than a rejection doesn't tell whether the orders were placed
+async fn send_order_batch(
+    account: &Account,
+    orders: &[&OrderRequest],
+) -> Result<Vec<PlaceOrderResponseBody>, CustomError> {
+    let mut query_args = HashMap::new();
+    query_args.insert("geo_block_token", "");
+
+    let method = Method::POST;
+    let path = "/orders";
+    let headers = LayerTwoClobAuthHeaders::new(
+        &account.signer().address().to_string(),
+        account.get_api_creds().unwrap(),
+        method.as_str(),
+        path,
+        Some(&serde_json::to_string(orders).unwrap()),
+        None,
+    )
+    .to_headermap();
+
+    let request_params = RequestParams {
+        url: &format!("https://clob.polymarket.com{path}"),
+        method,
+        body: Some(orders),
+        query_args: Some(query_args),
+    };
+
+    let response = send_http_request::<Vec<PlaceOrderResponseBody>>(
+        &request_params,
+        Some(&headers),
+        account.proxy().as_ref(),
+    )
+    .await?;
+
+    Ok(response.body.unwrap())
+}
+
+fn handle_response(
+    account: &Account,
+    signed_order: &SignedOrder,
+    body: PlaceOrderResponseBody,
+) -> Result<PlaceOrderResponseBody, CustomError> {
+    let side = signed_order.side.to_string();
+
+    if !body.error_msg.is_empty() {
+        let rejection = ClobRejection::parse(&body.error_msg);
+        handle_rejection(account, signed_order, &rejection);
+
+        return Err(CustomError::ClobRejected(rejection));
+    }
+
+    let status = body
+        .status
+        .as_ref()
+        .map_or("unknown", |status| status.as_str());
+    METRICS.record_order(&side, "placed", status);
+    record_placed_order(account, signed_order);
+
+    if matches!(body.status, Some(OrderStatus::Matched)) {
+        METRICS.record_order(&side, "filled", status);
+
+        // USDC.e is what a buy gives and what a sell takes
+        let usdc_amount = match side.as_str() {
+            "BUY" => body.making_amount.as_deref(),
+            _ => body.taking_amount.as_deref(),
+        }
+        .and_then(|amount| amount.parse::<f64>().ok())
+        .unwrap_or_default();
+
+        if is_large_fill(usdc_amount) {
+            notify(
+                Severity::Info,
+                "Large fill",
+                format!(
+                    "{} | {side} order {} filled for {usdc_amount} USDC.e",
+                    account.proxy_address,
+                    body.order_id.as_deref().unwrap_or_default()
+                ),
+            );
+        }
+    }
+
+    Ok(body)
+}
+
+fn handle_rejection(account: &Account, order: &SignedOrder, rejection: &ClobRejection) {
+    let side = order.side.to_string();
+
+    METRICS.record_order(&side, "rejected", rejection.as_str());
+
+    // Tick and minimum sizes are refetched, the cached ones are what the order was built with
+    if matches!(
+        rejection,
+        ClobRejection::InvalidTickSize | ClobRejection::MinSizeNotMet
+    ) {
+        invalidate_market_metadata(&order.token_id);
+    }
+
+    notify(
+        Severity::Warning,
+        "Order rejected",
+        format!(
+            "{} | {side} order rejected: {rejection}",
+            account.proxy_address
+        ),
+    );
+}
+
+pub async fn cancel_all_orders(account: &Account) -> Result<CancelOrdersResponseBody, CustomError> {
+    let method = Method::DELETE;
+    let path = "/cancel-all";
+    let headers = LayerTwoClobAuthHeaders::new(
+        &account.signer().address().to_string(),
+        account.get_api_creds().unwrap(),
+        method.as_str(),
+        path,
+        None,
+        None,
+    )
+    .to_headermap();
+
+    let request_params = RequestParams {
+        url: &format!("https://clob.polymarket.com{path}"),
+        method,
+        body: None::<serde_json::Value>,
+        query_args: None,
+    };
+
+    let response = send_http_request_with_retries::<CancelOrdersResponseBody>(
         &request_params,
         Some(&headers),
         account.proxy().as_ref(),
@@ -232,10 +577,104 @@ pub async fn place_order(
     )
     .await?;
 
-    let body = response.body.unwrap();
+    Ok(response.body.unwrap())
+}
+
+// `None` when the CLOB doesn't know the order, matched and canceled orders are still returned
+pub async fn get_order(
+    account: &Account,
+    order_id: &str,
+) -> Result<Option<OpenOrder>, CustomError> {
+    let method = Method::GET;
+    let path = format!("/data/order/{order_id}");
+    let headers = LayerTwoClobAuthHeaders::new(
+        &account.signer().address().to_string(),
+        account.get_api_creds().unwrap(),
+        method.as_str(),
+        &path,
+        None,
+        None,
+    )
+    .to_headermap();
+
+    let request_params = RequestParams {
+        url: &format!("https://clob.polymarket.com{path}"),
+        method,
+        body: None::<serde_json::Value>,
+        query_args: None,
+    };
+
+    let response = send_http_request_with_retries::<Option<OpenOrder>>(
+        &request_params,
+        Some(&headers),
+        account.proxy().as_ref(),
+        None,
+        None,
+        |err| match err {
+            CustomError::HttpStatusError { status, .. } => status != &StatusCode::NOT_FOUND,
+            _ => true,
+        },
+    )
+    .await;
+
+    match response {
+        Ok(response) => Ok(response.body.flatten()),
+        Err(CustomError::HttpStatusError { status, .. }) if status == StatusCode::NOT_FOUND => {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// The cursor of the last page
+const END_CURSOR: &str = "LTE=";
+
+pub async fn get_open_orders(account: &Account) -> Result<Vec<OpenOrder>, CustomError> {
+    let method = Method::GET;
+    let path = "/data/orders";
+    let mut orders = vec![];
+    let mut cursor = String::new();
+
+    loop {
+        let headers = LayerTwoClobAuthHeaders::new(
+            &account.signer().address().to_string(),
+            account.get_api_creds().unwrap(),
+            method.as_str(),
+            path,
+            None,
+            None,
+        )
+        .to_headermap();
 
-    match body.error_msg.is_empty() {
-        true => Ok(body),
-        false => Err(CustomError::ClobApiError(body.error_msg)),
+        let mut query_args = HashMap::new();
+        if !cursor.is_empty() {
+            query_args.insert("next_cursor", cursor.as_str());
+        }
+
+        let request_params = RequestParams {
+            url: &format!("https://clob.polymarket.com{path}"),
+            method: method.clone(),
+            body: None::<serde_json::Value>,
+            query_args: Some(query_args),
+        };
+
+        let response = send_http_request_with_retries::<OpenOrdersResponseBody>(
+            &request_params,
+            Some(&headers),
+            account.proxy().as_ref(),
+            None,
+            None,
+            |_| true,
+        )
+        .await?;
+
+        let page = response.body.unwrap();
+        orders.extend(page.data);
+
+        if page.next_cursor.is_empty() || page.next_cursor == END_CURSOR {
+            return Ok(orders);
+        }
+
+        cursor = page.next_cursor;
     }
 }
@@ -62,7 +62,13 @@ pub fn adjust_amount(mut amount: f64, allowed_decimals: u32) -> f64 {
     amount
 }
 
-fn sort_orders(side: Side, orders: &mut [Order]) -> Vec<Order> {
+// Fee charged on a fill of `size` shares, in USDC: `base rate * min(price, 1 - price) * size`,
+// buys pay it in shares (fee / price) and sells in collateral
+pub fn calculate_fee(fee_rate_bps: f64, price: f64, size: f64) -> f64 {
+    fee_rate_bps / 10_000.0 * price.min(1.0 - price) * size
+}
+
+pub(super) fn sort_orders(side: Side, orders: &mut [Order]) -> Vec<Order> {
     match side {
         Side::Buy => {
             orders.sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap());
@@ -74,7 +80,7 @@ fn sort_orders(side: Side, orders: &mut [Order]) -> Vec<Order> {
     orders.to_vec()
 }
 
-fn calculate_accumulated_values(orders: &[Order]) -> Vec<AccumulatedOrder> {
+pub(super) fn calculate_accumulated_values(orders: &[Order]) -> Vec<AccumulatedOrder> {
     let mut accumulated_orders = vec![];
 
     for current_order in orders.iter() {
@@ -0,0 +1,86 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::Proxy;
+
+use crate::{
+    errors::custom::CustomError, polymarket::api::events::endpoints::get_market_by_token_id,
+};
+
+use super::{
+    endpoints::{get_clob_market, get_fee_rate, get_tick_size},
+    typedefs::TickSize,
+};
+
+// Tick sizes shrink as prices approach 0 or 1, so entries are refreshed even without a rejection
+const METADATA_TTL: Duration = Duration::from_secs(10 * 60);
+
+static METADATA_CACHE: LazyLock<Mutex<HashMap<String, (Instant, MarketMetadata)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Everything orders of a token are signed and validated with
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct MarketMetadata {
+    pub token_id: String,
+    pub condition_id: String,
+    pub tick_size: TickSize,
+    pub neg_risk: bool,
+    pub min_order_size: f64,
+    pub fee_rate_bps: f64,
+}
+
+pub async fn get_market_metadata(
+    token_id: &str,
+    proxy: Option<&Proxy>,
+) -> Result<MarketMetadata, CustomError> {
+    if let Some((fetched_at, metadata)) = METADATA_CACHE.lock().unwrap().get(token_id) {
+        if fetched_at.elapsed() < METADATA_TTL {
+            return Ok(metadata.clone());
+        }
+    }
+
+    let clob_market = async {
+        let condition_id = get_market_by_token_id(token_id, proxy)
+            .await?
+            .and_then(|market| market.condition_id)
+            .ok_or_else(|| {
+                CustomError::PolymarketApi(format!("No market found for token {token_id}"))
+            })?;
+
+        get_clob_market(&condition_id, proxy).await
+    };
+
+    // The tick size endpoint picks up changes before the market does
+    let (market, tick_size, fee_rate_bps) = tokio::try_join!(
+        clob_market,
+        get_tick_size(proxy, token_id),
+        get_fee_rate(token_id, proxy)
+    )?;
+
+    let metadata = MarketMetadata {
+        token_id: token_id.to_string(),
+        condition_id: market.condition_id,
+        tick_size: TickSize::try_from(tick_size)?,
+        neg_risk: market.neg_risk,
+        min_order_size: market.minimum_order_size,
+        fee_rate_bps,
+    };
+
+    METADATA_CACHE
+        .lock()
+        .unwrap()
+        .insert(token_id.to_string(), (Instant::now(), metadata.clone()));
+
+    Ok(metadata)
+}
+
+// Called when the CLOB rejects an order over its tick or minimum size, the next order refetches it
+pub fn invalidate_market_metadata(token_id: &str) {
+    if METADATA_CACHE.lock().unwrap().remove(token_id).is_some() {
+        tracing::debug!("Market metadata of {token_id} invalidated");
+    }
+}
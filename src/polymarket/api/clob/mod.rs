@@ -1,6 +1,9 @@
-mod constants;
+pub mod analytics;
+pub mod constants;
 pub mod endpoints;
 pub mod math;
+pub mod metadata;
 pub mod order_builder;
 pub mod schemas;
 pub mod typedefs;
+pub mod validation;
@@ -4,7 +4,7 @@ use alloy::{
     dyn_abi::Eip712Domain,
     primitives::{utils::parse_units, Address},
     signers::Signer,
-    sol_types::eip712_domain,
+    sol_types::{eip712_domain, SolStruct},
 };
 use chrono::Utc;
 use rand::Rng;
@@ -50,9 +50,14 @@ where
 
     pub async fn build_signed_order(
         &self,
-        user_order: UserOrder,
+        mut user_order: UserOrder,
         options: CreateOrderOptions,
     ) -> eyre::Result<SignedOrder> {
+        // The CLOB rejects orders signed with a fee rate other than the market's
+        if user_order.fee_rate_bps.is_none() {
+            user_order.fee_rate_bps = options.fee_rate_bps;
+        }
+
         let signer_address = self.signer.address().to_string();
 
         let maker = match self.funder_address {
@@ -81,9 +86,13 @@ where
 
     pub async fn build_signed_market_buy_order(
         &self,
-        user_market_order: UserMarketOrder,
+        mut user_market_order: UserMarketOrder,
         options: CreateOrderOptions,
     ) -> eyre::Result<SignedOrder> {
+        if user_market_order.fee_rate_bps.is_none() {
+            user_market_order.fee_rate_bps = options.fee_rate_bps;
+        }
+
         let signer_address = self.signer.address().to_string();
 
         let maker = match self.funder_address {
@@ -119,8 +128,9 @@ where
         let order_domain = self.get_order_domain(verifying_contract);
 
         let order_signature = self.signer.sign_typed_data(&order, &order_domain).await?;
+        let order_hash = order.eip712_signing_hash(&order_domain);
 
-        SignedOrder::new(order, order_signature)
+        SignedOrder::new(order, order_signature, order_hash)
     }
 
     fn build_order(&self, mut order_data: OrderData) -> eyre::Result<Order> {
@@ -177,7 +187,7 @@ where
         let BuyOrderRawAmounts {
             raw_maker_amount,
             raw_taker_amount,
-        } = self.get_market_buy_order_raw_amounts(user_market_order.amount, price, round_config);
+        } = get_market_buy_order_raw_amounts(user_market_order.amount, price, round_config);
 
         let maker_amount = parse_units(&raw_maker_amount.to_string(), "MWEI")
             .unwrap()
@@ -216,20 +226,6 @@ where
         }
     }
 
-    fn get_market_buy_order_raw_amounts(
-        &self,
-        amount: f64,
-        price: f64,
-        round_config: &RoundingConfig,
-    ) -> BuyOrderRawAmounts {
-        let raw_maker_amount = amount.round_down(round_config.size);
-        let raw_price = price.round_down(round_config.price);
-
-        let raw_taker_amount = adjust_amount(raw_maker_amount.div(raw_price), round_config.amount);
-
-        BuyOrderRawAmounts::new(raw_maker_amount, raw_taker_amount)
-    }
-
     fn build_order_creation_args(
         &self,
         signer: &str,
@@ -242,7 +238,7 @@ where
             side,
             raw_maker_amount,
             raw_taker_amount,
-        } = self.get_order_raw_amounts(
+        } = get_order_raw_amounts(
             &user_order.side,
             user_order.size,
             user_order.price,
@@ -290,30 +286,41 @@ where
             signature_type,
         }
     }
+}
 
-    fn get_order_raw_amounts(
-        &self,
-        side: &Side,
-        size: f64,
-        price: f64,
-        round_config: &RoundingConfig,
-    ) -> OrderRawAmounts {
-        let raw_price = price.round_normal(round_config.price);
-        let (raw_maker_amount, raw_taker_amount) = match side {
-            Side::Buy => {
-                let raw_taker_amount = size.round_down(round_config.size);
-                let raw_maker_amount =
-                    adjust_amount(raw_taker_amount * raw_price, round_config.amount);
-                (raw_maker_amount, raw_taker_amount)
-            }
-            Side::Sell => {
-                let raw_maker_amount = size.round_down(round_config.size);
-                let raw_taker_amount =
-                    adjust_amount(raw_maker_amount * raw_price, round_config.amount);
-                (raw_maker_amount, raw_taker_amount)
-            }
-        };
+// Shared with the backtester so simulated orders are rounded exactly like signed ones
+pub fn get_market_buy_order_raw_amounts(
+    amount: f64,
+    price: f64,
+    round_config: &RoundingConfig,
+) -> BuyOrderRawAmounts {
+    let raw_maker_amount = amount.round_down(round_config.size);
+    let raw_price = price.round_down(round_config.price);
 
-        OrderRawAmounts::new(side, raw_maker_amount, raw_taker_amount)
-    }
+    let raw_taker_amount = adjust_amount(raw_maker_amount.div(raw_price), round_config.amount);
+
+    BuyOrderRawAmounts::new(raw_maker_amount, raw_taker_amount)
+}
+
+pub fn get_order_raw_amounts(
+    side: &Side,
+    size: f64,
+    price: f64,
+    round_config: &RoundingConfig,
+) -> OrderRawAmounts {
+    let raw_price = price.round_normal(round_config.price);
+    let (raw_maker_amount, raw_taker_amount) = match side {
+        Side::Buy => {
+            let raw_taker_amount = size.round_down(round_config.size);
+            let raw_maker_amount = adjust_amount(raw_taker_amount * raw_price, round_config.amount);
+            (raw_maker_amount, raw_taker_amount)
+        }
+        Side::Sell => {
+            let raw_maker_amount = size.round_down(round_config.size);
+            let raw_taker_amount = adjust_amount(raw_maker_amount * raw_price, round_config.amount);
+            (raw_maker_amount, raw_taker_amount)
+        }
+    };
+
+    OrderRawAmounts::new(side, raw_maker_amount, raw_taker_amount)
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{de, Deserialize, Deserializer, Serialize};
 
 use crate::onchain::constants::POLYGON_EXPLORER_TX_BASE_URL;
@@ -17,6 +19,24 @@ pub struct GetTickSizeResponseBody {
     pub minimum_tick_size: f64,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct FeeRateResponseBody {
+    pub base_fee: f64,
+}
+
+// Trading parameters of a market as the CLOB enforces them
+#[allow(unused)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClobMarket {
+    pub condition_id: String,
+    pub minimum_order_size: f64,
+    pub minimum_tick_size: f64,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default)]
+    pub accepting_orders: bool,
+}
+
 #[derive(Serialize, Debug)]
 pub struct TokenId<'a> {
     pub token_id: &'a str,
@@ -25,10 +45,10 @@ pub struct TokenId<'a> {
 #[allow(unused)]
 #[derive(Debug, Deserialize, Clone)]
 pub struct OrderBookData {
-    market: String,
-    asset_id: String,
-    timestamp: String,
-    hash: String,
+    pub market: String,
+    pub asset_id: String,
+    pub timestamp: String,
+    pub hash: String,
     pub bids: Vec<Order>,
     pub asks: Vec<Order>,
 }
@@ -51,9 +71,40 @@ pub struct AccumulatedOrder {
     pub net_size: f64,
 }
 
-#[derive(Deserialize)]
-pub struct NegRiskResponseBody {
-    pub neg_risk: bool,
+#[allow(unused)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenOrder {
+    pub id: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub market: String,
+    #[serde(default)]
+    pub asset_id: String,
+    #[serde(default)]
+    pub side: String,
+    #[serde(default)]
+    pub original_size: String,
+    #[serde(default)]
+    pub size_matched: String,
+    #[serde(default)]
+    pub price: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OpenOrdersResponseBody {
+    #[serde(default)]
+    pub data: Vec<OpenOrder>,
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CancelOrdersResponseBody {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    #[serde(default)]
+    pub not_canceled: HashMap<String, String>,
 }
 
 fn string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
@@ -97,6 +148,7 @@ impl OrderRequest {
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaceOrderResponseBody {
+    #[serde(default)]
     pub error_msg: String,
     #[serde(rename = "orderID")]
     pub order_id: Option<String>,
@@ -108,6 +160,22 @@ pub struct PlaceOrderResponseBody {
 }
 
 impl PlaceOrderResponseBody {
+    // What the CLOB would have answered for an order it placed, known only from looking it up
+    pub fn from_placed_order(order: &SignedOrder, placed: &OpenOrder) -> Self {
+        let amount = |amount: &str| (amount.parse::<f64>().unwrap_or_default() / 1e6).to_string();
+
+        Self {
+            error_msg: String::new(),
+            order_id: Some(placed.id.clone()),
+            making_amount: Some(amount(&order.maker_amount)),
+            taking_amount: Some(amount(&order.taker_amount)),
+            status: serde_json::from_value(serde_json::Value::String(placed.status.to_lowercase()))
+                .ok(),
+            transactions_hashes: None,
+            success: Some(true),
+        }
+    }
+
     pub fn get_tx_hash(&self) -> String {
         format!(
             "{}{}",
@@ -140,3 +208,14 @@ pub enum OrderStatus {
     Delayed,
     Unmatched,
 }
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Matched => "matched",
+            Self::Delayed => "delayed",
+            Self::Unmatched => "unmatched",
+        }
+    }
+}
@@ -1,17 +1,22 @@
 use std::{fmt::Display, str::FromStr};
 
 use alloy::{
-    primitives::{Address, Signature, U256},
+    primitives::{Address, B256, U256},
     sol,
 };
 use eyre::bail;
 use serde::Serialize;
 use serde_repr::Serialize_repr;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+use crate::{errors::custom::CustomError, polymarket::api::typedefs::EcdsaSignature};
+
+use super::metadata::MarketMetadata;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Default)]
 #[repr(u8)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Side {
+    #[default]
     Buy = 0,
     Sell = 1,
 }
@@ -25,12 +30,6 @@ impl Display for Side {
     }
 }
 
-impl Default for Side {
-    fn default() -> Self {
-        Self::Buy
-    }
-}
-
 impl TryFrom<u8> for Side {
     type Error = eyre::Report;
 
@@ -132,10 +131,13 @@ pub struct SignedOrder {
     pub fee_rate_bps: String,
     pub signature_type: SignatureType,
     pub signature: String,
+    // EIP-712 hash of the order, the CLOB uses it as the order id
+    #[serde(skip)]
+    pub hash: String,
 }
 
 impl SignedOrder {
-    pub fn new(order: Order, signature: Signature) -> eyre::Result<Self> {
+    pub fn new(order: Order, signature: EcdsaSignature, hash: B256) -> eyre::Result<Self> {
         Ok(Self {
             salt: order.salt.try_into().unwrap(),
             maker: order.maker.to_string(),
@@ -150,6 +152,7 @@ impl SignedOrder {
             side: Side::try_from(order.side)?,
             signature_type: SignatureType::try_from(order.signatureType)?,
             signature: const_hex::encode_prefixed(signature.as_bytes()),
+            hash: hash.to_string(),
         })
     }
 }
@@ -192,17 +195,30 @@ pub enum TickSize {
     TenThousandth,
 }
 
-impl TickSize {
-    pub fn from_str(s: &str) -> Option<Self> {
+impl FromStr for TickSize {
+    type Err = CustomError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "0.1" => Some(TickSize::OneTenth),
-            "0.01" => Some(TickSize::OneHundredth),
-            "0.001" => Some(TickSize::OneThousandth),
-            "0.0001" => Some(TickSize::TenThousandth),
-            _ => None,
+            "0.1" => Ok(TickSize::OneTenth),
+            "0.01" => Ok(TickSize::OneHundredth),
+            "0.001" => Ok(TickSize::OneThousandth),
+            "0.0001" => Ok(TickSize::TenThousandth),
+            _ => Err(CustomError::UnsupportedTickSize(s.to_string())),
         }
     }
+}
+
+// The CLOB and gamma report tick sizes as numbers
+impl TryFrom<f64> for TickSize {
+    type Error = CustomError;
 
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        value.to_string().parse()
+    }
+}
+
+impl TickSize {
     pub fn as_str(&self) -> &str {
         match self {
             TickSize::OneTenth => "0.1",
@@ -217,13 +233,16 @@ impl TickSize {
 pub struct CreateOrderOptions {
     pub tick_size: TickSize,
     pub neg_risk: Option<bool>,
+    // Used when the order itself doesn't carry a fee rate
+    pub fee_rate_bps: Option<f64>,
 }
 
 impl CreateOrderOptions {
-    pub fn new(tick_size: TickSize, neg_risk: Option<bool>) -> Self {
+    pub fn from_metadata(metadata: &MarketMetadata) -> Self {
         Self {
-            tick_size,
-            neg_risk,
+            tick_size: metadata.tick_size,
+            neg_risk: Some(metadata.neg_risk),
+            fee_rate_bps: Some(metadata.fee_rate_bps),
         }
     }
 }
@@ -0,0 +1,88 @@
+use crate::errors::custom::CustomError;
+
+use super::{
+    constants::ROUNDING_CONFIG,
+    metadata::MarketMetadata,
+    order_builder::{get_market_buy_order_raw_amounts, get_order_raw_amounts},
+    typedefs::Side,
+};
+
+// Shares the order ends up with once rounded like the order builder does. Buys are sized in
+// USDC.e and sells in shares, anything under the market's minimum is rejected before signing.
+// A zero price means the book couldn't be priced at all, which no order size would fix.
+pub fn validate_order_size(
+    side: &Side,
+    amount: f64,
+    price: f64,
+    metadata: &MarketMetadata,
+) -> Result<f64, CustomError> {
+    if price <= 0.0 {
+        return Err(CustomError::NoLiquidity(side.clone()));
+    }
+
+    let round_config = &ROUNDING_CONFIG[metadata.tick_size.as_str()];
+
+    let size = match side {
+        Side::Buy => get_market_buy_order_raw_amounts(amount, price, round_config).raw_taker_amount,
+        Side::Sell => get_order_raw_amounts(side, amount, price, round_config).raw_maker_amount,
+    };
+
+    if size <= 0.0 || size < metadata.min_order_size {
+        return Err(CustomError::OrderBelowMinSize {
+            size,
+            min_size: metadata.min_order_size,
+        });
+    }
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> MarketMetadata {
+        MarketMetadata {
+            token_id: String::new(),
+            condition_id: String::new(),
+            tick_size: "0.01".parse().unwrap(),
+            neg_risk: false,
+            min_order_size: 5.0,
+            fee_rate_bps: 0.0,
+        }
+    }
+
+    #[test]
+    fn orders_are_sized_in_shares() {
+        assert_eq!(
+            validate_order_size(&Side::Buy, 5.0, 0.5, &metadata()).unwrap(),
+            10.0
+        );
+        assert_eq!(
+            validate_order_size(&Side::Sell, 5.0, 0.5, &metadata()).unwrap(),
+            5.0
+        );
+    }
+
+    #[test]
+    fn orders_below_the_minimum_are_rejected() {
+        assert!(matches!(
+            validate_order_size(&Side::Buy, 2.0, 0.5, &metadata()),
+            Err(CustomError::OrderBelowMinSize { size, .. }) if size == 4.0
+        ));
+        assert!(matches!(
+            validate_order_size(&Side::Sell, 4.99, 0.5, &metadata()),
+            Err(CustomError::OrderBelowMinSize { .. })
+        ));
+    }
+
+    #[test]
+    fn unpriced_books_are_reported_as_empty() {
+        for side in [Side::Buy, Side::Sell] {
+            assert!(matches!(
+                validate_order_size(&side, 10.0, 0.0, &metadata()),
+                Err(CustomError::NoLiquidity(_))
+            ));
+        }
+    }
+}
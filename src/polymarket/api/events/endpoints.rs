@@ -2,7 +2,7 @@ use reqwest::{Method, Proxy};
 
 use crate::{
     errors::custom::CustomError,
-    polymarket::api::events::schemas::Event,
+    polymarket::api::events::schemas::{Event, EventsQuery, Market},
     utils::fetch::{send_http_request_with_retries, RequestParams},
 };
 
@@ -11,21 +11,23 @@ pub async fn get_events(
     offset: u64,
     proxy: Option<&Proxy>,
 ) -> Result<Vec<Event>, CustomError> {
-    let offset = offset.to_string();
-    let limit = limit.unwrap_or(20).to_string();
-
-    let query_args = [
-        ("limit", limit.as_str()),
-        ("active", "true"),
-        ("archived", "false"),
-        ("closed", "false"),
-        ("order", "volume24hr"),
-        ("ascending", "false"),
-        ("offset", offset.as_str()),
-    ]
-    .iter()
-    .map(|(arg, value)| (*arg, *value))
-    .collect();
+    let query = EventsQuery::default()
+        .with_limit(limit.unwrap_or(20))
+        .with_offset(offset);
+
+    search_events(&query, proxy).await
+}
+
+pub async fn search_events(
+    query: &EventsQuery,
+    proxy: Option<&Proxy>,
+) -> Result<Vec<Event>, CustomError> {
+    let args = query.to_query_args();
+
+    let query_args = args
+        .iter()
+        .map(|(arg, value)| (*arg, value.as_str()))
+        .collect();
 
     let request_params = RequestParams {
         url: "https://gamma-api.polymarket.com/events",
@@ -46,3 +48,61 @@ pub async fn get_events(
 
     Ok(response.body.unwrap())
 }
+
+pub async fn get_event_by_slug(
+    slug: &str,
+    proxy: Option<&Proxy>,
+) -> Result<Option<Event>, CustomError> {
+    let query_args = [("slug", slug)]
+        .iter()
+        .map(|(arg, value)| (*arg, *value))
+        .collect();
+
+    let request_params = RequestParams {
+        url: "https://gamma-api.polymarket.com/events",
+        method: Method::GET,
+        body: None::<serde_json::Value>,
+        query_args: Some(query_args),
+    };
+
+    let response = send_http_request_with_retries::<Vec<Event>>(
+        &request_params,
+        None,
+        proxy,
+        None,
+        None,
+        |_| true,
+    )
+    .await?;
+
+    Ok(response.body.unwrap().into_iter().next())
+}
+
+pub async fn get_market_by_token_id(
+    token_id: &str,
+    proxy: Option<&Proxy>,
+) -> Result<Option<Market>, CustomError> {
+    let query_args = [("clob_token_ids", token_id)]
+        .iter()
+        .map(|(arg, value)| (*arg, *value))
+        .collect();
+
+    let request_params = RequestParams {
+        url: "https://gamma-api.polymarket.com/markets",
+        method: Method::GET,
+        body: None::<serde_json::Value>,
+        query_args: Some(query_args),
+    };
+
+    let response = send_http_request_with_retries::<Vec<Market>>(
+        &request_params,
+        None,
+        proxy,
+        None,
+        None,
+        |_| true,
+    )
+    .await?;
+
+    Ok(response.body.unwrap().into_iter().next())
+}
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer};
 
 #[allow(unused)]
@@ -10,8 +11,172 @@ pub struct Event {
     pub slug: String,
     pub title: String,
     pub volume: f64,
+    #[serde(default)]
+    pub volume24hr: Option<f64>,
+    #[serde(default)]
+    pub liquidity: Option<f64>,
+    #[serde(default)]
+    pub end_date: Option<String>,
     pub markets: Vec<Market>,
     pub neg_risk: Option<bool>,
+    #[serde(rename = "negRiskMarketID")]
+    pub neg_risk_market_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+
+#[allow(unused)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Tag {
+    pub slug: String,
+    #[serde(default)]
+    pub label: String,
+}
+
+// Event a market belongs to, as embedded in the markets endpoint
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarketEvent {
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventsOrder {
+    Volume24hr,
+    Volume,
+    Liquidity,
+    EndDate,
+    StartDate,
+    Competitive,
+}
+
+impl EventsOrder {
+    pub const ALL: [EventsOrder; 6] = [
+        EventsOrder::Volume24hr,
+        EventsOrder::Volume,
+        EventsOrder::Liquidity,
+        EventsOrder::EndDate,
+        EventsOrder::StartDate,
+        EventsOrder::Competitive,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventsOrder::Volume24hr => "volume24hr",
+            EventsOrder::Volume => "volume",
+            EventsOrder::Liquidity => "liquidity",
+            EventsOrder::EndDate => "endDate",
+            EventsOrder::StartDate => "startDate",
+            EventsOrder::Competitive => "competitive",
+        }
+    }
+}
+
+impl Display for EventsOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventsQuery {
+    pub limit: u64,
+    pub offset: u64,
+    pub order: EventsOrder,
+    pub ascending: bool,
+    pub tag_slug: Option<String>,
+    pub end_date_min: Option<String>,
+    pub end_date_max: Option<String>,
+    pub liquidity_min: Option<f64>,
+    pub volume_min: Option<f64>,
+}
+
+impl Default for EventsQuery {
+    fn default() -> Self {
+        Self {
+            limit: 20,
+            offset: 0,
+            order: EventsOrder::Volume24hr,
+            ascending: false,
+            tag_slug: None,
+            end_date_min: None,
+            end_date_max: None,
+            liquidity_min: None,
+            volume_min: None,
+        }
+    }
+}
+
+impl EventsQuery {
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_order(mut self, order: EventsOrder, ascending: bool) -> Self {
+        self.order = order;
+        self.ascending = ascending;
+        self
+    }
+
+    pub fn with_tag_slug(mut self, tag_slug: &str) -> Self {
+        self.tag_slug = Some(tag_slug.to_string());
+        self
+    }
+
+    pub fn with_end_date_range(mut self, min: Option<String>, max: Option<String>) -> Self {
+        self.end_date_min = min;
+        self.end_date_max = max;
+        self
+    }
+
+    pub fn with_liquidity_min(mut self, liquidity_min: f64) -> Self {
+        self.liquidity_min = Some(liquidity_min);
+        self
+    }
+
+    pub fn with_volume_min(mut self, volume_min: f64) -> Self {
+        self.volume_min = Some(volume_min);
+        self
+    }
+
+    pub fn to_query_args(&self) -> Vec<(&'static str, String)> {
+        let mut args = vec![
+            ("limit", self.limit.to_string()),
+            ("active", "true".to_string()),
+            ("archived", "false".to_string()),
+            ("closed", "false".to_string()),
+            ("order", self.order.as_str().to_string()),
+            ("ascending", self.ascending.to_string()),
+            ("offset", self.offset.to_string()),
+        ];
+
+        if let Some(tag_slug) = &self.tag_slug {
+            args.push(("tag_slug", tag_slug.clone()));
+        }
+
+        if let Some(end_date_min) = &self.end_date_min {
+            args.push(("end_date_min", end_date_min.clone()));
+        }
+
+        if let Some(end_date_max) = &self.end_date_max {
+            args.push(("end_date_max", end_date_max.clone()));
+        }
+
+        if let Some(liquidity_min) = self.liquidity_min {
+            args.push(("liquidity_min", liquidity_min.to_string()));
+        }
+
+        if let Some(volume_min) = self.volume_min {
+            args.push(("volume_min", volume_min.to_string()));
+        }
+
+        args
+    }
 }
 
 #[allow(unused)]
@@ -23,18 +188,35 @@ pub struct Market {
     pub active: bool,
     #[serde(rename = "questionID")]
     pub question_id: Option<String>,
-    #[serde(deserialize_with = "deserialize_into_string_array")]
-    pub outcomes: [String; 2],
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    pub group_item_title: Option<String>,
+    #[serde(default)]
+    pub neg_risk: Option<bool>,
+    #[serde(deserialize_with = "deserialize_into_string_vec")]
+    pub outcomes: Vec<String>,
     #[serde(default, deserialize_with = "deserialize_outcome_prices")]
-    pub outcome_prices: Option<[f64; 2]>,
+    pub outcome_prices: Option<Vec<f64>>,
     pub rewards_max_spread: f64,
-    #[serde(default, deserialize_with = "deserialize_into_string_array")]
-    pub clob_token_ids: [String; 2],
+    #[serde(default, deserialize_with = "deserialize_into_string_vec")]
+    pub clob_token_ids: Vec<String>,
     pub spread: f64,
     pub order_price_min_tick_size: f64,
+    #[serde(default)]
+    pub events: Vec<MarketEvent>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub closed: Option<bool>,
+    #[serde(default)]
+    pub accepting_orders: Option<bool>,
+    // "proposed", "disputed" or "resolved" once the UMA oracle got involved
+    #[serde(default)]
+    pub uma_resolution_status: Option<String>,
 }
 
-fn deserialize_outcome_prices<'de, D>(deserializer: D) -> Result<Option<[f64; 2]>, D::Error>
+fn deserialize_outcome_prices<'de, D>(deserializer: D) -> Result<Option<Vec<f64>>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -45,19 +227,14 @@ where
             let vec_str: Vec<String> =
                 serde_json::from_str(&s).map_err(|err| de::Error::custom(err.to_string()))?;
 
-            if vec_str.len() != 2 {
-                return Err(de::Error::invalid_length(
-                    vec_str.len(),
-                    &"expected an array of length 2",
-                ));
-            }
-
-            let mut vec_f64 = [0.0; 2];
-            for (i, val_str) in vec_str.iter().enumerate() {
-                vec_f64[i] = val_str
-                    .parse::<f64>()
-                    .map_err(|e| de::Error::custom(e.to_string()))?;
-            }
+            let vec_f64 = vec_str
+                .iter()
+                .map(|val_str| {
+                    val_str
+                        .parse::<f64>()
+                        .map_err(|e| de::Error::custom(e.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
             Ok(Some(vec_f64))
         }
@@ -65,29 +242,109 @@ where
     }
 }
 
-fn deserialize_into_string_array<'de, D>(deserializer: D) -> Result<[String; 2], D::Error>
+fn deserialize_into_string_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s: String = Deserialize::deserialize(deserializer)?;
 
-    let vec: Vec<String> =
-        serde_json::from_str(&s).map_err(|err| de::Error::custom(err.to_string()))?;
+    serde_json::from_str(&s).map_err(|err| de::Error::custom(err.to_string()))
+}
+
+impl Market {
+    pub fn is_binary(&self) -> bool {
+        self.outcomes.len() == 2 && self.clob_token_ids.len() == 2
+    }
+
+    pub fn label(&self) -> &str {
+        self.group_item_title
+            .as_deref()
+            .filter(|title| !title.is_empty())
+            .unwrap_or(&self.question)
+    }
+
+    pub fn yes_token_id(&self) -> Option<&str> {
+        self.clob_token_ids.first().map(|id| id.as_str())
+    }
+
+    pub fn no_token_id(&self) -> Option<&str> {
+        self.clob_token_ids.get(1).map(|id| id.as_str())
+    }
+
+    // Neg risk question ids are the market id with the question index in the last byte
+    pub fn neg_risk_question_index(&self) -> Option<u8> {
+        let question_id = self.question_id.as_ref()?;
+        const_hex::decode(question_id).ok()?.last().copied()
+    }
 
-    if vec.len() != 2 {
-        return Err(de::Error::invalid_length(
-            vec.len(),
-            &"expected an array of length 2",
-        ));
+    pub fn yes_price(&self) -> Option<f64> {
+        self.outcome_prices
+            .as_ref()
+            .and_then(|prices| prices.first().copied())
     }
 
-    Ok([vec[0].clone(), vec[1].clone()])
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(self.end_date.as_deref()?)
+            .ok()
+            .map(|end_date| end_date.with_timezone(&Utc))
+    }
+
+    // Negative once the end date has passed
+    pub fn hours_until_end(&self) -> Option<f64> {
+        self.end_time()
+            .map(|end_time| (end_time - Utc::now()).num_seconds() as f64 / 3600.0)
+    }
+
+    pub fn is_accepting_orders(&self) -> bool {
+        self.active && !self.closed.unwrap_or(false) && self.accepting_orders.unwrap_or(true)
+    }
+
+    // Closed markets settle their outcome prices at 0 and 1
+    pub fn is_resolved(&self) -> bool {
+        let settled = self.closed.unwrap_or(false)
+            && self.outcome_prices.as_ref().is_some_and(|prices| {
+                prices.contains(&1.0) && prices.iter().all(|price| *price == 0.0 || *price == 1.0)
+            });
+
+        settled || self.uma_resolution_status.as_deref() == Some("resolved")
+    }
+
+    // Markets ending within `hours` are left alone so nothing is held into resolution
+    pub fn ends_within(&self, hours: f64) -> bool {
+        self.hours_until_end()
+            .is_some_and(|hours_left| hours_left <= hours)
+    }
 }
 
 impl Event {
     pub fn get_url(&self) -> String {
         format!("https://polymarket.com/event/{}", self.slug)
     }
+
+    // Neg risk events group several binary markets, exactly one of which resolves YES
+    pub fn is_multi_outcome(&self) -> bool {
+        self.neg_risk.unwrap_or(false) && self.markets.len() > 1
+    }
+
+    pub fn tradable_markets(&self) -> impl Iterator<Item = &Market> {
+        self.markets
+            .iter()
+            .filter(|market| market.is_accepting_orders() && market.is_binary())
+    }
+
+    pub fn implied_probabilities(&self) -> Vec<(&str, f64)> {
+        self.tradable_markets()
+            .filter_map(|market| market.yes_price().map(|price| (market.label(), price)))
+            .collect()
+    }
+
+    // Sum of YES prices across the group, anything above 1 is the book's overround
+    pub fn implied_probability_sum(&self) -> f64 {
+        self.implied_probabilities()
+            .iter()
+            .map(|(_, price)| price)
+            .sum()
+    }
 }
 
 impl Display for Event {
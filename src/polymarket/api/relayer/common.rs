@@ -1,15 +1,23 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use alloy::{
-    primitives::{bytes, Address, U256},
+    primitives::{bytes, Address, B256, U256},
     signers::Signer,
+    sol,
     sol_types::SolCall,
 };
 use reqwest::Proxy;
 
 use crate::{
+    db::relayer_transactions::{
+        RelayerTransactionKind, RelayerTransactionRecord, RelayerTransactionStore,
+    },
     errors::custom::CustomError,
-    onchain::client::IERC20::transferCall,
+    notifier::{notify, Severity},
+    onchain::{
+        client::IERC20::{approveCall, transferCall},
+        types::token::Token,
+    },
     polymarket::api::typedefs::AmpCookie,
     utils::{constants::PROXY_FACTORY_ADDRESS, poly::get_proxy_wallet_address},
 };
@@ -17,14 +25,29 @@ use crate::{
 use super::{
     constants::{
         CONDITIONAL_TOKENS_CONTRACT_ADDRESS, MULTISEND_CONTRACT_ADDRESS,
-        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+        NEG_RISK_ADAPTER_CONTRACT_ADDRESS, UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
     },
     endpoints::{get_nonce, send_relayer_transaction},
-    schemas::RelayerRequestBody,
+    schemas::{RelayerRequestBody, RelayerResponseBody},
     signature_params::{RelayerRequestType, SignatureParams},
     tx_builder::{get_multisend_calldata, get_packed_signature, RelayerTransaction},
 };
 
+sol! {
+    function convertPositions(bytes32 _marketId, uint256 _indexSet, uint256 _amount) external;
+    function setApprovalForAll(address operator, bool approved) external;
+
+    interface IConditionalTokens {
+        function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets) external;
+        function mergePositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount) external;
+    }
+
+    interface INegRiskAdapter {
+        function redeemPositions(bytes32 _conditionId, uint256[] _amounts) external;
+        function mergePositions(bytes32 _conditionId, uint256 _amount) external;
+    }
+}
+
 pub fn get_approve_bundle() -> Vec<RelayerTransaction> {
     vec![
         (UCHILD_ERC20_PROXY_CONTRACT_ADDRESS, bytes!("095ea7b30000000000000000000000004d97dcd97ec945f40cf65f87097ace5ea0476045ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")),
@@ -37,6 +60,39 @@ pub fn get_approve_bundle() -> Vec<RelayerTransaction> {
     ].into_iter().map(|(address, data)| RelayerTransaction::new(0, address, U256::ZERO, data)).collect::<Vec<_>>()
 }
 
+// Same calls as the approve bundle with zero allowances and operator approvals taken back
+pub fn get_revoke_bundle() -> Vec<RelayerTransaction> {
+    get_approve_bundle()
+        .into_iter()
+        .map(|transaction| {
+            let data = match approveCall::abi_decode(&transaction.data, true) {
+                Ok(call) => approveCall {
+                    spender: call.spender,
+                    amount: U256::ZERO,
+                }
+                .abi_encode(),
+                Err(_) => {
+                    let call = setApprovalForAllCall::abi_decode(&transaction.data, true)
+                        .expect("approve bundle only holds approve and setApprovalForAll calls");
+
+                    setApprovalForAllCall {
+                        operator: call.operator,
+                        approved: false,
+                    }
+                    .abi_encode()
+                }
+            };
+
+            RelayerTransaction::new(
+                transaction.operation,
+                transaction.to,
+                transaction.value,
+                data.into(),
+            )
+        })
+        .collect()
+}
+
 pub async fn enable_trading<S: Signer>(
     signer: Arc<S>,
     signature: &str,
@@ -50,10 +106,13 @@ pub async fn enable_trading<S: Signer>(
         .with_payment()
         .with_payment_receiver();
 
+    let from = signer.address();
+    let proxy_wallet = get_proxy_wallet_address(signer);
+
     let body = RelayerRequestBody::default()
-        .with_from(signer.address())
+        .with_from(from)
         .with_to(PROXY_FACTORY_ADDRESS)
-        .with_proxy_wallet(get_proxy_wallet_address(signer))
+        .with_proxy_wallet(proxy_wallet)
         .with_data("0x")
         .with_signature(signature)
         .with_signature_params(signature_params)
@@ -68,6 +127,23 @@ pub async fn enable_trading<S: Signer>(
     )
     .await?;
 
+    RelayerTransactionStore::record(
+        RelayerTransactionRecord::new(
+            &transaction_response.transaction_id,
+            RelayerTransactionKind::EnableTrading,
+            &from.to_string(),
+            &proxy_wallet.to_string(),
+            &PROXY_FACTORY_ADDRESS.to_string(),
+            "0x",
+            0,
+            None,
+        )
+        .with_response(
+            transaction_response.get_state(),
+            &transaction_response.transaction_hash,
+        ),
+    );
+
     Ok(transaction_response.transaction_id)
 }
 
@@ -80,65 +156,257 @@ pub async fn withdraw_usdc<S: Signer + Send + Sync>(
     to: Address,
     amount: U256,
 ) -> Result<String, CustomError> {
-    let nonce = get_nonce(
-        signer.address(),
+    let data = transferCall { to, amount }.abi_encode();
+    let proxy_wallet = get_proxy_wallet_address(signer.clone());
+    let ui_amount = Token::USDCE.to_ui_amount(amount);
+
+    let transaction_response = send_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
         proxy,
+        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+        data,
+        0,
+        RelayerTransactionKind::Withdraw,
+    )
+    .await
+    .inspect_err(|e| {
+        notify(
+            Severity::Error,
+            "Withdrawal failed",
+            format!("{proxy_wallet} | Withdrawing {ui_amount} USDC.e to {to} failed: {e}"),
+        )
+    })?;
+
+    notify(
+        Severity::Info,
+        "Withdrawal submitted",
+        format!(
+            "{proxy_wallet} | Withdrawing {ui_amount} USDC.e to {to}, relayer transaction {}",
+            transaction_response.transaction_id
+        ),
+    );
+
+    Ok(transaction_response.transaction_hash)
+}
+
+pub async fn approve_tokens<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+) -> Result<String, CustomError> {
+    let transactions = get_approve_bundle();
+    let data = get_multisend_calldata(transactions);
+
+    let transaction_response = send_safe_transaction(
+        signer,
         amp_cookie,
         polymarket_nonce,
         polymarket_session,
+        proxy,
+        MULTISEND_CONTRACT_ADDRESS,
+        data,
+        1,
+        RelayerTransactionKind::Approve,
     )
     .await?;
 
-    let data = transferCall { to, amount }.abi_encode();
-    let packed_signature = get_packed_signature(
-        signer.clone(),
-        0,
-        U256::from(nonce),
-        data.clone(),
-        UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+    Ok(transaction_response.transaction_id)
+}
+
+pub async fn revoke_approvals<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+) -> Result<String, CustomError> {
+    let transactions = get_revoke_bundle();
+    let data = get_multisend_calldata(transactions);
+
+    let transaction_response = send_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy,
+        MULTISEND_CONTRACT_ADDRESS,
+        data,
+        1,
+        RelayerTransactionKind::Revoke,
     )
     .await?;
 
-    let data_hex = const_hex::encode_prefixed(data);
-    let nonce_str = nonce.to_string();
+    Ok(transaction_response.transaction_id)
+}
 
-    let signature_params = SignatureParams::default()
-        .with_gas_price()
-        .with_operation("0")
-        .with_safe_txn_gas()
-        .with_base_gas()
-        .with_gas_token()
-        .with_refund_receiver();
+// Neg risk positions are redeemed through the adapter with the amounts held per outcome,
+// regular ones straight on the ConditionalTokens contract for both outcome slots
+pub async fn redeem_positions<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+    condition_id: B256,
+    neg_risk_amounts: Option<[U256; 2]>,
+) -> Result<String, CustomError> {
+    let (to, data) = match neg_risk_amounts {
+        Some(amounts) => (
+            NEG_RISK_ADAPTER_CONTRACT_ADDRESS,
+            INegRiskAdapter::redeemPositionsCall {
+                _conditionId: condition_id,
+                _amounts: amounts.to_vec(),
+            }
+            .abi_encode(),
+        ),
+        None => (
+            CONDITIONAL_TOKENS_CONTRACT_ADDRESS,
+            IConditionalTokens::redeemPositionsCall {
+                collateralToken: UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+                parentCollectionId: B256::ZERO,
+                conditionId: condition_id,
+                indexSets: vec![U256::from(1), U256::from(2)],
+            }
+            .abi_encode(),
+        ),
+    };
 
-    let body = RelayerRequestBody::default()
-        .with_from(signer.address())
-        .with_to(UCHILD_ERC20_PROXY_CONTRACT_ADDRESS)
-        .with_proxy_wallet(get_proxy_wallet_address(signer))
-        .with_data(&data_hex)
-        .with_nonce(&nonce_str)
-        .with_signature(&packed_signature)
-        .with_signature_params(signature_params)
-        .with_type(RelayerRequestType::Safe);
+    let proxy_wallet = get_proxy_wallet_address(signer.clone());
 
-    let transaction_response = send_relayer_transaction(
+    let transaction_response = send_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
         proxy,
-        body,
+        to,
+        data,
+        0,
+        RelayerTransactionKind::Redeem,
+    )
+    .await
+    .inspect_err(|e| {
+        notify(
+            Severity::Error,
+            "Redemption failed",
+            format!("{proxy_wallet} | Redeeming condition {condition_id} failed: {e}"),
+        )
+    })?;
+
+    notify(
+        Severity::Info,
+        "Redemption submitted",
+        format!(
+            "{proxy_wallet} | Redeeming condition {condition_id}, relayer transaction {}",
+            transaction_response.transaction_id
+        ),
+    );
+
+    Ok(transaction_response.transaction_id)
+}
+
+// Burns `amount` of both outcomes of a condition for the same amount of USDC.e
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_positions<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+    condition_id: B256,
+    amount: U256,
+    neg_risk: bool,
+) -> Result<String, CustomError> {
+    let (to, data) = match neg_risk {
+        true => (
+            NEG_RISK_ADAPTER_CONTRACT_ADDRESS,
+            INegRiskAdapter::mergePositionsCall {
+                _conditionId: condition_id,
+                _amount: amount,
+            }
+            .abi_encode(),
+        ),
+        false => (
+            CONDITIONAL_TOKENS_CONTRACT_ADDRESS,
+            IConditionalTokens::mergePositionsCall {
+                collateralToken: UCHILD_ERC20_PROXY_CONTRACT_ADDRESS,
+                parentCollectionId: B256::ZERO,
+                conditionId: condition_id,
+                partition: vec![U256::from(1), U256::from(2)],
+                amount,
+            }
+            .abi_encode(),
+        ),
+    };
+
+    let transaction_response = send_safe_transaction(
+        signer,
         amp_cookie,
         polymarket_nonce,
         polymarket_session,
+        proxy,
+        to,
+        data,
+        0,
+        RelayerTransactionKind::Merge,
     )
     .await?;
 
-    Ok(transaction_response.transaction_hash)
+    Ok(transaction_response.transaction_id)
 }
 
-pub async fn approve_tokens<S: Signer + Send + Sync>(
+#[allow(clippy::too_many_arguments)]
+pub async fn convert_positions<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+    market_id: B256,
+    index_set: U256,
+    amount: U256,
+) -> Result<String, CustomError> {
+    let data = convertPositionsCall {
+        _marketId: market_id,
+        _indexSet: index_set,
+        _amount: amount,
+    }
+    .abi_encode();
+
+    let transaction_response = send_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy,
+        NEG_RISK_ADAPTER_CONTRACT_ADDRESS,
+        data,
+        0,
+        RelayerTransactionKind::ConvertPositions,
+    )
+    .await?;
+
+    Ok(transaction_response.transaction_id)
+}
+
+// Re-signs a recorded Safe transaction with the same nonce, refusing if the Safe nonce has moved on
+pub async fn resubmit_safe_transaction<S: Signer + Send + Sync>(
     signer: Arc<S>,
     amp_cookie: &mut AmpCookie,
     polymarket_nonce: &str,
     polymarket_session: &str,
     proxy: Option<&Proxy>,
+    record: &RelayerTransactionRecord,
 ) -> Result<String, CustomError> {
+    let recorded_nonce = record.nonce.ok_or_else(|| {
+        CustomError::PolymarketApi("Only Safe transactions can be resubmitted".to_string())
+    })?;
+
     let nonce = get_nonce(
         signer.address(),
         proxy,
@@ -148,32 +416,139 @@ pub async fn approve_tokens<S: Signer + Send + Sync>(
     )
     .await?;
 
-    let transactions = get_approve_bundle();
+    if nonce > recorded_nonce {
+        RelayerTransactionStore::mark_superseded(&record.transaction_id);
+        return Err(CustomError::PolymarketApi(format!(
+            "Safe nonce {recorded_nonce} is already used (current {nonce}), transaction can't be resubmitted"
+        )));
+    }
+
+    if nonce < recorded_nonce {
+        return Err(CustomError::PolymarketApi(format!(
+            "Safe nonce {nonce} is still pending, resubmit that transaction first"
+        )));
+    }
+
+    let to = Address::from_str(&record.to)
+        .map_err(|e| CustomError::PolymarketApi(format!("Invalid recorded address: {e}")))?;
+    let data = const_hex::decode(&record.data)
+        .map_err(|e| CustomError::PolymarketApi(format!("Invalid recorded payload: {e}")))?;
+
+    let transaction_response = submit_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy,
+        SafeTransactionPayload {
+            to,
+            data,
+            operation: record.operation,
+            nonce,
+            kind: record.kind,
+            resubmission_of: Some(&record.transaction_id),
+        },
+    )
+    .await?;
+
+    RelayerTransactionStore::mark_superseded(&record.transaction_id);
+
+    Ok(transaction_response.transaction_id)
+}
+
+struct SafeTransactionPayload<'a> {
+    to: Address,
+    data: Vec<u8>,
+    operation: u8,
+    nonce: u64,
+    kind: RelayerTransactionKind,
+    resubmission_of: Option<&'a str>,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send_safe_transaction<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+    to: Address,
+    data: Vec<u8>,
+    operation: u8,
+    kind: RelayerTransactionKind,
+) -> Result<RelayerResponseBody, CustomError> {
+    let nonce = get_nonce(
+        signer.address(),
+        proxy,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+    )
+    .await?;
+
+    submit_safe_transaction(
+        signer,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy,
+        SafeTransactionPayload {
+            to,
+            data,
+            operation,
+            nonce,
+            kind,
+            resubmission_of: None,
+        },
+    )
+    .await
+}
+
+// Signs the payload as a Safe transaction of the proxy wallet, submits it and records it in the tracker
+async fn submit_safe_transaction<S: Signer + Send + Sync>(
+    signer: Arc<S>,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+    payload: SafeTransactionPayload<'_>,
+) -> Result<RelayerResponseBody, CustomError> {
+    let SafeTransactionPayload {
+        to,
+        data,
+        operation,
+        nonce,
+        kind,
+        resubmission_of,
+    } = payload;
 
-    let data = get_multisend_calldata(transactions);
     let packed_signature = get_packed_signature(
         signer.clone(),
-        1,
+        operation,
         U256::from(nonce),
         data.clone(),
-        MULTISEND_CONTRACT_ADDRESS,
+        to,
     )
     .await?;
+
     let data_hex = const_hex::encode_prefixed(data);
     let nonce_str = nonce.to_string();
+    let operation_str = operation.to_string();
+    let from = signer.address();
+    let proxy_wallet = get_proxy_wallet_address(signer);
 
     let signature_params = SignatureParams::default()
         .with_gas_price()
-        .with_operation("1")
+        .with_operation(&operation_str)
         .with_safe_txn_gas()
         .with_base_gas()
         .with_gas_token()
         .with_refund_receiver();
 
     let body = RelayerRequestBody::default()
-        .with_from(signer.address())
-        .with_to(MULTISEND_CONTRACT_ADDRESS)
-        .with_proxy_wallet(get_proxy_wallet_address(signer))
+        .with_from(from)
+        .with_to(to)
+        .with_proxy_wallet(proxy_wallet)
         .with_data(&data_hex)
         .with_nonce(&nonce_str)
         .with_signature(&packed_signature)
@@ -189,5 +564,23 @@ pub async fn approve_tokens<S: Signer + Send + Sync>(
     )
     .await?;
 
-    Ok(transaction_response.transaction_id)
+    RelayerTransactionStore::record(
+        RelayerTransactionRecord::new(
+            &transaction_response.transaction_id,
+            kind,
+            &from.to_string(),
+            &proxy_wallet.to_string(),
+            &to.to_string(),
+            &data_hex,
+            operation,
+            Some(nonce),
+        )
+        .with_response(
+            transaction_response.get_state(),
+            &transaction_response.transaction_hash,
+        )
+        .with_resubmission_of(resubmission_of),
+    );
+
+    Ok(transaction_response)
 }
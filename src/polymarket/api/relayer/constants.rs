@@ -6,3 +6,9 @@ pub const UCHILD_ERC20_PROXY_CONTRACT_ADDRESS: Address =
     address!("2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
 pub const CONDITIONAL_TOKENS_CONTRACT_ADDRESS: Address =
     address!("4D97DCd97eC945f40cF65F87097ACe5EA0476045");
+pub const NEG_RISK_ADAPTER_CONTRACT_ADDRESS: Address =
+    address!("d91E80cF2E7be2e162c6513ceD06f1dD0dA35296");
+pub const CTF_EXCHANGE_CONTRACT_ADDRESS: Address =
+    address!("4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E");
+pub const NEG_RISK_CTF_EXCHANGE_CONTRACT_ADDRESS: Address =
+    address!("C5d563A36AE78145C45a50134d48A1215220f80a");
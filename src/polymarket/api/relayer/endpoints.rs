@@ -1,4 +1,5 @@
 use crate::{
+    db::relayer_transactions::RelayerTransactionStore,
     errors::custom::CustomError,
     polymarket::api::typedefs::AmpCookie,
     utils::{
@@ -13,16 +14,16 @@ use tokio::time::{sleep, timeout, Duration};
 
 use super::schemas::{
     GetRelayerNonceResponseBody, GetTransactionStatusResponseBody, RelayerRequestBody,
-    RelayerResponseBody, TransactionState,
+    RelayerResponseBody,
 };
 
-pub async fn get_transaction_status(
+pub async fn get_transaction(
     transaction_id: &str,
     amp_cookie: &mut AmpCookie,
     polymarket_nonce: &str,
     polymarket_session: &str,
     proxy: Option<&Proxy>,
-) -> Result<Option<String>, CustomError> {
+) -> Result<GetTransactionStatusResponseBody, CustomError> {
     let headers = build_poly_headers(amp_cookie, polymarket_nonce, polymarket_session);
 
     let query_args = [("id", transaction_id)]
@@ -47,10 +48,41 @@ pub async fn get_transaction_status(
     )
     .await?;
 
-    let tx_status = &response.body.as_ref().unwrap()[0];
+    response
+        .body
+        .and_then(|statuses| statuses.into_iter().next())
+        .ok_or_else(|| {
+            CustomError::PolymarketApi(format!("Relayer transaction {transaction_id} not found"))
+        })
+}
+
+pub async fn get_transaction_status(
+    transaction_id: &str,
+    amp_cookie: &mut AmpCookie,
+    polymarket_nonce: &str,
+    polymarket_session: &str,
+    proxy: Option<&Proxy>,
+) -> Result<Option<String>, CustomError> {
+    let tx_status = get_transaction(
+        transaction_id,
+        amp_cookie,
+        polymarket_nonce,
+        polymarket_session,
+        proxy,
+    )
+    .await?;
+
+    RelayerTransactionStore::record_state(
+        transaction_id,
+        tx_status.state,
+        &tx_status.transaction_hash,
+    );
 
     match tx_status.state {
-        TransactionState::Mined => Ok(Some(tx_status.transaction_hash.clone())),
+        state if state.is_mined() => Ok(Some(tx_status.transaction_hash)),
+        state if state.is_failed() => Err(CustomError::RelayerTransactionFailed(format!(
+            "{transaction_id} ended in {state}"
+        ))),
         _ => Ok(None),
     }
 }
@@ -102,7 +102,14 @@ pub struct RelayerResponseBody {
     pub state: String,
 }
 
-#[derive(Deserialize, Debug)]
+impl RelayerResponseBody {
+    pub fn get_state(&self) -> TransactionState {
+        serde_json::from_value(serde_json::Value::String(self.state.clone()))
+            .unwrap_or(TransactionState::Unknown)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionState {
     #[serde(rename = "STATE_NEW")]
     New,
@@ -110,6 +117,44 @@ pub enum TransactionState {
     Executed,
     #[serde(rename = "STATE_MINED")]
     Mined,
+    #[serde(rename = "STATE_CONFIRMED")]
+    Confirmed,
+    #[serde(rename = "STATE_FAILED")]
+    Failed,
+    #[serde(rename = "STATE_INVALID")]
+    Invalid,
+    #[serde(other)]
+    Unknown,
+}
+
+impl TransactionState {
+    pub fn is_mined(&self) -> bool {
+        matches!(self, Self::Mined | Self::Confirmed)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed | Self::Invalid)
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.is_mined() || self.is_failed()
+    }
+}
+
+impl std::fmt::Display for TransactionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            Self::New => "STATE_NEW",
+            Self::Executed => "STATE_EXECUTED",
+            Self::Mined => "STATE_MINED",
+            Self::Confirmed => "STATE_CONFIRMED",
+            Self::Failed => "STATE_FAILED",
+            Self::Invalid => "STATE_INVALID",
+            Self::Unknown => "UNKNOWN",
+        };
+
+        write!(f, "{state}")
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -98,16 +98,11 @@ impl<'a> SignatureParams<'a> {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Default)]
 pub enum RelayerRequestType {
     #[serde(rename = "SAFE")]
     Safe,
     #[serde(rename = "SAFE-CREATE")]
+    #[default]
     SafeCreate,
 }
-
-impl Default for RelayerRequestType {
-    fn default() -> Self {
-        Self::SafeCreate
-    }
-}
@@ -3,12 +3,12 @@ use std::sync::Arc;
 use alloy::{
     dyn_abi::SolType,
     primitives::{Address, Bytes, U256},
-    signers::{Signature, Signer},
+    signers::Signer,
     sol,
     sol_types::{eip712_domain, SolCall, SolStruct, SolValue},
 };
 
-use crate::utils::poly::get_proxy_wallet_address;
+use crate::{polymarket::api::typedefs::EcdsaSignature, utils::poly::get_proxy_wallet_address};
 
 sol! {
     function multiSend(bytes bytes) external payable;
@@ -112,7 +112,7 @@ where
     Ok(const_hex::encode_prefixed(packed_signature))
 }
 
-fn get_v_incremented(signature: &Signature) -> u8 {
+fn get_v_incremented(signature: &EcdsaSignature) -> u8 {
     let mut v = signature.v().to_u64();
 
     match v {
@@ -18,6 +18,11 @@ use uuid::Uuid;
 
 use crate::{db::account::ApiCreds, utils::misc::get_timestamp_with_offset};
 
+// alloy-signer 0.3 still signs into the deprecated `Signature`, this alias is the one place it
+// is named until the signers move to `PrimitiveSignature`
+#[allow(deprecated)]
+pub type EcdsaSignature = alloy::primitives::Signature;
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthHeaderPayload<'a> {
@@ -17,8 +17,8 @@ use crate::{
 
 use super::schemas::{
     CreateUserRequestBody, CreateUserResponseBody, GetAuthNonceResponseBody, LoginReponseBody,
-    UpdatePreferencesRequestBody, UpdateUsernameRequestBody, User, UserOpenPositionsStats,
-    UserPnlStats, UserPosition, UserTradesResponseBody, UserVolumeStats,
+    MarketTrade, UpdatePreferencesRequestBody, UpdateUsernameRequestBody, User,
+    UserOpenPositionsStats, UserPnlStats, UserPosition, UserTradesResponseBody, UserVolumeStats,
 };
 
 pub async fn get_auth_nonce(proxy: Option<&Proxy>) -> Result<(String, String), CustomError> {
@@ -260,6 +260,38 @@ pub async fn get_user_positions(
     Ok(response.body.unwrap())
 }
 
+// Latest trades of a market (condition id), newest first
+pub async fn get_market_trades(
+    condition_id: &str,
+    limit: u64,
+    proxy: Option<&Proxy>,
+) -> Result<Vec<MarketTrade>, CustomError> {
+    let limit = limit.to_string();
+    let query_args = [("market", condition_id), ("limit", limit.as_str())]
+        .iter()
+        .map(|(arg, value)| (*arg, *value))
+        .collect();
+
+    let request_params = RequestParams {
+        url: "https://data-api.polymarket.com/trades",
+        method: Method::GET,
+        body: None::<serde_json::Value>,
+        query_args: Some(query_args),
+    };
+
+    let response = send_http_request_with_retries::<Vec<MarketTrade>>(
+        &request_params,
+        None,
+        proxy,
+        None,
+        None,
+        |_| true,
+    )
+    .await?;
+
+    Ok(response.body.unwrap_or_default())
+}
+
 pub async fn get_user_volume(
     proxy_wallet_address: &str,
     proxy: Option<&Proxy>,
@@ -293,12 +325,14 @@ pub async fn get_user_volume(
     Ok(response.body.unwrap())
 }
 
+// `window` is one of "1d", "7d", "30d" or "all"
 pub async fn get_user_pnl(
     proxy_wallet_address: &str,
+    window: &str,
     proxy: Option<&Proxy>,
 ) -> Result<Vec<UserPnlStats>, CustomError> {
     let query_args = [
-        ("window", "all"),
+        ("window", window),
         ("limit", "1"),
         ("address", proxy_wallet_address),
     ]
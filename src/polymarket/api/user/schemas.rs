@@ -233,12 +233,41 @@ impl<'a> UpdatePreferencesRequestBody<'a> {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UserPosition {
     pub asset: String,
     pub size: f64,
     pub negative_risk: bool,
+    #[serde(default)]
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub event_slug: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub outcome_index: Option<u8>,
+    #[serde(default)]
+    pub cur_price: Option<f64>,
+    #[serde(default)]
+    pub current_value: Option<f64>,
+    #[serde(default)]
+    pub redeemable: bool,
+}
+
+// Public trade of a market as reported by the data API, `timestamp` is in seconds
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketTrade {
+    pub side: String,
+    pub asset: String,
+    pub size: f64,
+    pub price: f64,
+    pub timestamp: i64,
+    #[serde(default)]
+    pub transaction_hash: String,
 }
 
 #[derive(Deserialize, Debug)]
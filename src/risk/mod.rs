@@ -0,0 +1,752 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use reqwest::Proxy;
+
+use crate::{
+    config::Config,
+    db::account::Account,
+    errors::custom::CustomError,
+    polymarket::api::{
+        clob::{endpoints::get_open_orders, typedefs::Side, typedefs::SignedOrder},
+        events::endpoints::{get_event_by_slug, get_market_by_token_id},
+        user::endpoints::{get_user_pnl, get_user_positions},
+    },
+    utils::metrics::METRICS,
+};
+
+static RISK_MANAGER: OnceLock<RiskManager> = OnceLock::new();
+static KILL_SWITCH: AtomicBool = AtomicBool::new(false);
+
+const DAILY_PNL_TTL: Duration = Duration::from_secs(60);
+
+// Zero disables a limit, empty lists don't filter
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    pub max_order_usdc: f64,
+    pub max_market_exposure_usdc: f64,
+    pub max_wallet_exposure_usdc: f64,
+    pub max_open_orders: usize,
+    pub daily_loss_limit_usdc: f64,
+    pub allowed_markets: Vec<String>,
+    pub blocked_markets: Vec<String>,
+    pub allowed_tags: Vec<String>,
+    pub blocked_tags: Vec<String>,
+    pub kill_switch_file: String,
+}
+
+impl RiskLimits {
+    pub fn from_config(config: &Config) -> Self {
+        let lowercase = |values: &[String]| {
+            values
+                .iter()
+                .map(|value| value.trim().to_lowercase())
+                .collect()
+        };
+
+        Self {
+            max_order_usdc: config.risk_max_order_usdc,
+            max_market_exposure_usdc: config.risk_max_market_exposure_usdc,
+            max_wallet_exposure_usdc: config.risk_max_wallet_exposure_usdc,
+            max_open_orders: config.risk_max_open_orders,
+            daily_loss_limit_usdc: config.risk_daily_loss_limit_usdc,
+            allowed_markets: lowercase(&config.risk_allowed_markets),
+            blocked_markets: lowercase(&config.risk_blocked_markets),
+            allowed_tags: lowercase(&config.risk_allowed_tags),
+            blocked_tags: lowercase(&config.risk_blocked_tags),
+            kill_switch_file: config.risk_kill_switch_file.clone(),
+        }
+    }
+
+    fn tracks_exposure(&self) -> bool {
+        self.max_market_exposure_usdc > 0.0 || self.max_wallet_exposure_usdc > 0.0
+    }
+
+    fn needs_market(&self) -> bool {
+        self.max_market_exposure_usdc > 0.0
+            || !self.allowed_markets.is_empty()
+            || !self.blocked_markets.is_empty()
+            || self.needs_tags()
+    }
+
+    fn needs_tags(&self) -> bool {
+        !self.allowed_tags.is_empty() || !self.blocked_tags.is_empty()
+    }
+}
+
+pub struct RiskViolation {
+    pub rule: &'static str,
+    pub reason: String,
+}
+
+impl RiskViolation {
+    fn new(rule: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            rule,
+            reason: reason.into(),
+        }
+    }
+}
+
+// Condition id, market and event slugs a market can be referred to by, and its event's tags
+#[derive(Debug, Clone)]
+struct MarketInfo {
+    condition_id: String,
+    identifiers: Vec<String>,
+    tags: Vec<String>,
+}
+
+// Orders of one batch that already passed the checks, counted as if they were placed so the
+// batch as a whole stays within the limits
+#[derive(Debug, Default)]
+pub struct PendingOrders {
+    open_orders: Option<usize>,
+    count: usize,
+    wallet_notional: f64,
+    market_notional: HashMap<String, f64>,
+}
+
+// Sits in front of every order sent to the CLOB. Sells only ever reduce exposure, so they are
+// always let through, even with the kill switch engaged, so positions can still be exited
+pub struct RiskManager {
+    limits: RiskLimits,
+    markets: Mutex<HashMap<String, MarketInfo>>,
+    // Wallet -> market -> USDC.e committed, seeded from the wallet's positions on first use
+    exposures: Mutex<HashMap<String, HashMap<String, f64>>>,
+    daily_pnl: Mutex<HashMap<String, (Instant, f64)>>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            markets: Mutex::new(HashMap::new()),
+            exposures: Mutex::new(HashMap::new()),
+            daily_pnl: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn kill_switch_engaged(&self) -> bool {
+        KILL_SWITCH.load(Ordering::SeqCst)
+            || (!self.limits.kill_switch_file.is_empty()
+                && Path::new(&self.limits.kill_switch_file).exists())
+    }
+
+    pub async fn check(&self, account: &Account, order: &SignedOrder) -> Result<(), RiskViolation> {
+        self.check_pending(account, order, &mut PendingOrders::default())
+            .await
+    }
+
+    // Accepted orders are added to `pending`, the open orders are only counted once per batch
+    pub async fn check_pending(
+        &self,
+        account: &Account,
+        order: &SignedOrder,
+        pending: &mut PendingOrders,
+    ) -> Result<(), RiskViolation> {
+        let limits = &self.limits;
+
+        if order.side == Side::Sell {
+            pending.count += 1;
+            return Ok(());
+        }
+
+        if self.kill_switch_engaged() {
+            return Err(RiskViolation::new("kill_switch", "kill switch is engaged"));
+        }
+
+        let notional = order_notional(order);
+
+        if limits.max_order_usdc > 0.0 && notional > limits.max_order_usdc {
+            return Err(RiskViolation::new(
+                "max_order",
+                format!(
+                    "order of {notional:.2} USDC.e is above the {:.2} USDC.e limit",
+                    limits.max_order_usdc
+                ),
+            ));
+        }
+
+        if limits.max_open_orders > 0 {
+            let open_orders = match pending.open_orders {
+                Some(open_orders) => open_orders,
+                None => {
+                    let open_orders = get_open_orders(account)
+                        .await
+                        .map_err(|e| {
+                            RiskViolation::new(
+                                "max_open_orders",
+                                format!("failed to count open orders: {e}"),
+                            )
+                        })?
+                        .len();
+
+                    *pending.open_orders.insert(open_orders)
+                }
+            } + pending.count;
+
+            if open_orders >= limits.max_open_orders {
+                return Err(RiskViolation::new(
+                    "max_open_orders",
+                    format!(
+                        "{open_orders} orders are open, the limit is {}",
+                        limits.max_open_orders
+                    ),
+                ));
+            }
+        }
+
+        let proxy = account.proxy();
+
+        let market = match limits.needs_market() {
+            true => Some(self.market_info(&order.token_id, proxy.as_ref()).await?),
+            false => None,
+        };
+
+        if let Some(market) = &market {
+            check_market_filters(limits, market)?;
+        }
+
+        if limits.daily_loss_limit_usdc > 0.0 {
+            let pnl = self
+                .daily_pnl(&account.proxy_address, proxy.as_ref())
+                .await?;
+
+            if pnl <= -limits.daily_loss_limit_usdc {
+                return Err(RiskViolation::new(
+                    "daily_loss",
+                    format!(
+                        "{pnl:.2} USDC.e P&L over the last day reached the {:.2} USDC.e loss limit",
+                        limits.daily_loss_limit_usdc
+                    ),
+                ));
+            }
+        }
+
+        if limits.tracks_exposure() {
+            self.seed_exposure(&account.proxy_address, proxy.as_ref())
+                .await?;
+
+            let exposures = self.exposures.lock().unwrap();
+            let wallet = exposures.get(&account.proxy_address);
+            let wallet_exposure = wallet.map_or(0.0, |markets| markets.values().sum::<f64>())
+                + pending.wallet_notional;
+
+            if limits.max_wallet_exposure_usdc > 0.0
+                && wallet_exposure + notional > limits.max_wallet_exposure_usdc
+            {
+                return Err(RiskViolation::new(
+                    "max_wallet_exposure",
+                    format!(
+                        "wallet exposure would be {:.2} USDC.e, the limit is {:.2}",
+                        wallet_exposure + notional,
+                        limits.max_wallet_exposure_usdc
+                    ),
+                ));
+            }
+
+            let market_key = market.as_ref().map_or(order.token_id.as_str(), |market| {
+                market.condition_id.as_str()
+            });
+            let market_exposure = wallet
+                .and_then(|markets| markets.get(market_key))
+                .copied()
+                .unwrap_or_default()
+                + pending
+                    .market_notional
+                    .get(market_key)
+                    .copied()
+                    .unwrap_or_default();
+
+            if limits.max_market_exposure_usdc > 0.0
+                && market_exposure + notional > limits.max_market_exposure_usdc
+            {
+                return Err(RiskViolation::new(
+                    "max_market_exposure",
+                    format!(
+                        "exposure to market {market_key} would be {:.2} USDC.e, the limit is {:.2}",
+                        market_exposure + notional,
+                        limits.max_market_exposure_usdc
+                    ),
+                ));
+            }
+
+            *pending
+                .market_notional
+                .entry(market_key.to_string())
+                .or_default() += notional;
+            pending.wallet_notional += notional;
+        }
+
+        pending.count += 1;
+
+        Ok(())
+    }
+
+    // Placed orders count fully against the exposure, whether they matched or rest on the book
+    pub fn record_placed(&self, account: &Account, order: &SignedOrder) {
+        if !self.limits.tracks_exposure() {
+            return;
+        }
+
+        let market_key = self
+            .markets
+            .lock()
+            .unwrap()
+            .get(&order.token_id)
+            .map_or(order.token_id.clone(), |market| market.condition_id.clone());
+
+        let mut exposures = self.exposures.lock().unwrap();
+        let Some(wallet) = exposures.get_mut(&account.proxy_address) else {
+            return;
+        };
+
+        let exposure = wallet.entry(market_key).or_default();
+        let notional = order_notional(order);
+
+        match order.side {
+            Side::Buy => *exposure += notional,
+            Side::Sell => *exposure = (*exposure - notional).max(0.0),
+        }
+    }
+
+    async fn market_info(
+        &self,
+        token_id: &str,
+        proxy: Option<&Proxy>,
+    ) -> Result<MarketInfo, RiskViolation> {
+        if let Some(market) = self.markets.lock().unwrap().get(token_id) {
+            return Ok(market.clone());
+        }
+
+        let unresolved = |reason: String| {
+            RiskViolation::new(
+                "market_lookup",
+                format!("failed to resolve the market of token {token_id}: {reason}"),
+            )
+        };
+
+        let market = get_market_by_token_id(token_id, proxy)
+            .await
+            .map_err(|e| unresolved(e.to_string()))?
+            .ok_or_else(|| unresolved("market not found".to_string()))?;
+
+        let condition_id = market
+            .condition_id
+            .clone()
+            .unwrap_or_else(|| token_id.to_string());
+        let event_slug = market.events.first().map(|event| event.slug.clone());
+
+        let tags = match (&event_slug, self.limits.needs_tags()) {
+            (Some(slug), true) => get_event_by_slug(slug, proxy)
+                .await
+                .map_err(|e| unresolved(e.to_string()))?
+                .map(|event| event.tags.into_iter().map(|tag| tag.slug).collect())
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+
+        let info = MarketInfo {
+            identifiers: [Some(condition_id.clone()), market.slug, event_slug]
+                .into_iter()
+                .flatten()
+                .map(|identifier| identifier.to_lowercase())
+                .collect(),
+            condition_id,
+            tags: tags.iter().map(|tag| tag.to_lowercase()).collect(),
+        };
+
+        self.markets
+            .lock()
+            .unwrap()
+            .insert(token_id.to_string(), info.clone());
+
+        Ok(info)
+    }
+
+    async fn daily_pnl(
+        &self,
+        proxy_wallet_address: &str,
+        proxy: Option<&Proxy>,
+    ) -> Result<f64, RiskViolation> {
+        if let Some((fetched_at, pnl)) = self.daily_pnl.lock().unwrap().get(proxy_wallet_address) {
+            if fetched_at.elapsed() < DAILY_PNL_TTL {
+                return Ok(*pnl);
+            }
+        }
+
+        let pnl = get_user_pnl(proxy_wallet_address, "1d", proxy)
+            .await
+            .map_err(|e| {
+                RiskViolation::new("daily_loss", format!("failed to fetch the daily P&L: {e}"))
+            })?
+            .first()
+            .map_or(0.0, |stats| stats.amount);
+
+        self.daily_pnl
+            .lock()
+            .unwrap()
+            .insert(proxy_wallet_address.to_string(), (Instant::now(), pnl));
+
+        Ok(pnl)
+    }
+
+    async fn seed_exposure(
+        &self,
+        proxy_wallet_address: &str,
+        proxy: Option<&Proxy>,
+    ) -> Result<(), RiskViolation> {
+        if self
+            .exposures
+            .lock()
+            .unwrap()
+            .contains_key(proxy_wallet_address)
+        {
+            return Ok(());
+        }
+
+        let positions = get_user_positions(proxy_wallet_address, proxy)
+            .await
+            .map_err(|e| {
+                RiskViolation::new(
+                    "exposure",
+                    format!("failed to fetch positions for the exposure: {e}"),
+                )
+            })?;
+
+        let mut markets = HashMap::new();
+        for position in positions {
+            let market_key = position.condition_id.unwrap_or(position.asset);
+            *markets.entry(market_key).or_default() += position.current_value.unwrap_or_default();
+        }
+
+        self.exposures
+            .lock()
+            .unwrap()
+            .entry(proxy_wallet_address.to_string())
+            .or_insert(markets);
+
+        Ok(())
+    }
+}
+
+fn check_market_filters(limits: &RiskLimits, market: &MarketInfo) -> Result<(), RiskViolation> {
+    let matches =
+        |list: &[String], values: &[String]| values.iter().any(|value| list.contains(value));
+
+    if !limits.allowed_markets.is_empty() && !matches(&limits.allowed_markets, &market.identifiers)
+    {
+        return Err(RiskViolation::new(
+            "market_filter",
+            format!("market {} is not allowed", market.condition_id),
+        ));
+    }
+
+    if matches(&limits.blocked_markets, &market.identifiers) {
+        return Err(RiskViolation::new(
+            "market_filter",
+            format!("market {} is blocked", market.condition_id),
+        ));
+    }
+
+    if !limits.allowed_tags.is_empty() && !matches(&limits.allowed_tags, &market.tags) {
+        return Err(RiskViolation::new(
+            "tag_filter",
+            format!(
+                "market {} has none of the allowed tags ({})",
+                market.condition_id,
+                market.tags.join(", ")
+            ),
+        ));
+    }
+
+    if let Some(tag) = market
+        .tags
+        .iter()
+        .find(|tag| limits.blocked_tags.contains(tag))
+    {
+        return Err(RiskViolation::new(
+            "tag_filter",
+            format!("market {} is tagged `{tag}`", market.condition_id),
+        ));
+    }
+
+    Ok(())
+}
+
+// USDC.e a buy spends or a sell asks for
+fn order_notional(order: &SignedOrder) -> f64 {
+    let amount = match order.side {
+        Side::Buy => &order.maker_amount,
+        Side::Sell => &order.taker_amount,
+    };
+
+    amount.parse::<f64>().unwrap_or_default() / 1e6
+}
+
+pub fn init_risk_manager(config: &Config) {
+    let manager = RiskManager::new(RiskLimits::from_config(config));
+
+    if manager.kill_switch_engaged() {
+        tracing::warn!(
+            "Kill switch file {} exists, no buy orders will be placed until it is removed",
+            manager.limits.kill_switch_file
+        );
+    }
+
+    let _ = RISK_MANAGER.set(manager);
+
+    listen_for_kill_switch_signals();
+}
+
+// `kill -USR1 <pid>` blocks every new buy, `kill -USR2 <pid>` lets them through again
+#[cfg(unix)]
+fn listen_for_kill_switch_signals() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (engage, release) = match (
+        signal(SignalKind::user_defined1()),
+        signal(SignalKind::user_defined2()),
+    ) {
+        (Ok(engage), Ok(release)) => (engage, release),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!("Kill switch signals are unavailable: {e}");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let (mut engage, mut release) = (engage, release);
+
+        loop {
+            tokio::select! {
+                _ = engage.recv() => {
+                    KILL_SWITCH.store(true, Ordering::SeqCst);
+                    tracing::warn!("Kill switch engaged by SIGUSR1, new buy orders are blocked");
+                }
+                _ = release.recv() => {
+                    KILL_SWITCH.store(false, Ordering::SeqCst);
+                    tracing::warn!("Kill switch released by SIGUSR2");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn listen_for_kill_switch_signals() {}
+
+pub fn kill_switch_engaged() -> bool {
+    RISK_MANAGER.get().map_or_else(
+        || KILL_SWITCH.load(Ordering::SeqCst),
+        RiskManager::kill_switch_engaged,
+    )
+}
+
+// Limits the risk manager was set up with, none apply before it is
+pub fn risk_limits() -> RiskLimits {
+    RISK_MANAGER
+        .get()
+        .map(|manager| manager.limits.clone())
+        .unwrap_or_default()
+}
+
+pub async fn check_order(account: &Account, order: &SignedOrder) -> Result<(), CustomError> {
+    let Some(manager) = RISK_MANAGER.get() else {
+        return Ok(());
+    };
+
+    manager
+        .check(account, order)
+        .await
+        .map_err(|violation| blocked(account, order, violation))
+}
+
+// Checks the orders of a batch one after another, each against the limits with the ones accepted
+// before it already counted
+pub async fn check_orders(
+    account: &Account,
+    orders: &[&SignedOrder],
+) -> Vec<Result<(), CustomError>> {
+    let Some(manager) = RISK_MANAGER.get() else {
+        return orders.iter().map(|_| Ok(())).collect();
+    };
+
+    let mut pending = PendingOrders::default();
+    let mut results = Vec::with_capacity(orders.len());
+
+    for order in orders {
+        let result = manager
+            .check_pending(account, order, &mut pending)
+            .await
+            .map_err(|violation| blocked(account, order, violation));
+
+        results.push(result);
+    }
+
+    results
+}
+
+fn blocked(account: &Account, order: &SignedOrder, violation: RiskViolation) -> CustomError {
+    METRICS.record_order(&order.side.to_string(), "blocked", violation.rule);
+    tracing::warn!(
+        "{} | Order blocked by the {} rule: {}",
+        account.proxy_address,
+        violation.rule,
+        violation.reason
+    );
+
+    CustomError::RiskRejected(violation.reason)
+}
+
+pub fn record_placed_order(account: &Account, order: &SignedOrder) {
+    if let Some(manager) = RISK_MANAGER.get() {
+        manager.record_placed(account, order);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polymarket::api::clob::typedefs::SignatureType;
+
+    use super::*;
+
+    fn account() -> Account {
+        Account::new(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+            None,
+            None,
+        )
+    }
+
+    // A buy spending or a sell asking for `usdc` USDC.e
+    fn order(side: Side, usdc: f64) -> SignedOrder {
+        let amount = ((usdc * 1e6) as u64).to_string();
+        let (maker_amount, taker_amount) = match side {
+            Side::Buy => (amount, "0".to_string()),
+            Side::Sell => ("0".to_string(), amount),
+        };
+
+        SignedOrder {
+            salt: 0,
+            maker: String::new(),
+            signer: String::new(),
+            taker: String::new(),
+            token_id: "yes".to_string(),
+            maker_amount,
+            taker_amount,
+            side,
+            expiration: "0".to_string(),
+            nonce: "0".to_string(),
+            fee_rate_bps: "0".to_string(),
+            signature_type: SignatureType::PolyProxy,
+            signature: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    async fn rule(
+        manager: &RiskManager,
+        order: &SignedOrder,
+        pending: &mut PendingOrders,
+    ) -> Option<&'static str> {
+        manager
+            .check_pending(&account(), order, pending)
+            .await
+            .err()
+            .map(|violation| violation.rule)
+    }
+
+    #[tokio::test]
+    async fn only_buys_are_held_to_the_order_limit() {
+        let manager = RiskManager::new(RiskLimits {
+            max_order_usdc: 10.0,
+            ..Default::default()
+        });
+        let mut pending = PendingOrders::default();
+
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 10.0), &mut pending).await,
+            None
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 20.0), &mut pending).await,
+            Some("max_order")
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Sell, 20.0), &mut pending).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn the_kill_switch_blocks_buys_but_not_exits() {
+        let kill_switch_file =
+            std::env::temp_dir().join(format!("polymarket-eye-kill-switch-{}", std::process::id()));
+        std::fs::write(&kill_switch_file, "").unwrap();
+
+        let manager = RiskManager::new(RiskLimits {
+            kill_switch_file: kill_switch_file.to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        let mut pending = PendingOrders::default();
+
+        let buy = rule(&manager, &order(Side::Buy, 1.0), &mut pending).await;
+        let sell = rule(&manager, &order(Side::Sell, 1.0), &mut pending).await;
+        std::fs::remove_file(&kill_switch_file).unwrap();
+
+        assert_eq!(buy, Some("kill_switch"));
+        assert_eq!(sell, None);
+    }
+
+    #[tokio::test]
+    async fn orders_of_a_batch_count_against_the_limits_of_the_next() {
+        let manager = RiskManager::new(RiskLimits {
+            max_wallet_exposure_usdc: 25.0,
+            max_open_orders: 4,
+            ..Default::default()
+        });
+        manager.exposures.lock().unwrap().insert(
+            account().proxy_address,
+            HashMap::from([("yes".to_string(), 5.0)]),
+        );
+
+        // One order is already open
+        let mut pending = PendingOrders {
+            open_orders: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 10.0), &mut pending).await,
+            None
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 10.0), &mut pending).await,
+            None
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 10.0), &mut pending).await,
+            Some("max_wallet_exposure")
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Sell, 50.0), &mut pending).await,
+            None
+        );
+        assert_eq!(
+            rule(&manager, &order(Side::Buy, 1.0), &mut pending).await,
+            Some("max_open_orders")
+        );
+
+        assert_eq!(pending.count, 3);
+        assert_eq!(pending.wallet_notional, 20.0);
+        assert_eq!(pending.market_notional["yes"], 20.0);
+    }
+}
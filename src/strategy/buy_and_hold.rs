@@ -0,0 +1,40 @@
+use std::collections::HashSet;
+
+use crate::{config::Config, polymarket::api::clob::schemas::OrderBookData};
+
+use super::{OrderIntent, Strategy, StrategyContext};
+
+// Baseline to compare other strategies against: spends the bet percentage of the remaining
+// balance on every token the first time its book is seen and never sells
+pub struct BuyAndHoldStrategy {
+    bet_percentage: f64,
+    bought: HashSet<String>,
+}
+
+impl BuyAndHoldStrategy {
+    pub fn from_config(config: &Config) -> Self {
+        let [min_percentage, max_percentage] = config.bet_balance_percentage;
+
+        Self {
+            bet_percentage: (min_percentage + max_percentage) as f64 / 2.0,
+            bought: HashSet::new(),
+        }
+    }
+}
+
+impl Strategy for BuyAndHoldStrategy {
+    fn name(&self) -> &'static str {
+        "buy_and_hold"
+    }
+
+    fn on_book(&mut self, ctx: &StrategyContext, book: &OrderBookData) -> Vec<OrderIntent> {
+        if !self.bought.insert(book.asset_id.clone()) {
+            return vec![];
+        }
+
+        vec![OrderIntent::buy(
+            &book.asset_id,
+            ctx.cash * self.bet_percentage / 100.0,
+        )]
+    }
+}
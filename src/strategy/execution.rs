@@ -0,0 +1,375 @@
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use serde::Deserialize;
+
+use crate::{
+    db::account::Account,
+    errors::{clob::ClobRemedy, custom::CustomError},
+    modules::registration::create_or_derive_api_key,
+    polymarket::api::clob::{
+        endpoints::{get_order_book, place_order},
+        math::{calculate_fee, calculate_market_price},
+        metadata::{get_market_metadata, MarketMetadata},
+        order_builder::OrderBuilder,
+        schemas::{OrderBookData, OrderRequest, OrderStatus, OrderType},
+        typedefs::{CreateOrderOptions, Side, SignedOrder, UserMarketOrder, UserOrder},
+        validation::validate_order_size,
+    },
+    risk::{kill_switch_engaged, risk_limits, RiskLimits},
+};
+
+use super::{simulator::simulate_fill, FillEvent, OrderIntent, Portfolio};
+
+pub const MAX_ORDER_ATTEMPTS: usize = 3;
+pub const ORDER_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    Live,
+    DryRun,
+}
+
+// Checks every intent has to pass before it is priced, whether it is executed live, in a dry run or
+// in a backtest. The risk limits are applied to the portfolio at its marks, live orders are
+// checked against the wallet's actual exposure by the risk manager again when they are placed
+pub fn check_intent(
+    intent: &OrderIntent,
+    portfolio: &Portfolio,
+    limits: &RiskLimits,
+) -> Result<(), String> {
+    if intent.amount <= 0.0 {
+        return Err("zero amount".to_string());
+    }
+
+    match intent.side {
+        Side::Sell if portfolio.position(&intent.token_id) <= 0.0 => {
+            return Err("no position to sell".to_string())
+        }
+        // Sells only reduce exposure, like the risk manager the limits let them through
+        Side::Sell => return Ok(()),
+        Side::Buy if intent.amount > portfolio.cash => {
+            return Err(format!(
+                "not enough balance, {:.2} USDC.e needed and {:.2} available",
+                intent.amount, portfolio.cash
+            ))
+        }
+        Side::Buy => {}
+    }
+
+    let notional = intent.amount;
+
+    if limits.max_order_usdc > 0.0 && notional > limits.max_order_usdc {
+        return Err(format!(
+            "order of {notional:.2} USDC.e is above the {:.2} USDC.e limit",
+            limits.max_order_usdc
+        ));
+    }
+
+    let wallet_exposure = portfolio.positions_value() + notional;
+    if limits.max_wallet_exposure_usdc > 0.0 && wallet_exposure > limits.max_wallet_exposure_usdc {
+        return Err(format!(
+            "wallet exposure would be {wallet_exposure:.2} USDC.e, the limit is {:.2}",
+            limits.max_wallet_exposure_usdc
+        ));
+    }
+
+    let market_exposure = portfolio.market_value(&intent.token_id) + notional;
+    if limits.max_market_exposure_usdc > 0.0 && market_exposure > limits.max_market_exposure_usdc {
+        return Err(format!(
+            "market exposure would be {market_exposure:.2} USDC.e, the limit is {:.2}",
+            limits.max_market_exposure_usdc
+        ));
+    }
+
+    Ok(())
+}
+
+// What to do about a failed order, `None` when it should be given up on
+pub fn remedy_for(error: &CustomError, attempt: usize) -> Option<ClobRemedy> {
+    let remedy = error.clob_rejection()?.remedy();
+
+    (attempt < MAX_ORDER_ATTEMPTS && remedy != ClobRemedy::Abort).then_some(remedy)
+}
+
+pub async fn retry_after_rejection(
+    proxy_wallet_address: &str,
+    error: &CustomError,
+    attempt: usize,
+) {
+    tracing::warn!(
+        "{proxy_wallet_address} | {error}, attempt {}/{MAX_ORDER_ATTEMPTS} in {} seconds",
+        attempt + 1,
+        ORDER_RETRY_DELAY.as_secs()
+    );
+
+    tokio::time::sleep(ORDER_RETRY_DELAY).await;
+}
+
+// Dry runs are charged the market's fee rate too, so their P&L matches what live fills would make
+pub struct Executor<'a> {
+    account: &'a Account,
+    mode: ExecutionMode,
+}
+
+impl<'a> Executor<'a> {
+    pub fn new(account: &'a Account, mode: ExecutionMode) -> Self {
+        Self { account, mode }
+    }
+
+    // `None` when the order was accepted but rests on the book instead of matching
+    pub async fn execute(
+        &self,
+        intent: &OrderIntent,
+        book: &OrderBookData,
+        portfolio: &Portfolio,
+    ) -> eyre::Result<Option<FillEvent>> {
+        if intent.side == Side::Buy && kill_switch_engaged() {
+            eyre::bail!("kill switch is engaged");
+        }
+
+        check_intent(intent, portfolio, &risk_limits()).map_err(|e| eyre::eyre!(e))?;
+
+        let proxy = self.account.proxy();
+        let metadata = get_market_metadata(&intent.token_id, proxy.as_ref()).await?;
+
+        match self.mode {
+            ExecutionMode::DryRun => simulate_fill(
+                intent,
+                book,
+                portfolio,
+                metadata.fee_rate_bps,
+                metadata.tick_size,
+            )
+            .map(|simulated| Some(simulated.fill))
+            .map_err(|e| eyre::eyre!(e)),
+            ExecutionMode::Live => self.place(intent, book, portfolio, &metadata).await,
+        }
+    }
+
+    async fn build_order(
+        &self,
+        intent: &OrderIntent,
+        amount: f64,
+        book: &OrderBookData,
+        portfolio: &Portfolio,
+        metadata: &MarketMetadata,
+    ) -> eyre::Result<(SignedOrder, Option<OrderType>)> {
+        let account = self.account;
+        let proxy_wallet_address = account.get_proxy_address().to_string();
+        let order_builder =
+            OrderBuilder::new(account.signer(), 137, None, Some(&proxy_wallet_address));
+
+        let options = CreateOrderOptions::from_metadata(metadata);
+
+        match intent.side {
+            Side::Buy => {
+                let price = calculate_market_price(Side::Buy, book.clone(), amount, None);
+                validate_order_size(&Side::Buy, amount, price, metadata)?;
+                let order = UserMarketOrder::new(
+                    intent.token_id.clone(),
+                    amount,
+                    Some(price),
+                    None,
+                    None,
+                    None,
+                );
+
+                Ok((
+                    order_builder
+                        .build_signed_market_buy_order(order, options)
+                        .await?,
+                    None,
+                ))
+            }
+            Side::Sell => {
+                let size = amount.min(portfolio.position(&intent.token_id));
+                let price = calculate_market_price(Side::Sell, book.clone(), size, None);
+                validate_order_size(&Side::Sell, size, price, metadata)?;
+                let order = UserOrder::default()
+                    .with_token_id(&intent.token_id)
+                    .with_price(price)
+                    .with_side(Side::Sell)
+                    .with_size(size)
+                    .with_taker(Address::ZERO.to_string());
+
+                Ok((
+                    order_builder.build_signed_order(order, options).await?,
+                    Some(OrderType::Gtc),
+                ))
+            }
+        }
+    }
+
+    // Rejections a rebuilt order can get past are retried on a fresh book and market metadata,
+    // buys that couldn't fill whole are halved
+    async fn place(
+        &self,
+        intent: &OrderIntent,
+        book: &OrderBookData,
+        portfolio: &Portfolio,
+        metadata: &MarketMetadata,
+    ) -> eyre::Result<Option<FillEvent>> {
+        let account = self.account;
+        let proxy = account.proxy();
+
+        let api_key = {
+            let maybe_key = account.api_key.read().unwrap().clone();
+            if let Some(key) = maybe_key {
+                key
+            } else {
+                let response =
+                    create_or_derive_api_key(account.signer(), account.proxy().as_ref()).await?;
+                account.update_credentials(response);
+                account.api_key.read().unwrap().as_ref().unwrap().clone()
+            }
+        };
+
+        let mut book = book.clone();
+        let mut metadata = metadata.clone();
+        let mut amount = intent.amount;
+        let mut attempt = 1;
+
+        let response = loop {
+            let (signed_order, order_type) = self
+                .build_order(intent, amount, &book, portfolio, &metadata)
+                .await?;
+
+            let error = match place_order(
+                account,
+                OrderRequest::new(signed_order, &api_key, order_type),
+            )
+            .await
+            {
+                Ok(response) => break response,
+                Err(e) => e,
+            };
+
+            match remedy_for(&error, attempt) {
+                Some(ClobRemedy::Retry) => {}
+                Some(ClobRemedy::Resize) if intent.side == Side::Buy => amount /= 2.0,
+                _ => return Err(error.into()),
+            }
+
+            retry_after_rejection(&account.proxy_address, &error, attempt).await;
+
+            book = get_order_book(&intent.token_id, proxy.as_ref()).await?;
+            metadata = get_market_metadata(&intent.token_id, proxy.as_ref()).await?;
+            attempt += 1;
+        };
+
+        response.log_successful_placement(intent.side.clone(), &account.proxy_address);
+
+        if !matches!(response.status, Some(OrderStatus::Matched)) {
+            return Ok(None);
+        }
+
+        let amount = |amount: &Option<String>| {
+            amount
+                .as_deref()
+                .and_then(|amount| amount.parse::<f64>().ok())
+                .unwrap_or_default()
+        };
+
+        // A buy makes USDC.e and takes shares, a sell the other way around
+        let (notional, size) = match intent.side {
+            Side::Buy => (
+                amount(&response.making_amount),
+                amount(&response.taking_amount),
+            ),
+            Side::Sell => (
+                amount(&response.taking_amount),
+                amount(&response.making_amount),
+            ),
+        };
+
+        if size == 0.0 {
+            return Ok(None);
+        }
+
+        let price = notional / size;
+        let fee = calculate_fee(metadata.fee_rate_bps, price, size);
+
+        Ok(Some(FillEvent {
+            token_id: intent.token_id.clone(),
+            side: intent.side.clone(),
+            price,
+            size: match intent.side {
+                Side::Buy => size - fee / price,
+                Side::Sell => size,
+            },
+            notional,
+            fee,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::strategy::test_book;
+
+    use super::*;
+
+    fn book(market: &str, token_id: &str, price: f64) -> OrderBookData {
+        test_book(market, token_id, &[(price, 100.0)], &[(price, 100.0)])
+    }
+
+    fn portfolio() -> Portfolio {
+        let mut portfolio = Portfolio::new(
+            100.0,
+            HashMap::from([("yes".to_string(), 40.0), ("other".to_string(), 20.0)]),
+        );
+        portfolio.update_mark(&book("0xmarket", "yes", 0.5));
+        portfolio.update_mark(&book("0xmarket", "no", 0.5));
+        portfolio.update_mark(&book("0xother", "other", 0.5));
+
+        portfolio
+    }
+
+    #[test]
+    fn orders_above_the_order_limit_are_refused() {
+        let limits = RiskLimits {
+            max_order_usdc: 10.0,
+            ..Default::default()
+        };
+
+        assert!(check_intent(&OrderIntent::buy("no", 10.0), &portfolio(), &limits).is_ok());
+        assert!(check_intent(&OrderIntent::buy("no", 11.0), &portfolio(), &limits).is_err());
+        // Sells of any size can exit a position
+        assert!(check_intent(&OrderIntent::sell("yes", 40.0), &portfolio(), &limits).is_ok());
+    }
+
+    #[test]
+    fn buys_count_the_marked_positions_against_the_exposure_limits() {
+        let wallet = RiskLimits {
+            max_wallet_exposure_usdc: 40.0,
+            ..Default::default()
+        };
+        assert!(check_intent(&OrderIntent::buy("no", 10.0), &portfolio(), &wallet).is_ok());
+        assert!(check_intent(&OrderIntent::buy("no", 11.0), &portfolio(), &wallet).is_err());
+
+        // The other outcome of the market counts, the other market doesn't
+        let market = RiskLimits {
+            max_market_exposure_usdc: 25.0,
+            ..Default::default()
+        };
+        assert!(check_intent(&OrderIntent::buy("no", 5.0), &portfolio(), &market).is_ok());
+        assert!(check_intent(&OrderIntent::buy("no", 6.0), &portfolio(), &market).is_err());
+        assert!(check_intent(&OrderIntent::buy("other", 15.0), &portfolio(), &market).is_ok());
+    }
+
+    #[test]
+    fn sells_are_not_held_to_the_exposure_limits() {
+        let limits = RiskLimits {
+            max_wallet_exposure_usdc: 1.0,
+            max_market_exposure_usdc: 1.0,
+            ..Default::default()
+        };
+
+        assert!(check_intent(&OrderIntent::sell("yes", 40.0), &portfolio(), &limits).is_ok());
+        assert!(check_intent(&OrderIntent::sell("no", 1.0), &portfolio(), &limits).is_err());
+    }
+}
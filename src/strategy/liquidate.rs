@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use crate::polymarket::api::clob::schemas::OrderBookData;
+
+use super::{FillEvent, OrderIntent, Strategy, StrategyContext};
+
+// Sell all open positions: every held token is sold at the price its book can absorb, partial
+// fills are followed up until only a remainder below the size precision is left
+#[derive(Default)]
+pub struct LiquidateStrategy {
+    attempted: HashSet<String>,
+}
+
+impl Strategy for LiquidateStrategy {
+    fn name(&self) -> &'static str {
+        "liquidate"
+    }
+
+    fn on_book(&mut self, ctx: &StrategyContext, book: &OrderBookData) -> Vec<OrderIntent> {
+        let position = ctx.position(&book.asset_id);
+
+        if position <= 0.0 || !self.attempted.insert(book.asset_id.clone()) {
+            return vec![];
+        }
+
+        vec![OrderIntent::sell(&book.asset_id, position)]
+    }
+
+    fn on_fill(&mut self, ctx: &StrategyContext, fill: &FillEvent) -> Vec<OrderIntent> {
+        let remaining = ctx.position(&fill.token_id);
+
+        if remaining < 0.01 {
+            return vec![];
+        }
+
+        vec![OrderIntent::sell(&fill.token_id, remaining)]
+    }
+
+    // Every position got its sell, whatever is still held couldn't be sold
+    fn finished(&self, ctx: &StrategyContext) -> bool {
+        ctx.positions
+            .keys()
+            .all(|token_id| self.attempted.contains(token_id))
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::Config,
+    polymarket::api::clob::{analytics::BookAnalytics, schemas::OrderBookData, typedefs::Side},
+};
+
+pub mod buy_and_hold;
+pub mod execution;
+pub mod liquidate;
+pub mod opposing;
+pub mod runtime;
+pub mod simulator;
+
+// What a strategy wants to trade, sized like the live order flows: buys spend `amount` USDC.e
+// as a market order and sells dispose of `amount` shares at the price the book can absorb
+#[derive(Debug, Clone)]
+pub struct OrderIntent {
+    pub token_id: String,
+    pub side: Side,
+    pub amount: f64,
+}
+
+impl OrderIntent {
+    pub fn buy(token_id: &str, usdc_amount: f64) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            amount: usdc_amount,
+        }
+    }
+
+    pub fn sell(token_id: &str, size: f64) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            side: Side::Sell,
+            amount: size,
+        }
+    }
+}
+
+// A matched order, `size` is what the position changed by and `fee` is in USDC.e
+#[derive(Debug, Clone)]
+pub struct FillEvent {
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub notional: f64,
+    pub fee: f64,
+}
+
+// State of the wallet a strategy trades with at the time an event is delivered, `timestamp_ms`
+// is wall clock time live and recorded time in a backtest
+pub struct StrategyContext<'a> {
+    pub cash: f64,
+    pub positions: &'a HashMap<String, f64>,
+    pub timestamp_ms: i64,
+}
+
+impl StrategyContext<'_> {
+    pub fn position(&self, token_id: &str) -> f64 {
+        self.positions.get(token_id).copied().unwrap_or_default()
+    }
+}
+
+// Decision logic only, the runtime or the backtester feeds it events and executes what it returns
+pub trait Strategy: Send {
+    fn name(&self) -> &'static str;
+
+    fn on_book(&mut self, ctx: &StrategyContext, book: &OrderBookData) -> Vec<OrderIntent>;
+
+    fn on_fill(&mut self, _ctx: &StrategyContext, _fill: &FillEvent) -> Vec<OrderIntent> {
+        vec![]
+    }
+
+    fn on_timer(&mut self, _ctx: &StrategyContext) -> Vec<OrderIntent> {
+        vec![]
+    }
+
+    // The runtime stops once this is true, strategies that trade for as long as they run never are
+    fn finished(&self, _ctx: &StrategyContext) -> bool {
+        false
+    }
+}
+
+pub fn available_strategies(config: &Config) -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(buy_and_hold::BuyAndHoldStrategy::from_config(config)),
+        Box::new(liquidate::LiquidateStrategy::default()),
+        Box::new(opposing::OpposingStrategy::from_config(config)),
+    ]
+}
+
+pub fn strategy_by_name(name: &str, config: &Config) -> eyre::Result<Box<dyn Strategy>> {
+    let strategies = available_strategies(config);
+    let names = strategies
+        .iter()
+        .map(|strategy| strategy.name())
+        .collect::<Vec<_>>();
+
+    strategies
+        .into_iter()
+        .find(|strategy| strategy.name() == name)
+        .ok_or_else(|| eyre::eyre!("Unknown strategy `{name}`, expected one of {names:?}"))
+}
+
+#[derive(Default)]
+pub struct Portfolio {
+    pub cash: f64,
+    pub positions: HashMap<String, f64>,
+    marks: HashMap<String, f64>,
+    // Token -> condition id of its market, learned from the books
+    markets: HashMap<String, String>,
+}
+
+impl Portfolio {
+    pub fn new(cash: f64, positions: HashMap<String, f64>) -> Self {
+        Self {
+            cash,
+            positions,
+            marks: HashMap::new(),
+            markets: HashMap::new(),
+        }
+    }
+
+    pub fn context(&self, timestamp_ms: i64) -> StrategyContext<'_> {
+        StrategyContext {
+            cash: self.cash,
+            positions: &self.positions,
+            timestamp_ms,
+        }
+    }
+
+    pub fn position(&self, token_id: &str) -> f64 {
+        self.positions.get(token_id).copied().unwrap_or_default()
+    }
+
+    // Positions are marked at the midpoint, or the best bid of a one sided book
+    pub fn update_mark(&mut self, book: &OrderBookData) {
+        let analytics = BookAnalytics::new(book.clone());
+
+        if !book.market.is_empty() {
+            self.markets
+                .insert(book.asset_id.clone(), book.market.clone());
+        }
+
+        if let Some(mark) = analytics
+            .midpoint()
+            .or(analytics.best_bid().map(|bid| bid.price))
+        {
+            self.marks.insert(book.asset_id.clone(), mark);
+        }
+    }
+
+    pub fn apply_fill(&mut self, fill: &FillEvent) {
+        let position = self.positions.entry(fill.token_id.clone()).or_default();
+
+        match fill.side {
+            Side::Buy => {
+                self.cash -= fill.notional;
+                *position += fill.size;
+            }
+            Side::Sell => {
+                self.cash += fill.notional - fill.fee;
+                *position -= fill.size;
+            }
+        }
+
+        if *position <= f64::EPSILON {
+            self.positions.remove(&fill.token_id);
+        }
+    }
+
+    pub fn positions_value(&self) -> f64 {
+        self.positions
+            .iter()
+            .map(|(token_id, size)| size * self.mark(token_id))
+            .sum()
+    }
+
+    pub fn mark(&self, token_id: &str) -> f64 {
+        self.marks.get(token_id).copied().unwrap_or_default()
+    }
+
+    // Marked value of the positions held in the market of `token_id`
+    pub fn market_value(&self, token_id: &str) -> f64 {
+        let market = self.markets.get(token_id);
+
+        self.positions
+            .iter()
+            .filter(|(held, _)| {
+                held.as_str() == token_id || (market.is_some() && self.markets.get(*held) == market)
+            })
+            .map(|(held, size)| size * self.mark(held))
+            .sum()
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.cash + self.positions_value()
+    }
+}
+
+// Book of `token_id` in `market` with the given (price, size) levels
+#[cfg(test)]
+pub fn test_book(
+    market: &str,
+    token_id: &str,
+    bids: &[(f64, f64)],
+    asks: &[(f64, f64)],
+) -> OrderBookData {
+    use crate::polymarket::api::clob::schemas::Order;
+
+    let levels = |levels: &[(f64, f64)]| {
+        levels
+            .iter()
+            .map(|&(price, size)| Order { price, size })
+            .collect()
+    };
+
+    OrderBookData {
+        market: market.to_string(),
+        asset_id: token_id.to_string(),
+        timestamp: String::new(),
+        hash: String::new(),
+        bids: levels(bids),
+        asks: levels(asks),
+    }
+}
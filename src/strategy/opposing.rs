@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use crate::{config::Config, polymarket::api::clob::schemas::OrderBookData};
+
+use super::{OrderIntent, Strategy, StrategyContext};
+
+enum Phase {
+    Idle { cycles: u64 },
+    Holding { since_ms: i64, cycles: u64 },
+}
+
+struct MarketState {
+    token_ids: Vec<String>,
+    phase: Phase,
+}
+
+impl MarketState {
+    // A leg that failed to fill unwinds the other one right away
+    fn exit(&mut self, ctx: &StrategyContext, hold_ms: i64) -> Vec<OrderIntent> {
+        let Phase::Holding { since_ms, cycles } = self.phase else {
+            return vec![];
+        };
+
+        let filled_legs = self
+            .token_ids
+            .iter()
+            .filter(|token_id| ctx.position(token_id) > 0.0)
+            .count();
+
+        if filled_legs == 2 && ctx.timestamp_ms - since_ms < hold_ms {
+            return vec![];
+        }
+
+        self.phase = Phase::Idle { cycles: cycles + 1 };
+
+        self.token_ids
+            .iter()
+            .filter(|token_id| ctx.position(token_id) > 0.0)
+            .map(|token_id| OrderIntent::sell(token_id, ctx.position(token_id)))
+            .collect()
+    }
+}
+
+// Opposing bets: buys both outcomes of a market, holds them for the sell delay and sells both,
+// `cycle_count` times per market. Live every leg is bought from its own wallet, each spending the
+// bet percentage of the smaller balance, in a backtest both come out of the one portfolio
+pub struct OpposingStrategy {
+    bet_percentage: f64,
+    hold_ms: i64,
+    cycle_count: u64,
+    markets: HashMap<String, MarketState>,
+}
+
+impl OpposingStrategy {
+    pub fn new(bet_percentage: f64, hold_ms: i64, cycle_count: u64) -> Self {
+        Self {
+            bet_percentage,
+            hold_ms,
+            cycle_count,
+            markets: HashMap::new(),
+        }
+    }
+
+    // Ranges are replaced by their midpoints so runs are reproducible
+    pub fn from_config(config: &Config) -> Self {
+        let [min_percentage, max_percentage] = config.bet_balance_percentage;
+        let [min_delay, max_delay] = config.sell_delay_range;
+
+        Self::new(
+            (min_percentage + max_percentage) as f64 / 2.0,
+            (min_delay + max_delay) as i64 * 1000 / 2,
+            config.cycle_count,
+        )
+    }
+}
+
+impl Strategy for OpposingStrategy {
+    fn name(&self) -> &'static str {
+        "opposing"
+    }
+
+    fn on_book(&mut self, ctx: &StrategyContext, book: &OrderBookData) -> Vec<OrderIntent> {
+        let state = self
+            .markets
+            .entry(book.market.clone())
+            .or_insert_with(|| MarketState {
+                token_ids: vec![],
+                phase: Phase::Idle { cycles: 0 },
+            });
+
+        if !state.token_ids.contains(&book.asset_id) {
+            state.token_ids.push(book.asset_id.clone());
+        }
+
+        // Both outcomes have to be seen before the market can be traded
+        if state.token_ids.len() != 2 {
+            return vec![];
+        }
+
+        match state.phase {
+            Phase::Idle { cycles } if cycles < self.cycle_count => {
+                let amount = ctx.cash * self.bet_percentage / 100.0;
+
+                state.phase = Phase::Holding {
+                    since_ms: ctx.timestamp_ms,
+                    cycles,
+                };
+
+                state
+                    .token_ids
+                    .iter()
+                    .map(|token_id| OrderIntent::buy(token_id, amount))
+                    .collect()
+            }
+            Phase::Holding { .. } => state.exit(ctx, self.hold_ms),
+            Phase::Idle { .. } => vec![],
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &StrategyContext) -> Vec<OrderIntent> {
+        self.markets
+            .values_mut()
+            .flat_map(|state| state.exit(ctx, self.hold_ms))
+            .collect()
+    }
+
+    fn finished(&self, _ctx: &StrategyContext) -> bool {
+        !self.markets.is_empty()
+            && self.markets.values().all(
+                |state| matches!(state.phase, Phase::Idle { cycles } if cycles >= self.cycle_count),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        polymarket::api::clob::typedefs::Side,
+        strategy::{test_book, FillEvent, Portfolio},
+    };
+
+    use super::*;
+
+    fn book(token_id: &str) -> OrderBookData {
+        test_book("0xmarket", token_id, &[(0.49, 100.0)], &[(0.51, 100.0)])
+    }
+
+    fn fill(token_id: &str, size: f64) -> FillEvent {
+        FillEvent {
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            price: 0.5,
+            size,
+            notional: size * 0.5,
+            fee: 0.0,
+        }
+    }
+
+    fn sells(intents: &[OrderIntent]) -> Vec<(&str, f64)> {
+        intents
+            .iter()
+            .filter(|intent| intent.side == Side::Sell)
+            .map(|intent| (intent.token_id.as_str(), intent.amount))
+            .collect()
+    }
+
+    #[test]
+    fn both_legs_are_held_for_the_delay_then_sold() {
+        let mut strategy = OpposingStrategy::new(10.0, 1000, 1);
+        let mut portfolio = Portfolio::new(100.0, HashMap::new());
+
+        assert!(strategy
+            .on_book(&portfolio.context(0), &book("yes"))
+            .is_empty());
+
+        let buys = strategy.on_book(&portfolio.context(0), &book("no"));
+        assert_eq!(buys.len(), 2);
+        assert!(buys
+            .iter()
+            .all(|intent| intent.side == Side::Buy && intent.amount == 10.0));
+
+        portfolio.apply_fill(&fill("yes", 20.0));
+        portfolio.apply_fill(&fill("no", 20.0));
+
+        assert!(strategy.on_timer(&portfolio.context(999)).is_empty());
+        assert!(!strategy.finished(&portfolio.context(999)));
+
+        let exits = strategy.on_timer(&portfolio.context(1000));
+        assert_eq!(sells(&exits), [("yes", 20.0), ("no", 20.0)]);
+        assert!(strategy.finished(&portfolio.context(1000)));
+    }
+
+    #[test]
+    fn a_leg_that_did_not_fill_unwinds_the_other() {
+        let mut strategy = OpposingStrategy::new(10.0, 60_000, 2);
+        let mut portfolio = Portfolio::new(100.0, HashMap::new());
+
+        strategy.on_book(&portfolio.context(0), &book("yes"));
+        strategy.on_book(&portfolio.context(0), &book("no"));
+        portfolio.apply_fill(&fill("yes", 20.0));
+
+        let exits = strategy.on_book(&portfolio.context(1), &book("yes"));
+        assert_eq!(sells(&exits), [("yes", 20.0)]);
+
+        // One cycle of two is done, the next book starts the second
+        assert!(!strategy.finished(&portfolio.context(1)));
+        assert_eq!(
+            strategy.on_book(&portfolio.context(2), &book("no")).len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use alloy::primitives::utils::format_units;
+use chrono::Utc;
+use itertools::Itertools;
+
+use crate::{
+    config::Config,
+    db::account::Account,
+    onchain::{
+        rpc::{quorum_balance_of, PolygonProvider},
+        types::token::Token,
+    },
+    polymarket::api::{
+        clob::{endpoints::get_order_books, schemas::OrderBookData},
+        user::endpoints::get_user_positions,
+    },
+};
+
+use super::{
+    execution::{ExecutionMode, Executor},
+    OrderIntent, Portfolio, Strategy,
+};
+
+// A wallet the runtime trades from, intents on `token_ids` are executed with its account
+pub struct StrategyWallet<'a> {
+    pub account: &'a Account,
+    pub portfolio: Portfolio,
+    pub token_ids: Vec<String>,
+}
+
+// Polls the books of every wallet's tokens and routes the strategy's intents through the executor
+// of the wallet trading the token, until the strategy is finished or Ctrl+C. Returns the fill count,
+// the wallets are left with their portfolios as the run ended
+pub async fn run_strategy(
+    config: &Config,
+    mode: ExecutionMode,
+    strategy: &mut dyn Strategy,
+    wallets: &mut [StrategyWallet<'_>],
+) -> eyre::Result<usize> {
+    let Some(proxy) = wallets.first().map(|wallet| wallet.account.proxy()) else {
+        return Ok(0);
+    };
+
+    let executors = wallets
+        .iter()
+        .map(|wallet| Executor::new(wallet.account, mode))
+        .collect_vec();
+    let mut latest_books: HashMap<String, OrderBookData> = HashMap::new();
+    let mut fills = 0;
+
+    let mut books_interval =
+        tokio::time::interval(Duration::from_secs(config.strategy_poll_interval_secs));
+    let mut timer_interval =
+        tokio::time::interval(Duration::from_secs(config.strategy_timer_interval_secs));
+    timer_interval.tick().await;
+
+    let token_ids = wallets
+        .iter()
+        .flat_map(|wallet| wallet.token_ids.iter().cloned())
+        .unique()
+        .collect_vec();
+    let ids = token_ids.iter().map(|id| id.as_str()).collect_vec();
+
+    tracing::info!(
+        "Running `{}` ({mode:?}) on {} tokens, press Ctrl+C to stop",
+        strategy.name(),
+        token_ids.len()
+    );
+
+    loop {
+        tokio::select! {
+            _ = books_interval.tick() => {
+                let books = match get_order_books(&ids, proxy.as_ref()).await {
+                    Ok(books) => books,
+                    Err(e) => {
+                        tracing::warn!("Failed to poll order books: {e}");
+                        continue;
+                    }
+                };
+
+                for book in books {
+                    for wallet in wallets.iter_mut() {
+                        wallet.portfolio.update_mark(&book);
+                    }
+                    latest_books.insert(book.asset_id.clone(), book.clone());
+
+                    let intents = strategy.on_book(&combined(wallets).context(now_ms()), &book);
+                    fills += execute_intents(&executors, strategy, wallets, &latest_books, intents).await;
+                }
+            }
+            _ = timer_interval.tick() => {
+                let intents = strategy.on_timer(&combined(wallets).context(now_ms()));
+                fills += execute_intents(&executors, strategy, wallets, &latest_books, intents).await;
+            }
+            _ = tokio::signal::ctrl_c() => {
+                log_summary(strategy, wallets, "stopped");
+                return Ok(fills);
+            }
+        }
+
+        if strategy.finished(&combined(wallets).context(now_ms())) {
+            log_summary(strategy, wallets, "finished");
+            return Ok(fills);
+        }
+    }
+}
+
+// Dry runs start from the wallet's real balance and positions too, only the fills are simulated
+pub async fn load_portfolio(
+    account: &Account,
+    config: &Config,
+    provider: Arc<PolygonProvider>,
+) -> eyre::Result<Portfolio> {
+    let balance = quorum_balance_of(
+        &[account.get_proxy_address()],
+        Token::USDCE,
+        provider,
+        config.rpc_balance_quorum,
+    )
+    .await?[0];
+
+    let positions = get_user_positions(&account.proxy_address, account.proxy().as_ref())
+        .await?
+        .into_iter()
+        .map(|position| (position.asset, position.size))
+        .collect();
+
+    Ok(Portfolio::new(
+        format_units(balance, "mwei")?.parse::<f64>()?,
+        positions,
+    ))
+}
+
+fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+// What the strategy sees of its wallets: their positions together and the smallest balance, as
+// every wallet has to afford the orders it executes
+fn combined(wallets: &[StrategyWallet]) -> Portfolio {
+    let cash = wallets
+        .iter()
+        .map(|wallet| wallet.portfolio.cash)
+        .fold(f64::INFINITY, f64::min);
+
+    let mut positions: HashMap<String, f64> = HashMap::new();
+    for (token_id, size) in wallets
+        .iter()
+        .flat_map(|wallet| &wallet.portfolio.positions)
+    {
+        *positions.entry(token_id.clone()).or_default() += size;
+    }
+
+    Portfolio::new(cash, positions)
+}
+
+fn log_summary(strategy: &dyn Strategy, wallets: &[StrategyWallet], outcome: &str) {
+    for wallet in wallets {
+        tracing::info!(
+            "{} | `{}` {outcome}. Cash: {:.2} USDC.e, positions value: {:.2}, equity: {:.2}",
+            wallet.account.proxy_address,
+            strategy.name(),
+            wallet.portfolio.cash,
+            wallet.portfolio.positions_value(),
+            wallet.portfolio.equity()
+        );
+    }
+}
+
+// Fills are fed back to the strategy and whatever it decides on them is executed in the same pass
+async fn execute_intents(
+    executors: &[Executor<'_>],
+    strategy: &mut dyn Strategy,
+    wallets: &mut [StrategyWallet<'_>],
+    latest_books: &HashMap<String, OrderBookData>,
+    intents: Vec<OrderIntent>,
+) -> usize {
+    let mut queue = VecDeque::from(intents);
+    let mut fills = 0;
+
+    while let Some(intent) = queue.pop_front() {
+        let Some(index) = wallets
+            .iter()
+            .position(|wallet| wallet.token_ids.contains(&intent.token_id))
+        else {
+            tracing::warn!("No wallet trades {}, skipping {intent:?}", intent.token_id);
+            continue;
+        };
+
+        let Some(book) = latest_books.get(&intent.token_id) else {
+            tracing::warn!(
+                "No order book for {} yet, skipping {intent:?}",
+                intent.token_id
+            );
+            continue;
+        };
+
+        let wallet = &mut wallets[index];
+        let address = &wallet.account.proxy_address;
+
+        match executors[index]
+            .execute(&intent, book, &wallet.portfolio)
+            .await
+        {
+            Ok(Some(fill)) => {
+                tracing::info!(
+                    "{address} | [{}] {} | Filled {:.2} shares at {:.4} for {:.2} USDC.e (fee {:.4})",
+                    fill.side,
+                    fill.token_id,
+                    fill.size,
+                    fill.price,
+                    fill.notional,
+                    fill.fee
+                );
+
+                wallet.portfolio.apply_fill(&fill);
+                fills += 1;
+                queue.extend(strategy.on_fill(&combined(wallets).context(now_ms()), &fill));
+            }
+            Ok(None) => tracing::info!(
+                "{address} | [{}] {} | Order rests on the book",
+                intent.side,
+                intent.token_id
+            ),
+            Err(e) => tracing::warn!(
+                "{address} | [{}] {} | Order intent not executed: {e}",
+                intent.side,
+                intent.token_id
+            ),
+        }
+    }
+
+    fills
+}
@@ -0,0 +1,218 @@
+use crate::polymarket::api::clob::{
+    analytics::BookAnalytics,
+    constants::ROUNDING_CONFIG,
+    math::{calculate_fee, calculate_market_price, ClobPrecision},
+    order_builder::{get_market_buy_order_raw_amounts, get_order_raw_amounts},
+    schemas::OrderBookData,
+    typedefs::{Side, TickSize},
+};
+
+use super::{FillEvent, OrderIntent, Portfolio};
+
+pub struct SimulatedFill {
+    pub limit_price: f64,
+    pub fill: FillEvent,
+    pub partial: bool,
+}
+
+// Prices the intent like the live order flows do and takes the book up to that price, every
+// level at its own price. Whatever the book can't absorb at the signed price is dropped
+pub fn simulate_fill(
+    intent: &OrderIntent,
+    book: &OrderBookData,
+    portfolio: &Portfolio,
+    fee_rate_bps: f64,
+    tick_size: TickSize,
+) -> Result<SimulatedFill, String> {
+    let round_config = &ROUNDING_CONFIG[tick_size.as_str()];
+    let analytics = BookAnalytics::new(book.clone());
+
+    match intent.side {
+        Side::Buy => {
+            let limit_price = calculate_market_price(Side::Buy, book.clone(), intent.amount, None);
+            if limit_price == 0.0 {
+                return Err("not enough liquidity".to_string());
+            }
+
+            let raw_amounts =
+                get_market_buy_order_raw_amounts(intent.amount, limit_price, round_config);
+            let raw_price = limit_price.round_down(round_config.price);
+
+            let (mut notional, mut size) = (0.0, 0.0);
+            for level in analytics
+                .asks
+                .iter()
+                .take_while(|level| level.price.round_normal(round_config.price) <= raw_price)
+            {
+                let take = (raw_amounts.raw_maker_amount - notional).min(level.value);
+                notional += take;
+                size += take / level.price;
+
+                if notional >= raw_amounts.raw_maker_amount {
+                    break;
+                }
+            }
+
+            if size == 0.0 {
+                return Err("amount below rounding precision".to_string());
+            }
+
+            let price = notional / size;
+            let fee = calculate_fee(fee_rate_bps, price, size);
+
+            Ok(SimulatedFill {
+                limit_price: raw_price,
+                fill: FillEvent {
+                    token_id: intent.token_id.clone(),
+                    side: Side::Buy,
+                    price,
+                    size: size - fee / price,
+                    notional,
+                    fee,
+                },
+                partial: notional < raw_amounts.raw_maker_amount,
+            })
+        }
+        Side::Sell => {
+            let requested = intent.amount.min(portfolio.position(&intent.token_id));
+
+            let limit_price = calculate_market_price(Side::Sell, book.clone(), requested, None);
+            if limit_price == 0.0 {
+                return Err("not enough liquidity".to_string());
+            }
+
+            let raw_amounts =
+                get_order_raw_amounts(&Side::Sell, requested, limit_price, round_config);
+            let raw_price = limit_price.round_normal(round_config.price);
+
+            if raw_amounts.raw_maker_amount <= 0.0 {
+                return Err("size below rounding precision".to_string());
+            }
+
+            let (mut notional, mut size) = (0.0, 0.0);
+            for level in analytics
+                .bids
+                .iter()
+                .take_while(|level| level.price.round_normal(round_config.price) >= raw_price)
+            {
+                let take = (raw_amounts.raw_maker_amount - size).min(level.size);
+                size += take;
+                notional += take * level.price;
+
+                if size >= raw_amounts.raw_maker_amount {
+                    break;
+                }
+            }
+
+            if size == 0.0 {
+                return Err("not enough liquidity".to_string());
+            }
+
+            let price = notional / size;
+
+            Ok(SimulatedFill {
+                limit_price: raw_price,
+                fill: FillEvent {
+                    token_id: intent.token_id.clone(),
+                    side: Side::Sell,
+                    price,
+                    size,
+                    notional,
+                    fee: calculate_fee(fee_rate_bps, price, size),
+                },
+                partial: size < raw_amounts.raw_maker_amount,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::strategy::test_book;
+
+    use super::*;
+
+    fn tick_size() -> TickSize {
+        "0.01".parse().unwrap()
+    }
+
+    #[test]
+    fn buys_take_the_book_up_to_the_signed_price() {
+        // 0.556 rounds up past the 0.55 the order is signed at, only the first level matches
+        let book = test_book("0xmarket", "yes", &[], &[(0.5, 10.0), (0.556, 100.0)]);
+        let portfolio = Portfolio::new(100.0, HashMap::new());
+
+        let simulated = simulate_fill(
+            &OrderIntent::buy("yes", 10.0),
+            &book,
+            &portfolio,
+            0.0,
+            tick_size(),
+        )
+        .unwrap();
+
+        assert_eq!(simulated.limit_price, 0.55);
+        assert!(simulated.partial);
+        assert_eq!(simulated.fill.notional, 5.0);
+        assert_eq!(simulated.fill.price, 0.5);
+        assert_eq!(simulated.fill.size, 10.0);
+    }
+
+    #[test]
+    fn buy_fees_are_taken_from_the_shares_bought() {
+        let book = test_book("0xmarket", "yes", &[], &[(0.5, 100.0)]);
+        let portfolio = Portfolio::new(100.0, HashMap::new());
+
+        let simulated = simulate_fill(
+            &OrderIntent::buy("yes", 10.0),
+            &book,
+            &portfolio,
+            100.0,
+            tick_size(),
+        )
+        .unwrap();
+
+        // 1% of 0.5 on 20 shares is 0.1 USDC.e, paid with 0.2 shares
+        assert!(!simulated.partial);
+        assert_eq!(simulated.fill.notional, 10.0);
+        assert!((simulated.fill.fee - 0.1).abs() < 1e-9);
+        assert!((simulated.fill.size - 19.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sells_are_capped_at_the_position_and_pay_fees_in_usdc() {
+        let book = test_book("0xmarket", "yes", &[(0.6, 100.0)], &[]);
+        let portfolio = Portfolio::new(0.0, HashMap::from([("yes".to_string(), 10.0)]));
+
+        let simulated = simulate_fill(
+            &OrderIntent::sell("yes", 20.0),
+            &book,
+            &portfolio,
+            100.0,
+            tick_size(),
+        )
+        .unwrap();
+
+        assert!(!simulated.partial);
+        assert_eq!(simulated.fill.size, 10.0);
+        assert!((simulated.fill.notional - 6.0).abs() < 1e-9);
+        assert!((simulated.fill.fee - 0.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn books_too_thin_for_the_order_are_refused() {
+        let book = test_book("0xmarket", "yes", &[], &[(0.5, 10.0)]);
+        let portfolio = Portfolio::new(100.0, HashMap::new());
+
+        assert!(simulate_fill(
+            &OrderIntent::buy("yes", 10.0),
+            &book,
+            &portfolio,
+            0.0,
+            tick_size()
+        )
+        .is_err());
+    }
+}
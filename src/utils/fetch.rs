@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use reqwest::{header::HeaderMap, Client, Proxy};
 use serde::{de::DeserializeOwned, Serialize};
@@ -10,6 +10,8 @@ use reqwest::Method;
 
 use crate::errors::custom::CustomError;
 
+use super::metrics::METRICS;
+
 #[derive(Clone)]
 pub struct RequestParams<'a, S: Serialize> {
     pub url: &'a str,
@@ -53,7 +55,15 @@ pub async fn send_http_request<R: DeserializeOwned>(
         request = request.headers(headers.clone());
     }
 
-    let response = request.send().await.inspect_err(|e| {
+    let started_at = Instant::now();
+    let response = request.send().await;
+
+    let status_label = response.as_ref().map_or("error".to_string(), |response| {
+        response.status().as_u16().to_string()
+    });
+    METRICS.observe_http_request(request_params.url, &status_label, started_at.elapsed());
+
+    let response = response.inspect_err(|e| {
         tracing::error!(
             "Request failed: {}. Proxy: {:?}",
             e,
@@ -128,6 +138,7 @@ pub async fn send_http_request_with_retries<R: DeserializeOwned>(
                 if !should_retry(&e) {
                     return Err(e);
                 }
+                METRICS.record_http_retry(request_params.url);
                 tokio::time::sleep(retry_delay).await;
             }
         }
@@ -1,46 +1,80 @@
+use serde::Deserialize;
 use tracing_appender::{
     non_blocking::WorkerGuard,
     rolling::{RollingFileAppender, Rotation},
 };
-use tracing_subscriber::{
-    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer,
-};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+use crate::config::Config;
 
 const LOGS_FOLDER_PATH: &str = "data/logs";
 
-fn init_logger(logs_folder_path: &str) -> WorkerGuard {
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+// `RUST_LOG` takes precedence over the configured directives, e.g. `info,polymarket_eye::onchain=debug`
+fn build_filter(directives: &str) -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(directives))
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid log level directives `{directives}`, falling back to info: {e}");
+            EnvFilter::new("info")
+        })
+}
+
+fn init_logger(logs_folder_path: &str, format: LogFormat, directives: &str) -> WorkerGuard {
     let file_appender = RollingFileAppender::builder()
         .rotation(Rotation::HOURLY)
         .filename_prefix("app")
-        .filename_suffix("log")
+        .filename_suffix(match format {
+            LogFormat::Pretty => "log",
+            LogFormat::Json => "json",
+        })
         .build(logs_folder_path)
         .expect("Appender to build");
 
     let (writer, guard) = tracing_appender::non_blocking(file_appender);
 
-    let stdout_filter = LevelFilter::INFO;
-    let file_filter = LevelFilter::INFO;
-
-    let stdout_layer = fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_thread_ids(true)
-        .pretty() // comment this out if want to use the default format
-        .with_ansi(true)
-        .with_filter(stdout_filter);
-
-    let file_layer = fmt::layer()
-        .with_writer(writer)
-        .with_ansi(false)
-        .with_filter(file_filter);
+    // Span fields (`account`, `proxy_wallet`, `market`, `order_id`) are attached to every event logged inside them
+    let (stdout_layer, file_layer) = match format {
+        LogFormat::Pretty => (
+            fmt::layer()
+                .with_writer(std::io::stdout)
+                .with_thread_ids(true)
+                .pretty()
+                .with_ansi(true)
+                .boxed(),
+            fmt::layer().with_writer(writer).with_ansi(false).boxed(),
+        ),
+        LogFormat::Json => (
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(std::io::stdout)
+                .boxed(),
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(writer)
+                .boxed(),
+        ),
+    };
 
     tracing_subscriber::registry()
-        .with(stdout_layer)
-        .with(file_layer)
+        .with(stdout_layer.with_filter(build_filter(directives)))
+        .with(file_layer.with_filter(build_filter(directives)))
         .init();
 
     guard
 }
 
-pub fn init_default_logger() -> WorkerGuard {
-    init_logger(LOGS_FOLDER_PATH)
+pub fn init_default_logger(config: &Config) -> WorkerGuard {
+    init_logger(LOGS_FOLDER_PATH, config.log_format, &config.log_level)
 }
@@ -0,0 +1,233 @@
+use std::{net::SocketAddr, sync::LazyLock, time::Duration};
+
+use prometheus::{
+    exponential_buckets, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, Opts,
+    Registry, TextEncoder,
+};
+use reqwest::Url;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    pub orders: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub http_retries: IntCounterVec,
+    pub relayer_transaction_states: IntCounterVec,
+    pub wallet_balances: GaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("polymarket_eye".to_string()), None)
+            .expect("Metrics registry to build");
+
+        let orders = IntCounterVec::new(
+            Opts::new(
+                "orders_total",
+                "Orders sent to the CLOB by side, result (placed, filled, rejected) and reason",
+            ),
+            &["side", "result", "reason"],
+        )
+        .unwrap();
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Latency of HTTP requests by API host and response status",
+            )
+            .buckets(exponential_buckets(0.05, 2.0, 10).unwrap()),
+            &["host", "status"],
+        )
+        .unwrap();
+
+        let http_retries = IntCounterVec::new(
+            Opts::new("http_retries_total", "HTTP requests retried by API host"),
+            &["host"],
+        )
+        .unwrap();
+
+        let relayer_transaction_states = IntCounterVec::new(
+            Opts::new(
+                "relayer_transaction_states_total",
+                "Relayer transaction state transitions by transaction kind and state",
+            ),
+            &["kind", "state"],
+        )
+        .unwrap();
+
+        let wallet_balances = GaugeVec::new(
+            Opts::new(
+                "wallet_balance",
+                "Last balance read of a wallet, in whole token units",
+            ),
+            &["token", "address"],
+        )
+        .unwrap();
+
+        for collector in [
+            Box::new(orders.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(http_retries.clone()),
+            Box::new(relayer_transaction_states.clone()),
+            Box::new(wallet_balances.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("Metric names to be unique");
+        }
+
+        Self {
+            registry,
+            orders,
+            http_request_duration,
+            http_retries,
+            relayer_transaction_states,
+            wallet_balances,
+        }
+    }
+
+    pub fn observe_http_request(&self, url: &str, status: &str, latency: Duration) {
+        self.http_request_duration
+            .with_label_values(&[&host_of(url), status])
+            .observe(latency.as_secs_f64());
+    }
+
+    pub fn record_http_retry(&self, url: &str) {
+        self.http_retries.with_label_values(&[&host_of(url)]).inc();
+    }
+
+    pub fn record_order(&self, side: &str, result: &str, reason: &str) {
+        self.orders.with_label_values(&[side, result, reason]).inc();
+    }
+
+    pub fn record_relayer_state(&self, kind: &str, state: &str) {
+        self.relayer_transaction_states
+            .with_label_values(&[kind, state])
+            .inc();
+    }
+
+    pub fn set_wallet_balance(&self, token: &str, address: &str, balance: f64) {
+        self.wallet_balances
+            .with_label_values(&[token, address])
+            .set(balance);
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {e}");
+        }
+
+        buffer
+    }
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Minimal HTTP server answering `GET /metrics` in the Prometheus text format, e.g. `curl 127.0.0.1:9100/metrics`
+pub async fn serve_metrics(listen_address: &str) -> eyre::Result<SocketAddr> {
+    let listener = TcpListener::bind(listen_address).await?;
+    let local_address = listener.local_addr()?;
+
+    tracing::info!("Serving metrics on http://{local_address}/metrics");
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(async move {
+                        if let Err(e) = respond(stream).await {
+                            tracing::debug!("Metrics request failed: {e}");
+                        }
+                    });
+                }
+                Err(e) => tracing::warn!("Failed to accept a metrics connection: {e}"),
+            }
+        }
+    });
+
+    Ok(local_address)
+}
+
+async fn respond(mut stream: TcpStream) -> eyre::Result<()> {
+    let mut buffer = [0u8; 1024];
+    let read = stream.read(&mut buffer).await?;
+    let request = String::from_utf8_lossy(&buffer[..read]);
+
+    let (status, content_type, body) = match request.lines().next() {
+        Some(line) if line.starts_with("GET /metrics ") => (
+            "200 OK",
+            TextEncoder::new().format_type().to_string(),
+            METRICS.encode(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain".to_string(),
+            b"not found".to_vec(),
+        ),
+    };
+
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn metrics_are_served_in_the_prometheus_format() {
+        let address = serve_metrics("127.0.0.1:0").await.unwrap();
+
+        METRICS.record_order("BUY", "placed", "test");
+        METRICS.observe_http_request(
+            "https://clob.polymarket.com/order",
+            "200",
+            Duration::from_millis(120),
+        );
+
+        let response = get(address, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response
+            .contains(r#"polymarket_eye_orders_total{reason="test",result="placed",side="BUY"}"#));
+        assert!(response.contains(
+            r#"polymarket_eye_http_request_duration_seconds_count{host="clob.polymarket.com",status="200"}"#
+        ));
+
+        let response = get(address, "/").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(!response.contains("polymarket_eye_"));
+    }
+}
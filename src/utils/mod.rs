@@ -2,5 +2,6 @@ pub mod constants;
 pub mod fetch;
 pub mod files;
 pub mod logger;
+pub mod metrics;
 pub mod misc;
 pub mod poly;